@@ -1,25 +1,39 @@
 use crate::config::Config;
-use crate::error::Result;
+use crate::error::{GitSwitchError, Result};
+use crate::hooks::SwitchScope;
 use colored::*;
+use dialoguer::Confirm;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
+/// Once the history log reaches this size it is rotated to `<file>.1`
+const MAX_HISTORY_BYTES: u64 = 512 * 1024;
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct UsageStats {
     pub account_usage: HashMap<String, u32>,
     pub last_used: HashMap<String, String>, // ISO date string
     pub repository_count: HashMap<String, u32>,
+    /// When each account's key was last confirmed to work (`account touch --verified`)
+    #[serde(default)]
+    pub last_verified: HashMap<String, String>, // ISO date string
 }
 
 /// Get analytics file path
-fn get_analytics_file_path() -> Result<PathBuf> {
-    let home_dir =
-        home::home_dir().ok_or_else(|| crate::error::GitSwitchError::HomeDirectoryNotFound)?;
+pub fn get_analytics_file_path() -> Result<PathBuf> {
+    let home_dir = crate::utils::home_dir()?;
     Ok(home_dir.join(".git-switch-analytics.toml"))
 }
 
+/// Get switch history file path (one JSON event per line)
+pub fn get_history_file_path() -> Result<PathBuf> {
+    let home_dir = crate::utils::home_dir()?;
+    Ok(home_dir.join(".git-switch-history.jsonl"))
+}
+
 /// Load usage statistics
 pub fn load_stats() -> Result<UsageStats> {
     let path = get_analytics_file_path()?;
@@ -36,39 +50,59 @@ pub fn load_stats() -> Result<UsageStats> {
 pub fn save_stats(stats: &UsageStats) -> Result<()> {
     let path = get_analytics_file_path()?;
     let content = toml::to_string_pretty(stats).map_err(crate::error::GitSwitchError::TomlSer)?;
-    fs::write(&path, content)?;
-    Ok(())
+    crate::utils::write_file_atomic(&path, &content)
 }
 
-/// Record account usage
-pub fn record_usage(account_name: &str) -> Result<()> {
+/// Load, change and save the statistics while holding the analytics lock, so
+/// concurrent switches (e.g. scripted in parallel) never lose an increment
+fn update_stats<T>(update: impl FnOnce(&mut UsageStats) -> T) -> Result<T> {
+    let _lock = crate::utils::lock_file(&get_analytics_file_path()?)?;
     let mut stats = load_stats()?;
+    let result = update(&mut stats);
+    save_stats(&stats)?;
+    Ok(result)
+}
 
-    // Increment usage count
-    *stats
-        .account_usage
-        .entry(account_name.to_string())
-        .or_insert(0) += 1;
+/// Record account usage
+pub fn record_usage(account_name: &str) -> Result<()> {
+    update_stats(|stats| {
+        // Increment usage count
+        *stats
+            .account_usage
+            .entry(account_name.to_string())
+            .or_insert(0) += 1;
 
-    // Update last used timestamp
-    let now = chrono::Utc::now().to_rfc3339();
-    stats.last_used.insert(account_name.to_string(), now);
+        // Update last used timestamp
+        let now = chrono::Utc::now().to_rfc3339();
+        stats.last_used.insert(account_name.to_string(), now);
+    })
+}
 
-    save_stats(&stats)?;
-    Ok(())
+/// Stamp an account as used now without counting a switch; `verified` also
+/// stamps its verification time. Returns the timestamp written.
+pub fn touch_account(account_name: &str, verified: bool) -> Result<String> {
+    update_stats(|stats| {
+        let now = chrono::Utc::now().to_rfc3339();
+        stats
+            .last_used
+            .insert(account_name.to_string(), now.clone());
+        if verified {
+            stats
+                .last_verified
+                .insert(account_name.to_string(), now.clone());
+        }
+        now
+    })
 }
 
 /// Record repository usage for an account
 pub fn record_repository_usage(account_name: &str) -> Result<()> {
-    let mut stats = load_stats()?;
-
-    *stats
-        .repository_count
-        .entry(account_name.to_string())
-        .or_insert(0) += 1;
-
-    save_stats(&stats)?;
-    Ok(())
+    update_stats(|stats| {
+        *stats
+            .repository_count
+            .entry(account_name.to_string())
+            .or_insert(0) += 1;
+    })
 }
 
 /// Display usage analytics
@@ -127,17 +161,298 @@ pub fn show_analytics(config: &Config) -> Result<()> {
         }
     }
 
+    if !stats.last_verified.is_empty() {
+        println!("\n{}", "Last Verified:".bold());
+        let mut verified_vec: Vec<(&String, &String)> = stats.last_verified.iter().collect();
+        verified_vec.sort();
+
+        for (account_name, date) in verified_vec {
+            let date = chrono::DateTime::parse_from_rfc3339(date)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|_| "Unknown".to_string());
+            println!("  {} - {}", account_name.cyan(), date.dimmed());
+        }
+    }
+
     Ok(())
 }
 
+/// How `analytics clear` treats the analytics file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClearOptions {
+    /// Only report what would be cleared
+    pub dry_run: bool,
+    /// Copy the file aside before clearing it
+    pub backup: bool,
+    /// Clear without asking, even at a terminal
+    pub yes: bool,
+}
+
+/// "N accounts (M switches)" for the stats about to be cleared
+fn describe_stats(stats: &UsageStats) -> String {
+    let accounts: HashSet<&String> = stats
+        .account_usage
+        .keys()
+        .chain(stats.last_used.keys())
+        .chain(stats.repository_count.keys())
+        .chain(stats.last_verified.keys())
+        .collect();
+    let switches: u32 = stats.account_usage.values().sum();
+    format!(
+        "{} accounts ({} recorded switches)",
+        accounts.len(),
+        switches
+    )
+}
+
 /// Clear analytics data
-pub fn clear_analytics() -> Result<()> {
+pub fn clear_analytics(options: ClearOptions) -> Result<()> {
     let path = get_analytics_file_path()?;
-    if path.exists() {
-        fs::remove_file(&path)?;
-        println!("{} Analytics data cleared", "✓".green());
-    } else {
+    if !path.exists() {
         println!("{} No analytics data to clear", "ℹ".blue());
+        return Ok(());
     }
+
+    if options.dry_run {
+        println!(
+            "{} Would clear usage stats for {} from {}",
+            "ℹ".blue(),
+            describe_stats(&load_stats()?),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    // An unreadable file can still be cleared
+    let summary = load_stats()
+        .map(|stats| describe_stats(&stats))
+        .unwrap_or_else(|_| "unreadable analytics data".to_string());
+    if !options.yes
+        && io::stdin().is_terminal()
+        && !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt(format!("Clear usage stats for {}?", summary))
+            .default(false)
+            .interact()?
+    {
+        println!("Operation cancelled");
+        return Ok(());
+    }
+
+    if options.backup {
+        let backup_path = path.with_extension("toml.backup");
+        fs::copy(&path, &backup_path)?;
+        println!("Analytics data backed up to: {}", backup_path.display());
+    }
+    fs::remove_file(&path)?;
+    println!("{} Analytics data cleared: {}", "✓".green(), summary);
+    Ok(())
+}
+
+/// One identity switch in the history log
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SwitchEvent {
+    pub timestamp: String, // ISO date string
+    pub account: String,
+    pub scope: String,
+    /// Repository the account was applied to, for local switches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<PathBuf>,
+}
+
+fn rotated_history_path(path: &std::path::Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".1");
+    PathBuf::from(name)
+}
+
+/// Append a switch to the history log. A full log is first pruned to the
+/// retention window, and rotated to `<file>.1` (replacing the previous one) if
+/// that still leaves it full; each file is pruned on its own, never merged
+pub fn record_switch(account_name: &str, scope: SwitchScope, retention_days: u32) -> Result<()> {
+    let path = get_history_file_path()?;
+    let _lock = crate::utils::lock_file(&path)?;
+    if fs::metadata(&path).is_ok_and(|meta| meta.len() >= MAX_HISTORY_BYTES) {
+        prune_history_file(&path, retention_days, true)?;
+        if fs::metadata(&path).is_ok_and(|meta| meta.len() >= MAX_HISTORY_BYTES) {
+            fs::rename(&path, rotated_history_path(&path))?;
+        } else {
+            prune_history_file(&rotated_history_path(&path), retention_days, true)?;
+        }
+    }
+
+    let repo = match scope {
+        SwitchScope::Global => None,
+        SwitchScope::Local => std::env::current_dir()
+            .ok()
+            .and_then(|dir| crate::git::repository_root_of(&dir)),
+    };
+    let event = SwitchEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        account: account_name.to_string(),
+        scope: scope.as_str().to_string(),
+        repo,
+    };
+    let line = serde_json::to_string(&event).map_err(GitSwitchError::Json)?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Split events into those inside the retention window and the number older
+/// than it; events with an unreadable timestamp are kept
+fn split_expired(events: Vec<SwitchEvent>, retention_days: u32) -> (Vec<SwitchEvent>, usize) {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(retention_days));
+    let total = events.len();
+    let kept: Vec<SwitchEvent> = events
+        .into_iter()
+        .filter(|event| {
+            chrono::DateTime::parse_from_rfc3339(&event.timestamp)
+                .map_or(true, |timestamp| timestamp >= cutoff)
+        })
+        .collect();
+    let expired = total - kept.len();
+    (kept, expired)
+}
+
+/// Drop the expired events of one history file in place (removing it once
+/// nothing is left), returning how many events it keeps and how many expired;
+/// only counts unless `apply` is set
+fn prune_history_file(
+    file: &std::path::Path,
+    retention_days: u32,
+    apply: bool,
+) -> Result<(usize, usize)> {
+    if !file.exists() {
+        return Ok((0, 0));
+    }
+    let (kept, expired) = split_expired(read_history_file(file)?, retention_days);
+    if apply && expired > 0 && kept.is_empty() {
+        fs::remove_file(file)?;
+    } else if apply && expired > 0 {
+        let mut content = String::new();
+        for event in &kept {
+            content.push_str(&serde_json::to_string(event).map_err(GitSwitchError::Json)?);
+            content.push('\n');
+        }
+        crate::utils::write_file_atomic(file, &content)?;
+    }
+    Ok((kept.len(), expired))
+}
+
+/// Drop switch events older than the retention window; only reports what
+/// would be removed unless `apply` is set
+pub fn prune_history(retention_days: u32, apply: bool) -> Result<()> {
+    let path = get_history_file_path()?;
+    let _lock = crate::utils::lock_file(&path)?;
+    let mut kept = 0;
+    let mut expired = 0;
+    for file in [rotated_history_path(&path), path] {
+        let (file_kept, file_expired) = prune_history_file(&file, retention_days, apply)?;
+        kept += file_kept;
+        expired += file_expired;
+    }
+
+    if expired == 0 {
+        println!(
+            "{} No switches older than {} days",
+            "✓".green().bold(),
+            retention_days
+        );
+        return Ok(());
+    }
+    if !apply {
+        println!(
+            "{} {} switches older than {} days would be removed ({} kept)",
+            "ℹ".blue(),
+            expired,
+            retention_days,
+            kept
+        );
+        println!(
+            "{} Run {} to remove them",
+            "ℹ".blue(),
+            "git-switch history prune --apply".cyan()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} Removed {} switches older than {} days ({} kept)",
+        "✓".green().bold(),
+        expired,
+        retention_days,
+        kept
+    );
+    Ok(())
+}
+
+/// Read the events of one history file, skipping unreadable lines
+fn read_history_file(file: &std::path::Path) -> Result<Vec<SwitchEvent>> {
+    let mut events = Vec::new();
+    if !file.exists() {
+        return Ok(events);
+    }
+    let content = fs::read_to_string(file)?;
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(event) => events.push(event),
+            Err(e) => tracing::warn!(
+                "Skipping unreadable history entry in {}: {}",
+                file.display(),
+                e
+            ),
+        }
+    }
+    Ok(events)
+}
+
+/// Load switch events, oldest first, including the rotated log
+pub fn load_history() -> Result<Vec<SwitchEvent>> {
+    let path = get_history_file_path()?;
+    let mut events = read_history_file(&rotated_history_path(&path))?;
+    events.extend(read_history_file(&path)?);
+    Ok(events)
+}
+
+/// Display the most recent switches, newest first
+pub fn show_history(account: Option<&str>, limit: usize, json: bool) -> Result<()> {
+    let events: Vec<SwitchEvent> = load_history()?
+        .into_iter()
+        .rev()
+        .filter(|event| account.is_none_or(|name| event.account == name))
+        .take(limit)
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&events).map_err(GitSwitchError::Json)?
+        );
+        return Ok(());
+    }
+
+    println!("{}", "Switch History".bold().cyan());
+    println!("{}", "─".repeat(35));
+
+    if events.is_empty() {
+        println!("{} No switches recorded yet", "ℹ".blue());
+        return Ok(());
+    }
+
+    for event in &events {
+        let when = chrono::DateTime::parse_from_rfc3339(&event.timestamp)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| event.timestamp.clone());
+        let target = match &event.repo {
+            Some(repo) => format!("{} {}", event.scope, repo.display()),
+            None => event.scope.clone(),
+        };
+        println!("  {} {} ({})", when.dimmed(), event.account.cyan(), target);
+    }
+
     Ok(())
 }
@@ -1,58 +1,377 @@
-use crate::config::{Config, get_config_file_path, load_config, save_config};
+use crate::commands::{with_suffix, write_key_file};
+use crate::config::{
+    Account, Config, get_config_file_path, load_config, save_config, warn_if_migration_pending,
+};
+use crate::encryption;
 use crate::error::{GitSwitchError, Result};
-use crate::utils::{ensure_parent_dir_exists, read_file_content, write_file_content};
+use crate::utils::{
+    self, ensure_parent_dir_exists, read_document, read_file_content, write_file_content,
+};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
 
-/// Backup the current configuration to an encrypted file
-pub fn backup_config(backup_path: Option<&Path>) -> Result<PathBuf> {
+const BACKUPS_DIR_NAME: &str = ".git-switch-backups";
+const BACKUP_FILE_PREFIX: &str = "git-switch-backup-";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+const ENCRYPTED_BACKUP_EXTENSION: &str = "enc";
+
+/// Environment variable holding the backup passphrase, used instead of prompting
+pub const BACKUP_PASSPHRASE_ENV: &str = "GIT_SWITCH_BACKUP_PASSPHRASE";
+
+/// A timestamped backup in the default backups directory
+#[derive(Debug, Clone)]
+pub struct BackupEntry {
+    pub path: PathBuf,
+    pub created_at: chrono::NaiveDateTime,
+    /// Number of accounts in the backup, or None if it could not be parsed
+    pub account_count: Option<usize>,
+    /// Encrypted backups are not opened when listing
+    pub encrypted: bool,
+}
+
+/// How `backup create` writes a backup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BackupOptions {
+    /// Keep only the newest N default backups
+    pub keep: Option<usize>,
+    /// Encrypt the backup with a passphrase
+    pub encrypt: bool,
+    /// Bundle every account's key pair; only allowed with `encrypt`
+    pub include_keys: bool,
+}
+
+/// An account's key pair carried inside an encrypted backup
+#[derive(Serialize, Deserialize)]
+struct BundledKey {
+    account: String,
+    private_key: String,
+    public_key: Option<String>,
+}
+
+impl Drop for BundledKey {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+/// What an encrypted backup holds once decrypted
+#[derive(Serialize, Deserialize)]
+struct EncryptedBackup {
+    config: Config,
+    #[serde(default)]
+    keys: Vec<BundledKey>,
+}
+
+/// Get the directory holding timestamped default backups
+pub fn get_backups_dir() -> Result<PathBuf> {
+    let config_path = get_config_file_path()?;
+    let config_dir = config_path
+        .parent()
+        .ok_or_else(|| GitSwitchError::Other("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join(BACKUPS_DIR_NAME))
+}
+
+/// Backup the current configuration, optionally encrypted and with key material
+///
+/// Without an explicit path the backup gets a timestamped name in the backups
+/// directory, and `keep` prunes all but the newest `keep` backups there.
+/// Private keys only ever go into an encrypted backup.
+pub fn backup_config(backup_path: Option<&Path>, options: BackupOptions) -> Result<PathBuf> {
+    if options.include_keys && !options.encrypt {
+        return Err(GitSwitchError::BackupFailed {
+            message: "--include-keys requires --encrypt; private keys are never written to an unencrypted backup".to_string(),
+        });
+    }
     let config = load_config()?;
 
     let backup_file_path = if let Some(path) = backup_path {
         path.to_path_buf()
     } else {
-        // Default backup location
-        let config_path = get_config_file_path()?;
-        let config_dir = config_path.parent().ok_or_else(|| {
-            GitSwitchError::Other("Could not determine config directory".to_string())
-        })?;
-        config_dir.join("git-switch-backup.toml")
+        let timestamp = chrono::Local::now().format(BACKUP_TIMESTAMP_FORMAT);
+        let extension = if options.encrypt {
+            ENCRYPTED_BACKUP_EXTENSION
+        } else {
+            "toml"
+        };
+        get_backups_dir()?.join(format!("{}{}.{}", BACKUP_FILE_PREFIX, timestamp, extension))
     };
 
     ensure_parent_dir_exists(&backup_file_path)?;
 
-    // Serialize to TOML format for better readability
-    let toml_content = toml::to_string_pretty(&config).map_err(GitSwitchError::TomlSer)?;
+    if options.encrypt {
+        let keys = if options.include_keys {
+            bundle_keys(&config)?
+        } else {
+            Vec::new()
+        };
+        let key_count = keys.len();
+        let plaintext = Zeroizing::new(
+            toml::to_string_pretty(&EncryptedBackup { config, keys })
+                .map_err(GitSwitchError::TomlSer)?,
+        );
+        let passphrase = backup_passphrase(true)?;
+        let content = encryption::encrypt(plaintext.as_bytes(), &passphrase)?;
+        write_key_file(&backup_file_path, content.as_bytes(), 0o600)?;
 
-    write_file_content(&backup_file_path, &toml_content)?;
+        println!(
+            "Configuration backed up (encrypted) to: {}",
+            backup_file_path.display()
+        );
+        if options.include_keys {
+            println!(
+                "{} The backup includes {} key pair(s); anyone with the file and passphrase can use them",
+                "⚠".yellow(),
+                key_count
+            );
+        }
+    } else {
+        // Serialize to TOML format for better readability
+        let toml_content = toml::to_string_pretty(&config).map_err(GitSwitchError::TomlSer)?;
+        write_file_content(&backup_file_path, &toml_content)?;
+        println!("Configuration backed up to: {}", backup_file_path.display());
+    }
+
+    if let Some(keep) = options.keep {
+        prune_backups(keep)?;
+    }
 
-    println!("Configuration backed up to: {}", backup_file_path.display());
     Ok(backup_file_path)
 }
 
+/// Passphrase for an encrypted backup, from the environment or a prompt
+fn backup_passphrase(confirm: bool) -> Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var(BACKUP_PASSPHRASE_ENV)
+        && !passphrase.is_empty()
+    {
+        return Ok(Zeroizing::new(passphrase));
+    }
+
+    let theme = dialoguer::theme::ColorfulTheme::default();
+    let mut prompt = dialoguer::Password::with_theme(&theme).with_prompt("Backup passphrase");
+    if confirm {
+        prompt = prompt.with_confirmation("Repeat passphrase", "Passphrases do not match");
+    }
+    let passphrase = Zeroizing::new(prompt.interact()?);
+    if passphrase.is_empty() {
+        return Err(GitSwitchError::BackupFailed {
+            message: "The backup passphrase must not be empty".to_string(),
+        });
+    }
+    Ok(passphrase)
+}
+
+/// Read every account's key pair; accounts without a key file are skipped
+fn bundle_keys(config: &Config) -> Result<Vec<BundledKey>> {
+    let mut names: Vec<&String> = config.accounts.keys().collect();
+    names.sort();
+
+    let mut keys = Vec::new();
+    for name in names {
+        let account = &config.accounts[name];
+        if account.ssh_key_path.is_empty() {
+            continue;
+        }
+        let key_path = utils::expand_path(&account.ssh_key_path)?;
+        if !key_path.exists() {
+            println!(
+                "{} Skipping key for '{}': {} not found",
+                "⚠".yellow(),
+                name,
+                key_path.display()
+            );
+            continue;
+        }
+        keys.push(BundledKey {
+            account: name.clone(),
+            private_key: read_file_content(&key_path)?,
+            public_key: read_file_content(&with_suffix(&key_path, ".pub")).ok(),
+        });
+    }
+    Ok(keys)
+}
+
+/// Write bundled keys to the paths their accounts are configured with.
+///
+/// An existing file with the same content is left alone; one with different
+/// content is kept and reported rather than overwritten.
+fn restore_keys(config: &Config, keys: &[BundledKey]) -> Result<()> {
+    for key in keys {
+        let key_path = utils::expand_path(&config.accounts[&key.account].ssh_key_path)?;
+        let mut files = vec![(key_path.clone(), key.private_key.as_str(), 0o600)];
+        if let Some(public_key) = &key.public_key {
+            files.push((with_suffix(&key_path, ".pub"), public_key.as_str(), 0o644));
+        }
+
+        for (path, content, mode) in files {
+            if path.exists() {
+                if read_file_content(&path)? != content {
+                    println!(
+                        "{} Kept existing {}; it differs from the key in the backup",
+                        "⚠".yellow(),
+                        path.display()
+                    );
+                }
+                continue;
+            }
+            write_key_file(&path, content.as_bytes(), mode)?;
+        }
+        println!(
+            "🔑 Restored key for '{}' to {}",
+            key.account.cyan(),
+            key_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// List timestamped backups in the backups directory, newest first
+pub fn find_backups() -> Result<Vec<BackupEntry>> {
+    let backups_dir = get_backups_dir()?;
+    if !backups_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&backups_dir)?.flatten() {
+        let path = entry.path();
+        let Some(stem) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix(BACKUP_FILE_PREFIX))
+        else {
+            continue;
+        };
+        let (timestamp, encrypted) = match stem.strip_suffix(".toml") {
+            Some(timestamp) => (timestamp, false),
+            None => match stem
+                .strip_suffix(ENCRYPTED_BACKUP_EXTENSION)
+                .and_then(|t| t.strip_suffix('.'))
+            {
+                Some(timestamp) => (timestamp, true),
+                None => continue,
+            },
+        };
+        let Ok(created_at) =
+            chrono::NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT)
+        else {
+            continue;
+        };
+
+        let account_count = read_document(&path)
+            .ok()
+            .filter(|_| !encrypted)
+            .and_then(|content| toml::from_str::<Config>(&content).ok())
+            .map(|config| config.accounts.len());
+
+        backups.push(BackupEntry {
+            path,
+            created_at,
+            account_count,
+            encrypted,
+        });
+    }
+
+    backups.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+    Ok(backups)
+}
+
+/// Get the most recent timestamped backup
+pub fn latest_backup() -> Result<PathBuf> {
+    find_backups()?
+        .into_iter()
+        .next()
+        .map(|entry| entry.path)
+        .ok_or_else(|| GitSwitchError::RestoreFailed {
+            message: "No backups found. Create one with 'git-switch backup create'".to_string(),
+        })
+}
+
+/// Remove all but the newest `keep` timestamped backups
+fn prune_backups(keep: usize) -> Result<()> {
+    for entry in find_backups()?.into_iter().skip(keep) {
+        fs::remove_file(&entry.path)?;
+        println!("Pruned old backup: {}", entry.path.display());
+    }
+    Ok(())
+}
+
+/// Display available timestamped backups
+pub fn list_backups() -> Result<()> {
+    let backups = find_backups()?;
+
+    if backups.is_empty() {
+        println!("{} No backups found", "ℹ".blue());
+        println!(
+            "Create a backup with: {}",
+            "git-switch backup create".cyan()
+        );
+        return Ok(());
+    }
+
+    println!("{}", "Available Backups:".bold().underline());
+    println!();
+
+    for entry in &backups {
+        let name = entry
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let accounts = match entry.account_count {
+            _ if entry.encrypted => "encrypted".yellow().to_string(),
+            Some(1) => "1 account".to_string(),
+            Some(count) => format!("{} accounts", count),
+            None => "unreadable".red().to_string(),
+        };
+
+        println!(
+            "  {} {} ({})",
+            name.cyan(),
+            entry
+                .created_at
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .dimmed(),
+            accounts
+        );
+    }
+
+    Ok(())
+}
+
 /// Restore configuration from a backup file
-pub fn restore_config(backup_path: &Path) -> Result<()> {
+pub fn restore_config(backup_path: &Path, preview: bool) -> Result<()> {
     if !backup_path.exists() {
         return Err(GitSwitchError::BackupFailed {
             message: format!("Backup file not found: {}", backup_path.display()),
         });
     }
 
-    let backup_content = read_file_content(backup_path)?;
+    // Nothing below touches the live config until the backup has passed every check
+    let (config, keys) = verify_backup(backup_path).map_err(|e| GitSwitchError::RestoreFailed {
+        message: format!(
+            "Backup {} failed the integrity check, current configuration left untouched: {}",
+            backup_path.display(),
+            e
+        ),
+    })?;
 
-    // Try to parse as TOML first, fallback to JSON for backwards compatibility
-    let config: Config = if backup_path.extension().and_then(|s| s.to_str()) == Some("toml") {
-        toml::from_str(&backup_content).map_err(|e| GitSwitchError::RestoreFailed {
-            message: format!("Failed to parse TOML backup: {}", e),
-        })?
-    } else {
-        serde_json::from_str(&backup_content).map_err(|e| GitSwitchError::RestoreFailed {
-            message: format!("Failed to parse JSON backup: {}", e),
-        })?
-    };
-
-    // Validate the restored configuration
-    validate_config(&config)?;
+    if preview {
+        let changes = diff_accounts(&load_config()?, &config, true);
+        print_account_changes(&changes);
+        for key in &keys {
+            println!(
+                "🔑 Would restore the key for '{}' to {}",
+                key.account.cyan(),
+                config.accounts[&key.account].ssh_key_path
+            );
+        }
+        return Ok(());
+    }
 
     // Create a backup of current config before restoring
     let current_config_path = get_config_file_path()?;
@@ -67,11 +386,311 @@ pub fn restore_config(backup_path: &Path) -> Result<()> {
 
     save_config(&config)?;
     println!("Configuration restored from: {}", backup_path.display());
+    restore_keys(&config, &keys)?;
+    warn_if_migration_pending(&config);
+    Ok(())
+}
+
+/// Check that a backup file loads and passes validation, without restoring it
+pub fn verify_backup_file(backup_path: &Path) -> Result<()> {
+    if !backup_path.exists() {
+        return Err(GitSwitchError::BackupFailed {
+            message: format!("Backup file not found: {}", backup_path.display()),
+        });
+    }
+
+    let (config, keys) = verify_backup(backup_path).map_err(|e| GitSwitchError::BackupFailed {
+        message: format!(
+            "Backup {} failed verification: {}",
+            backup_path.display(),
+            e
+        ),
+    })?;
+
+    let format = if encryption::is_encrypted(&read_document(backup_path)?) {
+        "encrypted"
+    } else if backup_path.extension().and_then(|s| s.to_str()) == Some("toml") {
+        "TOML"
+    } else {
+        "JSON"
+    };
+    println!(
+        "{} Backup {} is valid ({})",
+        "✓".green().bold(),
+        backup_path.display().to_string().cyan(),
+        format
+    );
+    println!("👥 Accounts: {}", config.accounts.len());
+    if format == "encrypted" {
+        println!("🔑 Bundled keys: {}", keys.len());
+    }
+
+    // Not fatal: the backup may come from another machine
+    let mut names: Vec<&String> = config.accounts.keys().collect();
+    names.sort();
+    for name in names {
+        let account = &config.accounts[name];
+        if account.ssh_key_path.is_empty() || keys.iter().any(|key| &key.account == name) {
+            continue;
+        }
+        if !utils::expand_path(&account.ssh_key_path)?.exists() {
+            println!(
+                "{} Account '{}' uses {}, which does not exist on this machine",
+                "⚠".yellow(),
+                name,
+                account.ssh_key_path
+            );
+        }
+    }
     Ok(())
 }
 
+/// Config versions a backup may carry; older ones are restored as they are and
+/// reported as needing `git-switch migrate --apply`
+const RESTORABLE_VERSIONS: [&str; 2] = ["1.0", "2.0"];
+
+/// Parse a backup fully and check that it is complete and consistent.
+///
+/// Returns the configuration and any key pairs bundled with it.
+fn verify_backup(backup_path: &Path) -> Result<(Config, Vec<BundledKey>)> {
+    let backup_content = read_document(backup_path)?;
+    let corrupted = |message: String| GitSwitchError::CorruptedConfig { message };
+
+    // Encrypted backups carry the config (and maybe keys) in a TOML payload.
+    // Otherwise try TOML first, falling back to JSON for backwards compatibility.
+    // The version field defaults when deserialized, so it is read from the raw document.
+    let mut keys = Vec::new();
+    let (version, config): (Option<String>, Config) = if encryption::is_encrypted(&backup_content) {
+        let passphrase = backup_passphrase(false)?;
+        let plaintext = encryption::decrypt(&backup_content, &passphrase)?;
+        let plaintext = std::str::from_utf8(&plaintext)
+            .map_err(|_| corrupted("decrypted backup is not valid UTF-8".to_string()))?;
+        let raw: toml::Value = toml::from_str(plaintext)
+            .map_err(|e| corrupted(format!("failed to parse decrypted backup: {}", e)))?;
+        let version = raw
+            .get("config")
+            .and_then(|c| c.get("version"))
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let mut payload: EncryptedBackup = raw
+            .try_into()
+            .map_err(|e| corrupted(format!("failed to read decrypted backup: {}", e)))?;
+        keys = std::mem::take(&mut payload.keys);
+        (version, payload.config)
+    } else if backup_path.extension().and_then(|s| s.to_str()) == Some("toml") {
+        let raw: toml::Value = toml::from_str(&backup_content)
+            .map_err(|e| corrupted(utils::toml_parse_error(backup_path, &backup_content, &e)))?;
+        let version = raw
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let config = raw
+            .try_into()
+            .map_err(|e| corrupted(format!("failed to read TOML backup: {}", e)))?;
+        (version, config)
+    } else {
+        let raw: serde_json::Value = serde_json::from_str(&backup_content)
+            .map_err(|e| corrupted(utils::json_parse_error(backup_path, &backup_content, &e)))?;
+        let version = raw
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let config = serde_json::from_value(raw)
+            .map_err(|e| corrupted(format!("failed to read JSON backup: {}", e)))?;
+        (version, config)
+    };
+
+    // Hand-written exports often omit the version and are read as current;
+    // a version that is present must be one this release understands
+    if let Some(version) = version.as_deref()
+        && !RESTORABLE_VERSIONS.contains(&version)
+    {
+        return Err(corrupted(format!(
+            "unsupported config version '{}' (supported: {})",
+            version,
+            RESTORABLE_VERSIONS.join(", ")
+        )));
+    }
+
+    for (name, account) in &config.accounts {
+        if &account.name != name {
+            return Err(corrupted(format!(
+                "account entry '{}' is named '{}'",
+                name, account.name
+            )));
+        }
+        crate::validation::validate_account_name(name)?;
+        crate::validation::validate_username(&account.git_name)?;
+        if let Some(branch) = &account.default_branch {
+            crate::validation::validate_branch_name(branch)?;
+        }
+    }
+    for key in &keys {
+        if config
+            .accounts
+            .get(&key.account)
+            .is_none_or(|account| account.ssh_key_path.is_empty())
+        {
+            return Err(corrupted(format!(
+                "bundled key for '{}' has no account with a key path",
+                key.account
+            )));
+        }
+    }
+    validate_config(&config)?;
+
+    Ok((config, keys))
+}
+
+/// A change to a single account between two configurations
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountChange {
+    Added(String),
+    Removed(String),
+    Modified {
+        name: String,
+        /// (field, old value, new value)
+        fields: Vec<(&'static str, String, String)>,
+    },
+}
+
+/// Flatten the comparable fields of an account
+fn account_fields(account: &Account) -> Vec<(&'static str, String)> {
+    vec![
+        ("git_name", account.git_name.clone()),
+        ("login", account.login.clone()),
+        ("email", account.email.clone()),
+        ("additional_emails", account.additional_emails.join(", ")),
+        (
+            "email_domain",
+            account.email_domain.clone().unwrap_or_default(),
+        ),
+        ("ssh_key_path", account.ssh_key_path.clone()),
+        ("provider", account.provider.clone().unwrap_or_default()),
+        ("host", account.host.clone().unwrap_or_default()),
+        (
+            "additional_ssh_keys",
+            account.additional_ssh_keys.join(", "),
+        ),
+        ("groups", account.groups.join(", ")),
+        ("url_patterns", account.url_patterns.join(", ")),
+        (
+            "ssh_add_lifetime",
+            account
+                .ssh_add_lifetime
+                .map(|seconds| seconds.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "passphrase_ref",
+            account.passphrase_ref.clone().unwrap_or_default(),
+        ),
+        (
+            "strict_ssh",
+            account
+                .strict_ssh
+                .map(|strict| strict.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "default_branch",
+            account.default_branch.clone().unwrap_or_default(),
+        ),
+        (
+            "commit_template",
+            account.commit_template.clone().unwrap_or_default(),
+        ),
+        ("hooks_path", account.hooks_path.clone().unwrap_or_default()),
+        ("git_settings", {
+            let mut settings: Vec<String> = account
+                .git_settings
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            settings.sort();
+            settings.join(", ")
+        }),
+    ]
+}
+
+/// Compute account-level changes from `current` to `incoming`, sorted by account name
+pub fn diff_accounts(
+    current: &Config,
+    incoming: &Config,
+    include_removals: bool,
+) -> Vec<AccountChange> {
+    let mut changes = Vec::new();
+
+    for (name, new_account) in &incoming.accounts {
+        match current.accounts.get(name) {
+            None => changes.push(AccountChange::Added(name.clone())),
+            Some(old_account) => {
+                let fields: Vec<_> = account_fields(old_account)
+                    .into_iter()
+                    .zip(account_fields(new_account))
+                    .filter(|((_, old), (_, new))| old != new)
+                    .map(|((field, old), (_, new))| (field, old, new))
+                    .collect();
+                if !fields.is_empty() {
+                    changes.push(AccountChange::Modified {
+                        name: name.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+
+    if include_removals {
+        for name in current.accounts.keys() {
+            if !incoming.accounts.contains_key(name) {
+                changes.push(AccountChange::Removed(name.clone()));
+            }
+        }
+    }
+
+    changes.sort_by(|a, b| change_name(a).cmp(change_name(b)));
+    changes
+}
+
+fn change_name(change: &AccountChange) -> &str {
+    match change {
+        AccountChange::Added(name) | AccountChange::Removed(name) => name,
+        AccountChange::Modified { name, .. } => name,
+    }
+}
+
+/// Print a colored account diff for preview mode
+fn print_account_changes(changes: &[AccountChange]) {
+    println!("{}", "Preview of account changes:".bold().underline());
+
+    if changes.is_empty() {
+        println!("  {}", "No changes".dimmed());
+    }
+
+    for change in changes {
+        match change {
+            AccountChange::Added(name) => println!("  {} {}", "+".green().bold(), name.green()),
+            AccountChange::Removed(name) => println!("  {} {}", "-".red().bold(), name.red()),
+            AccountChange::Modified { name, fields } => {
+                println!("  {} {}", "~".yellow().bold(), name.yellow());
+                for (field, old, new) in fields {
+                    println!(
+                        "      {}: {} → {}",
+                        field,
+                        old.as_str().red(),
+                        new.as_str().green()
+                    );
+                }
+            }
+        }
+    }
+
+    println!("\n{} Preview only, nothing was written", "ℹ".blue());
+}
+
 /// Validate configuration data
-fn validate_config(config: &Config) -> Result<()> {
+pub fn validate_config(config: &Config) -> Result<()> {
     for (name, account) in &config.accounts {
         if name.is_empty() {
             return Err(GitSwitchError::CorruptedConfig {
@@ -91,10 +710,101 @@ fn validate_config(config: &Config) -> Result<()> {
                 email: account.email.clone(),
             });
         }
+        if let Some(host) = &account.host {
+            crate::validation::validate_ssh_host(host)?;
+        }
+        for (key, value) in &account.git_settings {
+            crate::validation::validate_git_setting(key, value)?;
+        }
+        for (label, path) in [
+            ("commit template", &account.commit_template),
+            ("hooks path", &account.hooks_path),
+        ] {
+            if let Some(path) = path {
+                crate::validation::validate_path_setting(label, path)?;
+            }
+        }
+        if let Some(email) = account
+            .additional_emails
+            .iter()
+            .find(|email| !email_address::EmailAddress::is_valid(email))
+        {
+            return Err(GitSwitchError::InvalidEmail {
+                email: email.clone(),
+            });
+        }
+    }
+
+    let collisions = find_account_collisions(config);
+    if !collisions.is_empty() {
+        return Err(GitSwitchError::CorruptedConfig {
+            message: format!(
+                "Conflicting accounts found:\n  - {}",
+                collisions.join("\n  - ")
+            ),
+        });
     }
     Ok(())
 }
 
+/// Find values claimed by more than one account.
+///
+/// Names that only differ in case (or map to the same SSH host alias) and shared
+/// URL patterns are reported as collisions; a shared SSH key is only a warning.
+fn find_account_collisions(config: &Config) -> Vec<String> {
+    let mut names: Vec<&String> = config.accounts.keys().collect();
+    names.sort();
+
+    let mut host_aliases: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut url_patterns: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut key_paths: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for name in names {
+        let account = &config.accounts[name];
+        host_aliases
+            .entry(crate::ssh::host_alias(&account.slug()))
+            .or_default()
+            .push(name);
+        for pattern in &account.url_patterns {
+            let claimed = url_patterns.entry(pattern.to_lowercase()).or_default();
+            if !claimed.contains(&name.as_str()) {
+                claimed.push(name);
+            }
+        }
+        if !account.ssh_key_path.is_empty() {
+            key_paths
+                .entry(account.ssh_key_path.as_str())
+                .or_default()
+                .push(name);
+        }
+    }
+
+    for (key_path, owners) in key_paths.iter().filter(|(_, owners)| owners.len() > 1) {
+        println!(
+            "{} SSH key '{}' is shared by accounts: {}",
+            "⚠".yellow(),
+            key_path,
+            owners.join(", ")
+        );
+    }
+
+    let mut collisions = Vec::new();
+    for (alias, owners) in host_aliases.iter().filter(|(_, owners)| owners.len() > 1) {
+        collisions.push(format!(
+            "accounts {} share the SSH host alias '{}'",
+            owners.join(", "),
+            alias
+        ));
+    }
+    for (pattern, owners) in url_patterns.iter().filter(|(_, owners)| owners.len() > 1) {
+        collisions.push(format!(
+            "accounts {} share the URL pattern '{}'",
+            owners.join(", "),
+            pattern
+        ));
+    }
+    collisions
+}
+
 /// Export accounts to a portable format
 pub fn export_accounts(export_path: &Path, format: ExportFormat) -> Result<()> {
     let config = load_config()?;
@@ -113,8 +823,90 @@ pub fn export_accounts(export_path: &Path, format: ExportFormat) -> Result<()> {
     Ok(())
 }
 
+/// Options for `backup import`
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Merge with existing accounts instead of replacing them
+    pub merge: bool,
+    /// Show the account changes without importing
+    pub preview: bool,
+    /// Prepended to every imported account name
+    pub prefix: Option<String>,
+    /// Appended to every imported account name
+    pub suffix: Option<String>,
+    /// TOML file mapping imported account names to new names (`old = "new"`)
+    pub rename_map: Option<PathBuf>,
+}
+
+impl ImportOptions {
+    fn renames(&self) -> bool {
+        self.prefix.is_some() || self.suffix.is_some() || self.rename_map.is_some()
+    }
+}
+
+/// Read a rename map: a TOML table of `old = "new"` account names
+fn load_rename_map(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content = read_document(path)?;
+    toml::from_str(&content).map_err(|e| {
+        GitSwitchError::Other(format!(
+            "Failed to parse rename map {}",
+            utils::toml_parse_error(path, &content, &e)
+        ))
+    })
+}
+
+/// Rename imported accounts by the rename map, or else by adding the prefix/suffix to
+/// every name, so imports can sit next to existing accounts
+fn rename_imported_accounts(import_config: &mut Config, options: &ImportOptions) -> Result<()> {
+    let rename_map = options
+        .rename_map
+        .as_deref()
+        .map(load_rename_map)
+        .transpose()?;
+    if let Some(map) = &rename_map {
+        let unknown: Vec<&str> = map
+            .keys()
+            .filter(|name| !import_config.accounts.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(GitSwitchError::Other(format!(
+                "Rename map names accounts that are not in the import: {}",
+                unknown.join(", ")
+            )));
+        }
+    }
+
+    let prefix = options.prefix.as_deref().unwrap_or_default();
+    let suffix = options.suffix.as_deref().unwrap_or_default();
+    let mut names = HashSet::new();
+    import_config.accounts = std::mem::take(&mut import_config.accounts)
+        .into_iter()
+        .map(|(name, mut account)| {
+            let renamed = match &rename_map {
+                Some(map) => map.get(&name).cloned().unwrap_or_else(|| name.clone()),
+                None => format!("{}{}{}", prefix, name, suffix),
+            };
+            crate::validation::validate_account_name(&renamed)?;
+            if !names.insert(renamed.clone()) {
+                return Err(GitSwitchError::Other(format!(
+                    "More than one imported account would be named '{}'",
+                    renamed
+                )));
+            }
+            if renamed != name {
+                account.name = renamed.clone();
+                // The slug follows the new name so the copy gets its own host alias
+                account.slug = None;
+            }
+            Ok((renamed, account))
+        })
+        .collect::<Result<_>>()?;
+    Ok(())
+}
+
 /// Import accounts from a file
-pub fn import_accounts(import_path: &Path, merge: bool) -> Result<()> {
+pub fn import_accounts(import_path: &Path, options: &ImportOptions) -> Result<()> {
     if !import_path.exists() {
         return Err(GitSwitchError::Other(format!(
             "Import file not found: {}",
@@ -122,21 +914,59 @@ pub fn import_accounts(import_path: &Path, merge: bool) -> Result<()> {
         )));
     }
 
-    let import_content = read_file_content(import_path)?;
+    let import_content = read_document(import_path)?;
     let import_config: Config = if import_path.extension().and_then(|s| s.to_str()) == Some("toml")
     {
-        toml::from_str(&import_content)
-            .map_err(|e| GitSwitchError::Other(format!("Failed to parse TOML import: {}", e)))?
+        toml::from_str(&import_content).map_err(|e| {
+            GitSwitchError::Other(format!(
+                "Failed to parse TOML import {}",
+                utils::toml_parse_error(import_path, &import_content, &e)
+            ))
+        })?
     } else {
-        serde_json::from_str(&import_content)
-            .map_err(|e| GitSwitchError::Other(format!("Failed to parse JSON import: {}", e)))?
+        serde_json::from_str(&import_content).map_err(|e| {
+            GitSwitchError::Other(format!(
+                "Failed to parse JSON import {}",
+                utils::json_parse_error(import_path, &import_content, &e)
+            ))
+        })?
     };
 
+    let mut import_config = import_config;
+    if options.renames() {
+        rename_imported_accounts(&mut import_config, options)?;
+    }
     validate_config(&import_config)?;
 
     let mut current_config = load_config()?;
+    // Renamed accounts are added alongside the existing ones, never over them
+    let merge = options.merge || options.renames();
 
-    if merge {
+    if options.preview {
+        // A merge never removes accounts; conflicts are shown as if overwritten
+        let changes = diff_accounts(&current_config, &import_config, !merge);
+        print_account_changes(&changes);
+        return Ok(());
+    }
+
+    if options.renames() {
+        if let Some(existing) = import_config
+            .accounts
+            .keys()
+            .filter(|name| current_config.accounts.contains_key(*name))
+            .min()
+        {
+            return Err(GitSwitchError::AccountExists {
+                name: existing.clone(),
+            });
+        }
+        for account in import_config.accounts.values() {
+            if !account.ssh_key_path.is_empty() {
+                crate::ssh::update_ssh_config(account, current_config.settings.ssh_include_mode)?;
+            }
+        }
+        current_config.accounts.extend(import_config.accounts);
+    } else if merge {
         // Merge accounts, asking for confirmation on conflicts
         for (name, account) in import_config.accounts {
             if current_config.accounts.contains_key(&name) {
@@ -1,16 +1,25 @@
 use crate::analytics;
-use crate::config::{self, Account, Config};
+use crate::config::{self, Account, Config, SettingKey};
+use crate::detection;
 use crate::error::{GitSwitchError, Result};
 use crate::git;
+use crate::hooks;
+use crate::profiles;
+use crate::repository;
+use crate::secrets;
 use crate::ssh;
+use crate::state;
+use crate::templates;
 use crate::utils;
 use crate::validation;
 use colored::*;
 use dialoguer::{Confirm, Input, Select};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
 
 /// Detect provider from email domain
 fn detect_provider_from_email(email: &str) -> Option<String> {
@@ -25,85 +34,314 @@ fn detect_provider_from_email(email: &str) -> Option<String> {
     }
 }
 
-/// Add account with enhanced validation and progress indicators
-pub fn add_account(
+/// Options shared by single and bulk account creation
+#[derive(Debug, Clone, Default)]
+pub struct AddAccountOptions {
+    /// Create the account without an SSH key
+    pub no_key: bool,
+    /// Replace an existing account with the same name
+    pub force: bool,
+    /// Refuse an email another account already uses instead of warning
+    pub strict: bool,
+    /// Provider handle used to detect the account from remote URLs (the Git name when unset)
+    pub login: Option<String>,
+    /// Copy the public key to the clipboard after creation
+    pub copy_key: bool,
+    /// Open the provider's key upload page in the browser after creation
+    pub open_key_page: bool,
+    /// Branch set as `init.defaultBranch` when the account is used globally
+    pub default_branch: Option<String>,
+    /// File name prefix of a generated key (`id_rsa` when unset), e.g. a template's key name
+    pub key_name: Option<String>,
+    /// File set as `commit.template` when the account is applied to a repository
+    pub commit_template: Option<String>,
+    /// Directory set as `core.hooksPath` when the account is applied to a repository
+    pub hooks_path: Option<String>,
+    /// Allowlisted Git settings (`push.default`, ...) applied with the identity
+    pub git_settings: Vec<(String, String)>,
+}
+
+/// Validate and store a new account, generating its SSH key if needed.
+///
+/// This is the quiet core of `add`; callers are responsible for user-facing output.
+fn create_account(
     config: &mut Config,
     name: &str,
     username: &str,
     email: &str,
-    ssh_key_path_opt: Option<PathBuf>,
+    ssh_key_path_opt: Option<&PathBuf>,
     provider: Option<String>,
+    options: &AddAccountOptions,
 ) -> Result<()> {
     // Validate inputs
     validation::validate_account_name(name)?;
     validation::validate_username(username)?;
     validation::validate_email(email)?;
+    if let Some(login) = &options.login {
+        validation::validate_login(login)?;
+    }
+    if let Some(branch) = &options.default_branch {
+        validation::validate_branch_name(branch)?;
+    }
+    if let Some(path) = &options.commit_template {
+        validation::validate_path_setting("commit template", path)?;
+    }
+    if let Some(path) = &options.hooks_path {
+        validation::validate_path_setting("hooks path", path)?;
+    }
+    let git_settings = options
+        .git_settings
+        .iter()
+        .map(|(key, value)| {
+            validation::validate_git_setting(key, value)
+                .map(|key| (key.to_string(), value.trim().to_string()))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
 
-    if config.accounts.contains_key(name) {
+    if config.accounts.contains_key(name) && !options.force {
         return Err(GitSwitchError::AccountExists {
             name: name.to_string(),
         });
     }
+    check_shared_email(config, name, email, options.strict)?;
 
-    let ssh_key_path_str = if let Some(custom_path) = ssh_key_path_opt.as_ref() {
+    let generates_key = !options.no_key && ssh_key_path_opt.is_none();
+    let key_name = options.key_name.as_deref().unwrap_or(DEFAULT_KEY_NAME);
+    let slug = unique_account_slug(config, name, generates_key.then_some(key_name))?;
+    let ssh_key_path_str = if options.no_key {
+        String::new()
+    } else if let Some(custom_path) = ssh_key_path_opt {
         custom_path
             .to_str()
             .ok_or_else(|| GitSwitchError::InvalidPath(custom_path.clone()))?
             .to_string()
     } else {
-        format!("~/.ssh/id_rsa_{}", name.replace(" ", "_").to_lowercase())
+        default_key_path(key_name, &slug)
     };
 
-    let expanded_key_path = utils::expand_path(&ssh_key_path_str)?;
-    utils::ensure_parent_dir_exists(&expanded_key_path)?;
+    // Undo every step below unless creation completes
+    let mut rollback = AddAccountRollback::new(config, name);
 
-    // Clean progress indicator for key generation
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
+    if !options.no_key {
+        let expanded_key_path = utils::expand_path(&ssh_key_path_str)?;
+        utils::ensure_parent_dir_exists(&expanded_key_path)?;
 
-    if ssh_key_path_opt.is_none() && !expanded_key_path.exists() {
-        pb.set_message("🔐 Generating SSH key pair...");
-        pb.enable_steady_tick(std::time::Duration::from_millis(80));
-        ssh::generate_ssh_key(&expanded_key_path)?;
-        pb.finish_and_clear();
-    } else if ssh_key_path_opt.is_some() && !expanded_key_path.exists() {
-        return Err(GitSwitchError::SshKeyGeneration {
-            message: format!(
-                "Specified SSH key path does not exist: {}",
-                expanded_key_path.display()
-            ),
-        });
-    } else if expanded_key_path.exists() {
-        // Validate existing SSH key
-        validation::validate_ssh_key(&expanded_key_path)?;
+        // Clean progress indicator for key generation
+        let pb = utils::progress_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg}")
+                .unwrap(),
+        );
+
+        if ssh_key_path_opt.is_none() && !expanded_key_path.exists() {
+            pb.set_message("🔐 Generating SSH key pair...");
+            pb.enable_steady_tick(std::time::Duration::from_millis(80));
+            rollback.generated_key = Some(expanded_key_path.clone());
+            ssh::generate_ssh_key(&expanded_key_path)?;
+            pb.finish_and_clear();
+        } else if ssh_key_path_opt.is_some() && !expanded_key_path.exists() {
+            return Err(GitSwitchError::SshKeyGeneration {
+                message: format!(
+                    "Specified SSH key path does not exist: {}",
+                    expanded_key_path.display()
+                ),
+            });
+        } else if expanded_key_path.exists() {
+            // Validate existing SSH key
+            validation::validate_ssh_key(&expanded_key_path)?;
+        }
     }
 
     let account = Account {
         name: name.to_string(),
-        username: username.to_string(),
+        git_name: username.to_string(),
+        // A display name such as "John Doe" is not a provider handle
+        login: options.login.clone().unwrap_or_else(|| {
+            validation::validate_login(username)
+                .map(|_| username.to_string())
+                .unwrap_or_default()
+        }),
         email: email.to_string(),
         ssh_key_path: ssh_key_path_str.clone(),
         additional_ssh_keys: Vec::new(),
-        provider: provider.or_else(|| detect_provider_from_email(email)),
+        provider: provider
+            .or_else(|| detect_provider_from_email(email))
+            .or_else(|| rollback.config.settings.default_provider.clone()),
         groups: Vec::new(),
+        url_patterns: Vec::new(),
+        ssh_add_lifetime: None,
+        passphrase_ref: None,
+        strict_ssh: None,
+        additional_emails: Vec::new(),
+        email_domain: None,
+        slug: Some(slug),
+        host: None,
+        default_branch: options.default_branch.clone(),
+        commit_template: options.commit_template.clone(),
+        hooks_path: options.hooks_path.clone(),
+        git_settings,
     };
 
-    config.accounts.insert(name.to_string(), account);
-    config::save_config(config)?;
+    rollback.config.accounts.insert(name.to_string(), account);
+    rollback.config_changed = true;
+    config::save_config(rollback.config)?;
 
     // Update SSH config silently
-    ssh::update_ssh_config(name, &ssh_key_path_str)?;
+    if !options.no_key {
+        ssh::update_ssh_config(
+            &rollback.config.accounts[name],
+            rollback.config.settings.ssh_include_mode,
+        )?;
+    }
+
+    rollback.commit();
+    Ok(())
+}
+
+/// Guard that undoes a partially created account when dropped without `commit`.
+///
+/// Restores the previous config entry (or removes the new one) and deletes a key
+/// pair generated during this attempt, so a failed `add` can be retried cleanly.
+struct AddAccountRollback<'a> {
+    config: &'a mut Config,
+    name: String,
+    previous: Option<Account>,
+    generated_key: Option<PathBuf>,
+    config_changed: bool,
+    committed: bool,
+}
+
+impl<'a> AddAccountRollback<'a> {
+    fn new(config: &'a mut Config, name: &str) -> Self {
+        let previous = config.accounts.get(name).cloned();
+        Self {
+            config,
+            name: name.to_string(),
+            previous,
+            generated_key: None,
+            config_changed: false,
+            committed: false,
+        }
+    }
+
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for AddAccountRollback<'_> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+
+        if self.config_changed {
+            match self.previous.take() {
+                Some(previous) => {
+                    self.config.accounts.insert(self.name.clone(), previous);
+                }
+                None => {
+                    self.config.accounts.remove(&self.name);
+                }
+            }
+            if let Err(e) = config::save_config(self.config) {
+                tracing::warn!("Failed to roll back account '{}': {}", self.name, e);
+            }
+        }
+
+        if let Some(key_path) = &self.generated_key {
+            let _ = std::fs::remove_file(key_path);
+            let _ = std::fs::remove_file(with_suffix(key_path, ".pub"));
+        }
+    }
+}
+
+/// Other accounts whose primary or additional emails include `email`, sorted
+fn accounts_sharing_email(config: &Config, name: &str, email: &str) -> Vec<String> {
+    let mut owners: Vec<String> = config
+        .accounts
+        .values()
+        .filter(|account| account.name != name && account.matches_email(email))
+        .map(|account| account.name.clone())
+        .collect();
+    owners.sort();
+    owners
+}
+
+/// Warn when a new account's email already belongs to another account, since
+/// email-based detection can then no longer tell them apart. `strict` turns
+/// the warning into an error; at a terminal the user confirms instead
+fn check_shared_email(config: &Config, name: &str, email: &str, strict: bool) -> Result<()> {
+    let owners = accounts_sharing_email(config, name, email);
+    if owners.is_empty() {
+        return Ok(());
+    }
+    let owners = owners
+        .iter()
+        .map(|owner| format!("'{}'", owner))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if strict {
+        return Err(GitSwitchError::Other(format!(
+            "Email {} is already used by account {}",
+            email, owners
+        )));
+    }
+
+    println!(
+        "{} Email {} is already used by account {}; repositories committing with it will match both accounts",
+        "⚠".yellow(),
+        email,
+        owners
+    );
+    if io::stdin().is_terminal()
+        && !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt("Create another account with this email?")
+            .default(false)
+            .interact()?
+    {
+        return Err(GitSwitchError::Other(
+            "Account creation cancelled".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Add account with enhanced validation and progress indicators
+pub fn add_account(
+    config: &mut Config,
+    name: &str,
+    username: &str,
+    email: &str,
+    ssh_key_path_opt: Option<PathBuf>,
+    provider: Option<String>,
+    options: AddAccountOptions,
+) -> Result<()> {
+    create_account(
+        config,
+        name,
+        username,
+        email,
+        ssh_key_path_opt.as_ref(),
+        provider,
+        &options,
+    )?;
+    let expanded_key_path = utils::expand_path(&config.accounts[name].ssh_key_path)?;
 
     // Beautiful success message
     println!("\n{}", "🎉 Account Created Successfully!".bold().green());
     println!("{}", "─".repeat(40).bright_black());
 
     println!("📧 {} {}", "Account:".bold(), name.cyan().bold());
-    println!("👤 {} {}", "Username:".bold(), username.bright_white());
+    println!("👤 {} {}", "Git name:".bold(), username.bright_white());
+    if config.accounts[name].login != username {
+        println!(
+            "🪪 {} {}",
+            "Login:".bold(),
+            config.accounts[name].login.bright_white()
+        );
+    }
     println!("✉️  {} {}", "Email:".bold(), email.bright_white());
 
     if let Some(provider) = &config.accounts[name].provider {
@@ -121,8 +359,11 @@ pub fn add_account(
         );
     }
 
-    if ssh_key_path_opt.is_none() {
+    if options.no_key {
+        println!("🔑 {} None", "SSH Key:".bold());
+    } else if ssh_key_path_opt.is_none() {
         println!("🔑 {} Generated and configured", "SSH Key:".bold());
+        print_fingerprint(&expanded_key_path);
 
         // Display formatted public key
         println!("\n{}", "📋 Your Public Key".bold().yellow());
@@ -130,56 +371,26 @@ pub fn add_account(
         if let Ok(()) = ssh::display_public_key_formatted(&expanded_key_path) {
             // Provider-specific instructions
             if let Some(provider) = &config.accounts[name].provider {
-                match provider.as_str() {
-                    "github" => {
-                        println!(
-                            "\n{} {} Copy the key above and add it to GitHub:",
-                            "🚀".bold(),
-                            "Next Steps:".bold().bright_yellow()
-                        );
-                        println!(
-                            "   {}",
-                            "https://github.com/settings/keys".bright_blue().underline()
-                        );
-                    }
-                    "gitlab" => {
-                        println!(
-                            "\n{} {} Copy the key above and add it to GitLab:",
-                            "🚀".bold(),
-                            "Next Steps:".bold().bright_yellow()
-                        );
-                        println!(
-                            "   {}",
-                            "https://gitlab.com/-/profile/keys"
-                                .bright_blue()
-                                .underline()
-                        );
-                    }
-                    "bitbucket" => {
-                        println!(
-                            "\n{} {} Copy the key above and add it to Bitbucket:",
-                            "🚀".bold(),
-                            "Next Steps:".bold().bright_yellow()
-                        );
-                        println!(
-                            "   {}",
-                            "https://bitbucket.org/account/settings/ssh-keys/"
-                                .bright_blue()
-                                .underline()
-                        );
-                    }
-                    _ => {
-                        println!(
-                            "\n{} {} Copy the key above and add it to your Git provider",
-                            "🚀".bold(),
-                            "Next Steps:".bold().bright_yellow()
-                        );
-                    }
-                }
+                print_key_upload_instructions(provider, name);
             }
         }
     } else {
         println!("🔑 {} Using existing key", "SSH Key:".bold());
+        print_fingerprint(&expanded_key_path);
+    }
+
+    if options.copy_key && !options.no_key {
+        println!();
+        copy_public_key(&expanded_key_path)?;
+    }
+    if options.open_key_page && !options.no_key {
+        match &config.accounts[name].provider {
+            Some(provider) => open_key_upload_page(provider, name),
+            None => println!(
+                "{} No provider set, so there is no key upload page to open",
+                "ℹ".blue()
+            ),
+        }
     }
 
     println!(
@@ -193,41 +404,247 @@ pub fn add_account(
     Ok(())
 }
 
-/// Interactive account creation
-pub fn add_account_interactive(config: &mut Config, suggested_name: &str) -> Result<()> {
-    println!("{}", "Interactive Account Setup".bold().cyan());
-    println!("Let's create a new Git account configuration.\n");
+/// `template use --interactive`: prompt for the account's name, username and
+/// email, then create it from the template
+pub fn add_account_from_template_interactive(
+    config: &mut Config,
+    template: &str,
+    ssh_key_path_opt: Option<PathBuf>,
+    no_key: bool,
+) -> Result<()> {
+    let tmpl = templates::get_template(template)?;
+    println!(
+        "{}",
+        format!("Interactive Account Setup ({} template)", template)
+            .bold()
+            .cyan()
+    );
+    let key = if no_key {
+        "none".to_string()
+    } else if let Some(path) = &ssh_key_path_opt {
+        path.display().to_string()
+    } else {
+        format!("new {} key", tmpl.default_ssh_key_name)
+    };
+    println!("Provider: {}, SSH key: {}\n", tmpl.provider.cyan(), key);
 
-    let name: String = Input::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("Account name")
-        .default(suggested_name.to_string())
-        .interact_text()?;
+    let (name, username, email) = prompt_account_identity(config, template)?;
+    add_account_from_template(
+        config,
+        template,
+        &name,
+        &username,
+        &email,
+        ssh_key_path_opt,
+        no_key,
+    )
+}
 
-    validation::validate_account_name(&name)?;
+/// Create an account from a provider template, exactly like `add` with the
+/// template's provider and key name
+pub fn add_account_from_template(
+    config: &mut Config,
+    template: &str,
+    name: &str,
+    username: &str,
+    email: &str,
+    ssh_key_path_opt: Option<PathBuf>,
+    no_key: bool,
+) -> Result<()> {
+    let tmpl = templates::get_template(template)?;
+    add_account(
+        config,
+        name,
+        username,
+        email,
+        ssh_key_path_opt,
+        Some(tmpl.provider.clone()),
+        AddAccountOptions {
+            no_key,
+            key_name: Some(tmpl.default_ssh_key_name.clone()),
+            ..AddAccountOptions::default()
+        },
+    )?;
+    println!(
+        "{} Account '{}' created from {} template",
+        "✓".green().bold(),
+        name.cyan(),
+        template.cyan()
+    );
+    Ok(())
+}
 
-    if config.accounts.contains_key(&name) {
-        return Err(GitSwitchError::AccountExists { name });
+/// Copy an account's public key to the clipboard, printing it when no clipboard is available
+fn copy_public_key(key_path: &std::path::Path) -> Result<()> {
+    let public_key = ssh::read_public_key(key_path)?;
+    match utils::copy_to_clipboard(&public_key) {
+        Ok(true) => println!("📋 {} Public key copied to clipboard", "✓".green().bold()),
+        Ok(false) => {
+            println!(
+                "{} Public key copied, but the clipboard is cleared when git-switch exits unless a clipboard manager is running; it is also printed below:",
+                "⚠".yellow()
+            );
+            println!("{}", public_key);
+        }
+        Err(e) => {
+            println!(
+                "{} Clipboard unavailable ({}); copy the public key below manually:",
+                "⚠".yellow(),
+                e
+            );
+            println!("{}", public_key);
+        }
     }
+    Ok(())
+}
 
-    let username: String = Input::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("Username")
-        .interact_text()?;
+/// Point the user at the provider page where the new public key must be uploaded
+fn print_key_upload_instructions(provider: &str, account_name: &str) {
+    let label = match provider {
+        "github" => Some("GitHub"),
+        "gitlab" => Some("GitLab"),
+        "bitbucket" => Some("Bitbucket"),
+        "azure" => Some("Azure DevOps"),
+        _ => None,
+    };
+    match (label, templates::key_upload_link(provider, account_name)) {
+        (Some(label), Some(link)) => {
+            println!(
+                "\n{} {} Copy the key above and add it to {}:",
+                "🚀".bold(),
+                "Next Steps:".bold().bright_yellow(),
+                label
+            );
+            println!("   {}", link.bright_blue().underline());
+        }
+        _ => {
+            println!(
+                "\n{} {} Copy the key above and add it to your Git provider",
+                "🚀".bold(),
+                "Next Steps:".bold().bright_yellow()
+            );
+        }
+    }
+}
 
-    let email: String = Input::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("Email address")
-        .validate_with(|input: &String| -> Result<(), &str> {
-            if validation::validate_email(input).is_ok() {
-                Ok(())
-            } else {
-                Err("Please enter a valid email address")
+/// Open the provider's new-key page in the browser, saying why when it can't
+fn open_key_upload_page(provider: &str, account_name: &str) {
+    let Some(link) = templates::key_upload_link(provider, account_name) else {
+        println!(
+            "{} No key upload page is known for provider '{}'",
+            "ℹ".blue(),
+            provider
+        );
+        return;
+    };
+    match utils::open_url(&link) {
+        Ok(()) => println!("🌐 Opened {} in your browser", link.bright_blue()),
+        Err(e) => println!(
+            "{} Could not open a browser ({}); open this link to add the key: {}",
+            "⚠".yellow(),
+            e,
+            link
+        ),
+    }
+}
+
+/// Print the key fingerprint line of the add summary, if it can be computed
+fn print_fingerprint(key_path: &std::path::Path) {
+    if let Some(fingerprint) = ssh::get_key_fingerprint(key_path) {
+        println!(
+            "🧬 {} {}",
+            "Fingerprint:".bold(),
+            fingerprint.bright_white()
+        );
+    }
+}
+
+/// Ask for one value: a dialoguer prompt at a terminal, otherwise a line read
+/// from stdin so the flow can be scripted. Invalid answers are asked again
+fn prompt_text(
+    prompt: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<()>,
+) -> Result<String> {
+    if io::stdin().is_terminal() {
+        let theme = dialoguer::theme::ColorfulTheme::default();
+        let mut input = Input::<String>::with_theme(&theme).with_prompt(prompt);
+        if let Some(default) = default {
+            input = input.default(default.to_string());
+        }
+        return Ok(input
+            .validate_with(|value: &String| validate(value).map_err(|e| e.to_string()))
+            .interact_text()?);
+    }
+
+    loop {
+        match default {
+            Some(default) => print!("{} [{}]: ", prompt, default),
+            None => print!("{}: ", prompt),
+        }
+        io::Write::flush(&mut io::stdout())?;
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Err(GitSwitchError::Other(format!(
+                "Input ended before '{}' was answered",
+                prompt
+            )));
+        }
+        println!();
+        let value = match (line.trim(), default) {
+            ("", Some(default)) => default.to_string(),
+            (value, _) => value.to_string(),
+        };
+        match validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(e) => println!("{} {}", "✗".red().bold(), e),
+        }
+    }
+}
+
+/// Prompt for a new account's name, Git name and email, validating each
+fn prompt_account_identity(
+    config: &Config,
+    suggested_name: &str,
+) -> Result<(String, String, String)> {
+    let name = prompt_text(
+        "Account name",
+        Some(suggested_name).filter(|name| !name.is_empty()),
+        |name| {
+            validation::validate_account_name(name)?;
+            if config.accounts.contains_key(name) {
+                return Err(GitSwitchError::AccountExists {
+                    name: name.to_string(),
+                });
             }
-        })
-        .interact_text()?;
+            Ok(())
+        },
+    )?;
+    let username = prompt_text("Git name (user.name)", None, validation::validate_username)?;
+    let email = prompt_text("Email address", None, validation::validate_email)?;
+    Ok((name, username, email))
+}
+
+/// Interactive account creation
+pub fn add_account_interactive(config: &mut Config, suggested_name: &str) -> Result<()> {
+    println!("{}", "Interactive Account Setup".bold().cyan());
+    println!("Let's create a new Git account configuration.\n");
+
+    let (name, username, email) = prompt_account_identity(config, suggested_name)?;
 
     let providers = vec!["github", "gitlab", "bitbucket", "other"];
+    // Pre-select the configured default provider, falling back to "other" for
+    // providers that are not in the picker so add_account can still apply it
+    let default_selection = match config.settings.default_provider.as_deref() {
+        Some(default) => providers
+            .iter()
+            .position(|p| *p == default)
+            .unwrap_or(providers.len() - 1),
+        None => 0,
+    };
     let provider_selection = Select::with_theme(&dialoguer::theme::ColorfulTheme::default())
         .with_prompt("Select Git provider")
-        .default(0)
+        .default(default_selection)
         .items(&providers)
         .interact()?;
 
@@ -251,481 +668,3744 @@ pub fn add_account_interactive(config: &mut Config, suggested_name: &str) -> Res
         None
     };
 
-    add_account(config, &name, &username, &email, ssh_key_path, provider)
+    add_account(
+        config,
+        &name,
+        &username,
+        &email,
+        ssh_key_path,
+        provider,
+        AddAccountOptions::default(),
+    )
 }
 
-/// List accounts with optional detailed view
-pub fn list_accounts(config: &Config, detailed: bool) -> Result<()> {
-    if config.accounts.is_empty() {
-        println!(
-            "\n{} {}",
-            "📭".yellow(),
-            "No Git accounts configured yet".bold()
-        );
-        println!("{}", "──────────────────────────────────".bright_black());
-        println!("Get started by adding your first account:");
-        println!(
-            "{} {}",
-            "💡".bold(),
-            "git-switch add <name> <username> <email>".bright_cyan()
-        );
-        println!(
-            "{} {}",
-            "📖".bold(),
-            "git-switch add --help".bright_white().dimmed()
-        );
+/// An account definition in a bulk `add --stdin` payload
+#[derive(Debug, Deserialize)]
+struct AccountSpec {
+    name: String,
+    username: String,
+    email: String,
+    #[serde(default)]
+    login: Option<String>,
+    #[serde(default)]
+    ssh_key_path: Option<PathBuf>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    default_branch: Option<String>,
+    #[serde(default)]
+    commit_template: Option<String>,
+    #[serde(default)]
+    hooks_path: Option<String>,
+    #[serde(default)]
+    git_settings: HashMap<String, String>,
+}
+
+/// Wrapper for payloads of the form `{ accounts = [...] }`
+#[derive(Debug, Deserialize)]
+struct AccountSpecList {
+    accounts: Vec<AccountSpec>,
+}
+
+/// Parse a bulk payload: a JSON array, or a JSON/TOML document with an `accounts` list
+fn parse_account_specs(payload: &str) -> Result<Vec<AccountSpec>> {
+    let trimmed = payload.trim_start();
+    if trimmed.starts_with('[') && !trimmed.starts_with("[[") {
+        return serde_json::from_str(trimmed).map_err(GitSwitchError::Json);
+    }
+    if trimmed.starts_with('{') {
+        let list: AccountSpecList = serde_json::from_str(trimmed).map_err(GitSwitchError::Json)?;
+        return Ok(list.accounts);
+    }
+    let list: AccountSpecList = toml::from_str(payload).map_err(GitSwitchError::Toml)?;
+    Ok(list.accounts)
+}
+
+/// Create several accounts from a TOML/JSON payload read from stdin
+pub fn add_accounts_from_stdin(config: &mut Config, options: AddAccountOptions) -> Result<()> {
+    let mut payload = String::new();
+    io::stdin().read_to_string(&mut payload)?;
+    let specs = parse_account_specs(&payload)?;
+
+    if specs.is_empty() {
+        println!("{} No account definitions found on stdin", "ℹ".blue());
         return Ok(());
     }
 
-    let account_count = config.accounts.len();
-    let plural = if account_count == 1 {
-        "Account"
-    } else {
-        "Accounts"
-    };
+    let mut failed = 0;
+    for spec in &specs {
+        match create_account(
+            config,
+            &spec.name,
+            &spec.username,
+            &spec.email,
+            spec.ssh_key_path.as_ref(),
+            spec.provider.clone(),
+            &AddAccountOptions {
+                login: spec.login.clone(),
+                default_branch: spec
+                    .default_branch
+                    .clone()
+                    .or_else(|| options.default_branch.clone()),
+                commit_template: spec
+                    .commit_template
+                    .clone()
+                    .or_else(|| options.commit_template.clone()),
+                hooks_path: spec
+                    .hooks_path
+                    .clone()
+                    .or_else(|| options.hooks_path.clone()),
+                git_settings: options
+                    .git_settings
+                    .iter()
+                    .cloned()
+                    .chain(spec.git_settings.clone())
+                    .collect(),
+                ..options.clone()
+            },
+        ) {
+            Ok(()) => println!("{} {}", "✓".green().bold(), spec.name.cyan()),
+            Err(e) => {
+                failed += 1;
+                println!("{} {} ({})", "✗".red().bold(), spec.name.cyan(), e);
+            }
+        }
+    }
 
     println!(
-        "\n{} {} {} {}",
-        "📚".bold(),
-        account_count.to_string().bright_yellow().bold(),
-        plural.bold(),
-        "Configured".bold()
+        "\n{} created, {} failed",
+        (specs.len() - failed).to_string().green(),
+        failed.to_string().red()
     );
-    println!("{}", "═".repeat(50).bright_black());
 
-    if detailed {
-        for (i, (name, account)) in config.accounts.iter().enumerate() {
-            if i > 0 {
-                println!(); // Add spacing between accounts
-            }
+    if failed > 0 {
+        return Err(GitSwitchError::Other(format!(
+            "{} of {} accounts could not be created",
+            failed,
+            specs.len()
+        )));
+    }
+    Ok(())
+}
 
-            // Get provider emoji and info
-            let (provider_emoji, provider_name) = match account.provider.as_deref() {
-                Some("github") => ("🐙", "GitHub"),
-                Some("gitlab") => ("🦊", "GitLab"),
-                Some("bitbucket") => ("🪣", "Bitbucket"),
-                Some(other) => ("�", other),
-                None => ("❓", "Unknown"),
-            };
+/// Find the account whose email matches a Git identity
+pub fn account_for_email<'a>(config: &'a Config, email: &str) -> Option<&'a Account> {
+    config
+        .accounts
+        .values()
+        .find(|acc| acc.matches_email(email))
+}
 
-            // Check if SSH key exists
-            let ssh_key_status =
-                if let Ok(expanded_path) = utils::expand_path(&account.ssh_key_path) {
-                    if expanded_path.exists() {
-                        ("✅", "Found".green())
-                    } else {
-                        ("❌", "Missing".red())
-                    }
-                } else {
-                    ("⚠️", "Invalid Path".yellow())
-                };
+/// Names of the accounts applied to the current repository and globally
+fn active_account_names(config: &Config) -> (Option<String>, Option<String>) {
+    let local = if git::is_in_git_repository().unwrap_or(false) {
+        git::get_local_config()
+            .ok()
+            .and_then(|(_, email)| account_for_email(config, &email))
+            .map(|account| account.name.clone())
+    } else {
+        None
+    };
+    let global = git::get_global_config()
+        .ok()
+        .and_then(|(_, email)| account_for_email(config, &email))
+        .map(|account| account.name.clone());
+    (local, global)
+}
 
-            println!(
-                "╭─ {} {} {}",
-                "📋".bold(),
-                name.bright_cyan().bold(),
-                format!("({})", provider_name).bright_black()
-            );
-            println!("│");
-            println!(
-                "├─ {} {} {}",
-                "👤".bold(),
-                "Username:".bold(),
-                account.username.bright_white()
-            );
-            println!(
-                "├─ {} {} {}",
-                "✉️".bold(),
-                "Email:".bold(),
-                account.email.bright_white()
-            );
-            println!(
-                "├─ {} {} {}",
-                provider_emoji.bold(),
-                "Provider:".bold(),
-                provider_name.bright_cyan()
-            );
-            println!(
-                "├─ {} {} {} {}",
-                "🔑".bold(),
-                "SSH Key:".bold(),
-                ssh_key_status.1,
-                ssh_key_status.0
-            );
-            println!("│   {}", account.ssh_key_path.bright_black());
+/// Context markers shown next to an account in the listing
+fn context_tags(name: &str, local: Option<&str>, global: Option<&str>) -> String {
+    let mut tags = String::new();
+    if local == Some(name) {
+        tags.push_str(&format!(" {}", "● active here".green().bold()));
+    }
+    if global == Some(name) {
+        tags.push_str(&format!(" {}", "🌍 global".bright_blue()));
+    }
+    tags
+}
 
-            if !account.groups.is_empty() {
-                println!(
-                    "├─ {} {} {}",
-                    "👥".bold(),
-                    "Groups:".bold(),
-                    account.groups.join(", ").bright_white()
-                );
-            }
-            if !account.additional_ssh_keys.is_empty() {
-                println!(
-                    "├─ {} {} {}",
-                    "🔐".bold(),
-                    "Additional Keys:".bold(),
-                    account.additional_ssh_keys.len().to_string().bright_white()
-                );
-            }
-            println!(
-                "╰─ {} {}",
-                "🚀".bold(),
-                format!("git-switch use '{}'", name).bright_green()
-            );
-        }
-    } else {
-        // Compact view with better formatting
-        for (name, account) in &config.accounts {
-            let (provider_emoji, provider_name) = match account.provider.as_deref() {
-                Some("github") => ("🐙", "GitHub"),
-                Some("gitlab") => ("🦊", "GitLab"),
-                Some("bitbucket") => ("🪣", "Bitbucket"),
-                Some(other) => ("🔗", other),
-                None => ("❓", "Unknown"),
-            };
+/// How `list` groups accounts in its tree view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListGrouping {
+    Provider,
+    Group,
+}
 
-            // Check SSH key status
-            let key_status = if let Ok(expanded_path) = utils::expand_path(&account.ssh_key_path) {
-                if expanded_path.exists() { "✅" } else { "❌" }
-            } else {
-                "⚠️"
-            };
+impl std::str::FromStr for ListGrouping {
+    type Err = GitSwitchError;
 
-            println!(
-                "  {} {} {} {} {} {} {}",
-                provider_emoji,
-                name.bright_cyan().bold(),
-                "•".bright_black(),
-                account.username.bright_white(),
-                "•".bright_black(),
-                provider_name.dimmed(),
-                key_status
-            );
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "provider" => Ok(ListGrouping::Provider),
+            "group" => Ok(ListGrouping::Group),
+            _ => Err(GitSwitchError::Other(format!(
+                "Unknown grouping: {}. Supported: provider, group",
+                s
+            ))),
         }
     }
+}
 
-    println!("\n{}", "─".repeat(50).bright_black());
-    println!(
-        "{} {} {}",
-        "💡".bold(),
-        "Quick commands:".bold().bright_yellow(),
-        "git-switch use <name> | git-switch add <name>"
-            .bright_white()
-            .dimmed()
-    );
-    Ok(())
+/// Options for `list`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListOptions {
+    /// Show detailed information per account
+    pub detailed: bool,
+    /// Nest accounts under their provider or group
+    pub group_by: Option<ListGrouping>,
+    /// Print JSON instead of the human-readable listing
+    pub json: bool,
+    /// Test each account's key against its provider and annotate the listing
+    pub check_providers: bool,
+    /// Print tab-separated `name username email provider` lines
+    pub porcelain: bool,
+    /// Porcelain records terminated by NUL instead of a newline
+    pub null: bool,
 }
 
-/// Find account by name or username/email
-fn find_account<'a>(config: &'a Config, name_or_username: &str) -> Option<&'a Account> {
-    config.accounts.get(name_or_username).or_else(|| {
+/// A branch of the `list` tree: accounts sharing a provider or group
+#[derive(Serialize)]
+struct AccountGroup<'a> {
+    group: String,
+    count: usize,
+    accounts: Vec<&'a Account>,
+}
+
+/// Accounts ordered by name, for stable listings
+fn sorted_accounts(config: &Config) -> Vec<&Account> {
+    let mut accounts: Vec<&Account> = config.accounts.values().collect();
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    accounts
+}
+
+/// Bucket accounts by provider or group; accounts in several groups appear under each
+fn group_accounts(config: &Config, grouping: ListGrouping) -> Vec<AccountGroup<'_>> {
+    let mut groups: BTreeMap<String, Vec<&Account>> = BTreeMap::new();
+    for account in sorted_accounts(config) {
+        let keys = match grouping {
+            ListGrouping::Provider => {
+                vec![
+                    account
+                        .provider
+                        .clone()
+                        .unwrap_or_else(|| "unknown".to_string()),
+                ]
+            }
+            ListGrouping::Group if account.groups.is_empty() => vec!["ungrouped".to_string()],
+            ListGrouping::Group => account.groups.clone(),
+        };
+        for key in keys {
+            groups.entry(key).or_default().push(account);
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(group, accounts)| AccountGroup {
+            group,
+            count: accounts.len(),
+            accounts,
+        })
+        .collect()
+}
+
+/// One-line summary of an account, used by the compact and tree listings
+fn print_account_summary(name: &str, account: &Account, tags: &str, indent: &str) {
+    let (provider_emoji, provider_name) = match account.provider.as_deref() {
+        Some("github") => ("🐙", "GitHub"),
+        Some("gitlab") => ("🦊", "GitLab"),
+        Some("bitbucket") => ("🪣", "Bitbucket"),
+        Some(other) => ("🔗", other),
+        None => ("❓", "Unknown"),
+    };
+
+    // Check SSH key status
+    let key_status = if let Ok(expanded_path) = utils::expand_path(&account.ssh_key_path) {
+        if expanded_path.exists() { "✅" } else { "❌" }
+    } else {
+        "⚠️"
+    };
+
+    println!(
+        "{}{} {} {} {} {} {} {}{}",
+        indent,
+        provider_emoji,
+        name.bright_cyan().bold(),
+        "•".bright_black(),
+        account.git_name.bright_white(),
+        "•".bright_black(),
+        provider_name.dimmed(),
+        key_status,
+        tags
+    );
+}
+
+/// Multi-line card for an account, used by `list --detailed`
+fn print_account_details(name: &str, account: &Account, tags: &str) {
+    // Get provider emoji and info
+    let (provider_emoji, provider_name) = match account.provider.as_deref() {
+        Some("github") => ("🐙", "GitHub"),
+        Some("gitlab") => ("🦊", "GitLab"),
+        Some("bitbucket") => ("🪣", "Bitbucket"),
+        Some(other) => ("�", other),
+        None => ("❓", "Unknown"),
+    };
+
+    // Check if SSH key exists
+    let ssh_key_status = if let Ok(expanded_path) = utils::expand_path(&account.ssh_key_path) {
+        if expanded_path.exists() {
+            ("✅", "Found".green())
+        } else {
+            ("❌", "Missing".red())
+        }
+    } else {
+        ("⚠️", "Invalid Path".yellow())
+    };
+
+    println!(
+        "╭─ {} {} {}{}",
+        "📋".bold(),
+        name.bright_cyan().bold(),
+        format!("({})", provider_name).bright_black(),
+        tags
+    );
+    println!("│");
+    println!(
+        "├─ {} {} {}",
+        "👤".bold(),
+        "Git name:".bold(),
+        account.git_name.bright_white()
+    );
+    println!(
+        "├─ {} {} {}",
+        "🪪".bold(),
+        "Login:".bold(),
+        account.login.bright_white()
+    );
+    println!(
+        "├─ {} {} {}",
+        "✉️".bold(),
+        "Email:".bold(),
+        account.email.bright_white()
+    );
+    println!(
+        "├─ {} {} {}",
+        provider_emoji.bold(),
+        "Provider:".bold(),
+        provider_name.bright_cyan()
+    );
+    println!(
+        "├─ {} {} {} {}",
+        "🔑".bold(),
+        "SSH Key:".bold(),
+        ssh_key_status.1,
+        ssh_key_status.0
+    );
+    println!("│   {}", account.ssh_key_path.bright_black());
+    if let Some(fingerprint) = utils::expand_path(&account.ssh_key_path)
+        .ok()
+        .and_then(|path| ssh::get_key_fingerprint(&path))
+    {
+        println!("│   {}", fingerprint.bright_black());
+    }
+
+    if !account.groups.is_empty() {
+        println!(
+            "├─ {} {} {}",
+            "👥".bold(),
+            "Groups:".bold(),
+            account.groups.join(", ").bright_white()
+        );
+    }
+    if !account.url_patterns.is_empty() {
+        println!(
+            "├─ {} {} {}",
+            "🧭".bold(),
+            "URL Patterns:".bold(),
+            account.url_patterns.join(", ").bright_white()
+        );
+    }
+    if !account.additional_ssh_keys.is_empty() {
+        println!(
+            "├─ {} {} {}",
+            "🔐".bold(),
+            "Additional Keys:".bold(),
+            account.additional_ssh_keys.len().to_string().bright_white()
+        );
+    }
+    println!(
+        "╰─ {} {}",
+        "🚀".bold(),
+        format!("git-switch use '{}'", name).bright_green()
+    );
+}
+
+/// List accounts with optional detailed view
+pub fn list_accounts(config: &Config, options: ListOptions) -> Result<()> {
+    if options.json {
+        let json = match options.group_by {
+            Some(grouping) => serde_json::to_string_pretty(&group_accounts(config, grouping)),
+            None => serde_json::to_string_pretty(&sorted_accounts(config)),
+        }
+        .map_err(GitSwitchError::Json)?;
+        println!("{}", json);
+        return Ok(());
+    }
+    if options.porcelain || options.null {
+        let terminator = if options.null { '\0' } else { '\n' };
+        for account in sorted_accounts(config) {
+            print!(
+                "{}\t{}\t{}\t{}{}",
+                account.name,
+                account.git_name,
+                account.email,
+                account.provider.as_deref().unwrap_or_default(),
+                terminator
+            );
+        }
+        return Ok(());
+    }
+
+    if config.accounts.is_empty() {
+        println!(
+            "\n{} {}",
+            "📭".yellow(),
+            "No Git accounts configured yet".bold()
+        );
+        println!("{}", "──────────────────────────────────".bright_black());
+        println!("Get started by adding your first account:");
+        println!(
+            "{} {}",
+            "💡".bold(),
+            "git-switch add <name> <username> <email>".bright_cyan()
+        );
+        println!(
+            "{} {}",
+            "📖".bold(),
+            "git-switch add --help".bright_white().dimmed()
+        );
+        return Ok(());
+    }
+
+    let account_count = config.accounts.len();
+    let plural = if account_count == 1 {
+        "Account"
+    } else {
+        "Accounts"
+    };
+
+    println!(
+        "\n{} {} {} {}",
+        "📚".bold(),
+        account_count.to_string().bright_yellow().bold(),
+        plural.bold(),
+        "Configured".bold()
+    );
+    println!("{}", "═".repeat(50).bright_black());
+
+    let registration: HashMap<String, AuthOutcome> = if options.check_providers {
+        check_key_registration(config)?
+    } else {
+        HashMap::new()
+    };
+
+    let (local_active, global_active) = active_account_names(config);
+    let tags = |name: &str| {
+        let mut tags = context_tags(name, local_active.as_deref(), global_active.as_deref());
+        if let Some(outcome) = registration.get(name) {
+            tags.push_str(&format!(" {}", registration_tag(outcome)));
+        }
+        tags
+    };
+
+    match options.group_by {
+        Some(grouping) => {
+            for (i, group) in group_accounts(config, grouping).iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!(
+                    "{} {}",
+                    group.group.bright_yellow().bold(),
+                    format!("({})", group.count).bright_black()
+                );
+                for account in &group.accounts {
+                    if options.detailed {
+                        println!();
+                        print_account_details(&account.name, account, &tags(&account.name));
+                    } else {
+                        print_account_summary(&account.name, account, &tags(&account.name), "    ");
+                    }
+                }
+            }
+        }
+        None if options.detailed => {
+            for (i, (name, account)) in config.accounts.iter().enumerate() {
+                if i > 0 {
+                    println!(); // Add spacing between accounts
+                }
+                print_account_details(name, account, &tags(name));
+            }
+        }
+        None => {
+            // Compact view with better formatting
+            for (name, account) in &config.accounts {
+                print_account_summary(name, account, &tags(name), "  ");
+            }
+        }
+    }
+
+    println!("\n{}", "─".repeat(50).bright_black());
+    println!(
+        "{} {} {}",
+        "💡".bold(),
+        "Quick commands:".bold().bright_yellow(),
+        "git-switch use <name> | git-switch add <name>"
+            .bright_white()
+            .dimmed()
+    );
+    Ok(())
+}
+
+/// Show a single account with its key details
+pub fn show_account(config: &Config, name: &str, copy_key: bool) -> Result<()> {
+    let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
+        name: name.to_string(),
+    })?;
+
+    println!("\n{} {}", "📋".bold(), account.name.bright_cyan().bold());
+    println!("{}", "─".repeat(40).bright_black());
+    println!(
+        "👤 {} {}",
+        "Git name:".bold(),
+        account.git_name.bright_white()
+    );
+    println!("🪪 {} {}", "Login:".bold(), account.login.bright_white());
+    println!("✉️  {} {}", "Email:".bold(), account.email.bright_white());
+    println!(
+        "🔗 {} {}",
+        "Provider:".bold(),
+        account
+            .provider
+            .as_deref()
+            .unwrap_or("Unknown")
+            .bright_cyan()
+    );
+    if let Some(host) = &account.host {
+        println!("🌐 {} {}", "Host:".bold(), host.bright_white());
+    }
+
+    if account.ssh_key_path.is_empty() {
+        println!("🔑 {} None", "SSH Key:".bold());
+        if copy_key {
+            println!("{} Account has no SSH key to copy", "ℹ".blue());
+        }
+    } else {
+        let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
+        let status = if expanded_key_path.exists() {
+            "Found".green()
+        } else {
+            "Missing".red()
+        };
+        println!(
+            "🔑 {} {} ({})",
+            "SSH Key:".bold(),
+            account.ssh_key_path.bright_white(),
+            status
+        );
+        match ssh::get_key_fingerprint(&expanded_key_path) {
+            Some(fingerprint) => {
+                println!(
+                    "🧬 {} {}",
+                    "Fingerprint:".bold(),
+                    fingerprint.bright_white()
+                )
+            }
+            None => println!("🧬 {} {}", "Fingerprint:".bold(), "Unavailable".dimmed()),
+        }
+        if copy_key {
+            copy_public_key(&expanded_key_path)?;
+        }
+    }
+
+    for key in &account.additional_ssh_keys {
+        println!("🔐 {} {}", "Additional Key:".bold(), key.bright_white());
+    }
+    if !account.groups.is_empty() {
+        println!("👥 {} {}", "Groups:".bold(), account.groups.join(", "));
+    }
+    if !account.url_patterns.is_empty() {
+        println!(
+            "🧭 {} {}",
+            "URL Patterns:".bold(),
+            account.url_patterns.join(", ")
+        );
+    }
+    if !account.additional_emails.is_empty() {
+        println!(
+            "📨 {} {}",
+            "Additional Emails:".bold(),
+            account.additional_emails.join(", ")
+        );
+    }
+    if let Some(branch) = &account.default_branch {
+        println!("🌿 {} {}", "Default Branch:".bold(), branch);
+    }
+    if let Some(path) = &account.commit_template {
+        println!("📝 {} {}", "Commit Template:".bold(), path);
+    }
+    if let Some(path) = &account.hooks_path {
+        println!("🪝 {} {}", "Hooks Path:".bold(), path);
+    }
+    if !account.git_settings.is_empty() {
+        println!("⚙️  {}", "Git Settings:".bold());
+        let mut settings: Vec<_> = account.git_settings.iter().collect();
+        settings.sort();
+        for (key, value) in settings {
+            println!("   {} = {}", key, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check an account's private key, public key and that the two belong together.
+///
+/// Every check is reported; the first failure is returned, with a mismatched pair
+/// surfacing as `KeyPairMismatch`.
+pub fn verify_account_key_pair(config: &Config, name: &str) -> Result<()> {
+    let account = config
+        .accounts
+        .get(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    if account.ssh_key_path.is_empty() {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' has no SSH key to verify",
+            name
+        )));
+    }
+    utils::require_openssh_program("ssh-keygen")?;
+
+    let private_key = utils::expand_path(&account.ssh_key_path)?;
+    let public_key = with_suffix(&private_key, ".pub");
+
+    println!(
+        "{} Verifying key pair for account '{}'",
+        "🔑".bold(),
+        name.cyan()
+    );
+
+    let private_result = validation::validate_ssh_key(&private_key).and_then(|()| {
+        let content = fs::read_to_string(&private_key)?;
+        validation::validate_ssh_private_key_content(&content)
+    });
+    let public_result = if public_key.exists() {
+        validation::validate_ssh_public_key_file(&public_key)
+    } else {
+        Err(GitSwitchError::InvalidSshKey {
+            message: format!("Public key not found: {}", public_key.display()),
+        })
+    };
+    let pair_result = match (&private_result, &public_result) {
+        (Ok(()), Ok(())) => Some(validation::verify_ssh_key_pair(&private_key, &public_key)),
+        _ => None,
+    };
+
+    let report = |label: &str, result: Option<&Result<()>>, detail: String| match result {
+        Some(Ok(())) => println!("  {} {:<12} {}", "✓".green().bold(), label, detail.dimmed()),
+        Some(Err(e)) => println!("  {} {:<12} {}", "✗".red().bold(), label, e),
+        None => println!(
+            "  {} {:<12} {}",
+            "-".dimmed(),
+            label,
+            "skipped (a key above is invalid)".dimmed()
+        ),
+    };
+    report(
+        "Private key",
+        Some(&private_result),
+        private_key.display().to_string(),
+    );
+    report(
+        "Public key",
+        Some(&public_result),
+        public_key.display().to_string(),
+    );
+    report(
+        "Key pair",
+        pair_result.as_ref(),
+        "public key matches private key".to_string(),
+    );
+
+    match ssh::get_key_fingerprint(&private_key) {
+        Some(fingerprint) => println!("🧬 {} {}", "Fingerprint:".bold(), fingerprint),
+        None => println!("🧬 {} {}", "Fingerprint:".bold(), "Unavailable".dimmed()),
+    }
+
+    private_result?;
+    public_result?;
+    pair_result.unwrap_or(Ok(()))
+}
+
+/// Find account by name, login or email
+fn find_account<'a>(config: &'a Config, name_or_login: &str) -> Option<&'a Account> {
+    config.accounts.get(name_or_login).or_else(|| {
         config
             .accounts
             .values()
-            .find(|acc| acc.username == name_or_username || acc.email == name_or_username)
+            .find(|acc| acc.login == name_or_login || acc.matches_email(name_or_login))
     })
 }
 
-/// Use account globally with enhanced feedback
-pub fn use_account_globally(config: &Config, name: &str) -> Result<()> {
+/// Accounts belonging to a group, sorted by name; a group without accounts is an error
+fn accounts_in_group<'a>(config: &'a Config, group: &str) -> Result<Vec<&'a Account>> {
+    let mut accounts: Vec<&Account> = config
+        .accounts
+        .values()
+        .filter(|account| account.groups.iter().any(|g| g == group))
+        .collect();
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    if accounts.is_empty() {
+        return Err(GitSwitchError::Other(format!(
+            "No accounts in group '{}'",
+            group
+        )));
+    }
+    Ok(accounts)
+}
+
+/// The account `use --group` applies: the group must have exactly one
+pub fn group_account_name(config: &Config, group: &str) -> Result<String> {
+    match accounts_in_group(config, group)?.as_slice() {
+        [account] => Ok(account.name.clone()),
+        accounts => {
+            let names: Vec<&str> = accounts.iter().map(|a| a.name.as_str()).collect();
+            Err(GitSwitchError::Other(format!(
+                "Group '{}' has {} accounts ({}); pick one with 'git-switch use <account>'",
+                group,
+                accounts.len(),
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Environment variables `use --temp` sets and `use --unset-temp` clears
+const TEMP_ENV_VARS: [&str; 5] = [
+    "GIT_AUTHOR_NAME",
+    "GIT_AUTHOR_EMAIL",
+    "GIT_COMMITTER_NAME",
+    "GIT_COMMITTER_EMAIL",
+    "GIT_SSH_COMMAND",
+];
+
+/// Print shell `export` lines that switch identity for the current shell only,
+/// meant to be `eval`'d; no Git config is written
+pub fn print_temp_switch(config: &Config, name: &str) -> Result<()> {
     let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
         name: name.to_string(),
     })?;
 
-    println!("🔄 Switching to account '{}'", account.name.cyan());
+    let mut exports = vec![
+        ("GIT_AUTHOR_NAME", account.git_name.clone()),
+        ("GIT_AUTHOR_EMAIL", account.email.clone()),
+        ("GIT_COMMITTER_NAME", account.git_name.clone()),
+        ("GIT_COMMITTER_EMAIL", account.email.clone()),
+    ];
+    if account.ssh_key_path.is_empty() {
+        // Don't leave an earlier temporary switch's key in place
+        println!("unset GIT_SSH_COMMAND");
+    } else {
+        let key_path = utils::expand_path(&account.ssh_key_path)?;
+        exports.push((
+            "GIT_SSH_COMMAND",
+            git::ssh_command_for_key(&key_path.to_string_lossy(), config.strict_ssh_for(account)),
+        ));
+    }
+    for (variable, value) in exports {
+        println!("export {}={}", variable, utils::shell_quote(&value));
+    }
+    Ok(())
+}
 
-    git::set_global_config(&account.username, &account.email)?;
+/// Print the shell command that undoes `use --temp`
+pub fn print_temp_unset() {
+    println!("unset {}", TEMP_ENV_VARS.join(" "));
+}
 
-    let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
-    if expanded_key_path.exists() {
-        ssh::add_ssh_key(&account.ssh_key_path)?;
-        println!("🔑 SSH key loaded");
-    }
+/// Whether a switch tests the account's SSH authentication once applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwitchVerify {
+    #[default]
+    Off,
+    /// Report a failed authentication test as a warning (`--verify`)
+    Warn,
+    /// Undo the switch when the authentication test fails (`--verify-strict`)
+    Strict,
+}
 
-    // Record usage analytics
-    if let Err(e) = analytics::record_usage(&account.name) {
-        tracing::warn!("Failed to record usage analytics: {}", e);
+impl SwitchVerify {
+    pub fn from_flags(verify: bool, verify_strict: bool) -> Self {
+        if verify_strict {
+            SwitchVerify::Strict
+        } else if verify {
+            SwitchVerify::Warn
+        } else {
+            SwitchVerify::Off
+        }
     }
+}
 
-    println!("{} Global Git config updated", "✓".green().bold());
-    Ok(())
+/// Git config values a switch is about to change, kept so a failed
+/// `--verify-strict` can put them back
+struct ConfigSnapshot {
+    scope: hooks::SwitchScope,
+    values: Vec<(&'static str, Option<String>)>,
 }
 
-/// Remove account with confirmation
-pub fn remove_account(config: &mut Config, name: &str, no_prompt: bool) -> Result<()> {
-    if !config.accounts.contains_key(name) {
-        return Err(GitSwitchError::AccountNotFound {
-            name: name.to_string(),
-        });
+impl ConfigSnapshot {
+    fn capture(scope: hooks::SwitchScope, keys: &[&'static str]) -> Self {
+        let values = keys
+            .iter()
+            .map(|&key| {
+                let value = match scope {
+                    hooks::SwitchScope::Global => git::get_global_config_key(key),
+                    hooks::SwitchScope::Local => git::get_local_config_key(key),
+                };
+                (key, value.ok())
+            })
+            .collect();
+        Self { scope, values }
     }
 
-    if !no_prompt {
-        let confirm = Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-            .with_prompt(format!("Remove account '{}'?", name.red()))
-            .default(false)
-            .interact()?;
+    fn restore(&self) -> Result<()> {
+        for (key, value) in &self.values {
+            match (self.scope, value) {
+                (hooks::SwitchScope::Global, Some(value)) => {
+                    git::set_global_config_key(key, value)?
+                }
+                (hooks::SwitchScope::Global, None) => {
+                    git::unset_global_config_key(key)?;
+                }
+                (hooks::SwitchScope::Local, value) => {
+                    git::set_or_unset_local_config_key_in(Path::new("."), key, value.as_deref())?
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
-        if !confirm {
-            println!("Operation cancelled");
+/// Run the account's authentication test after a switch. A failure is a
+/// warning, or with `SwitchVerify::Strict` rolls the switch back and is an error.
+fn verify_switch(
+    account: &Account,
+    key_path: &str,
+    verify: SwitchVerify,
+    snapshot: Option<&ConfigSnapshot>,
+) -> Result<()> {
+    if verify == SwitchVerify::Off {
+        return Ok(());
+    }
+    if !key_path.is_empty() {
+        utils::require_openssh_program("ssh")?;
+    }
+
+    let result = test_account_key_auth(account, key_path);
+    let reason = match result.outcome {
+        AuthOutcome::Passed => {
+            println!("🔓 Authentication verified against {}", result.host);
+            return Ok(());
+        }
+        AuthOutcome::Skipped(reason) => {
+            println!("🔓 Authentication test skipped ({})", reason);
             return Ok(());
         }
+        AuthOutcome::Failed(reason) | AuthOutcome::Offline(reason) => reason,
+    };
+
+    println!(
+        "{} Authentication test against {} failed: {}",
+        "⚠".yellow(),
+        result.host,
+        reason.trim()
+    );
+    let Some(snapshot) = snapshot.filter(|_| verify == SwitchVerify::Strict) else {
+        return Ok(());
+    };
+    snapshot.restore()?;
+    println!("↩️  Switch rolled back; the previous identity is in place");
+    Err(GitSwitchError::SshCommand {
+        command: format!("ssh -T {}", result.host),
+        message: format!(
+            "account '{}' did not authenticate: {}",
+            account.name,
+            reason.trim()
+        ),
+    })
+}
+
+/// How `use` should load the account's key into the SSH agent
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AgentOptions {
+    /// Skip ssh-add entirely
+    pub no_agent: bool,
+    /// Lifetime in seconds, overriding the account and global settings
+    pub lifetime: Option<u64>,
+}
+
+/// Use account globally with enhanced feedback
+pub fn use_account_globally(
+    config: &Config,
+    name: &str,
+    agent: AgentOptions,
+    verify: SwitchVerify,
+) -> Result<()> {
+    let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
+        name: name.to_string(),
+    })?;
+
+    println!("🔄 Switching to account '{}'", account.name.cyan());
+    let snapshot = (verify == SwitchVerify::Strict).then(|| {
+        ConfigSnapshot::capture(
+            hooks::SwitchScope::Global,
+            &["user.name", "user.email", "init.defaultBranch"],
+        )
+    });
+
+    git::set_global_config(&account.git_name, &account.email)?;
+    if let Some(branch) = &account.default_branch {
+        git::set_global_config_key("init.defaultBranch", branch)?;
+        println!("🌿 Default branch for new repositories: {}", branch.cyan());
+    }
+
+    let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
+    if agent.no_agent || !config.ssh_agent_enabled() {
+        println!("🔑 SSH agent loading skipped");
+    } else if expanded_key_path.exists() {
+        // Command line beats the account setting, which beats the global setting
+        let lifetime = agent
+            .lifetime
+            .or(account.ssh_add_lifetime)
+            .or(config.settings.ssh_add_lifetime);
+        let passphrase = resolve_passphrase(config, account)?;
+        ssh::add_ssh_key(
+            &account.ssh_key_path,
+            lifetime,
+            passphrase.as_ref().map(|p| p.as_str()),
+        )?;
+        match lifetime {
+            Some(seconds) => println!("🔑 SSH key loaded (expires in {}s)", seconds),
+            None => println!("🔑 SSH key loaded"),
+        }
+    }
+    verify_switch(account, &account.ssh_key_path, verify, snapshot.as_ref())?;
+
+    // Record usage analytics
+    if let Err(e) = analytics::record_usage(&account.name) {
+        tracing::warn!("Failed to record usage analytics: {}", e);
+    }
+    if let Err(e) = analytics::record_switch(
+        &account.name,
+        hooks::SwitchScope::Global,
+        config.settings.history_retention_days,
+    ) {
+        tracing::warn!("Failed to record switch history: {}", e);
+    }
+
+    // A standalone switch ends any globally active profile; `profile use --global`
+    // records its profile again afterwards
+    if let Err(e) = state::clear_active_profile() {
+        tracing::warn!("Failed to clear the active profile: {}", e);
+    }
+
+    println!("{} Global Git config updated", "✓".green().bold());
+    hooks::run_post_switch(config, account, hooks::SwitchScope::Global);
+    Ok(())
+}
+
+/// Resolve an account's key passphrase through the configured secret command
+fn resolve_passphrase(
+    config: &Config,
+    account: &Account,
+) -> Result<Option<zeroize::Zeroizing<String>>> {
+    let Some(reference) = &account.passphrase_ref else {
+        return Ok(None);
+    };
+    match &config.settings.secret_command {
+        Some(command) => Ok(Some(secrets::resolve_secret(command, reference)?)),
+        None => {
+            println!(
+                "{} Account '{}' has a passphrase reference but no secret-command is set; ssh-add will prompt",
+                "⚠".yellow(),
+                account.name
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Remove account with confirmation
+pub fn remove_account(
+    config: &mut Config,
+    name: &str,
+    no_prompt: bool,
+    dry_run: bool,
+    key_file: KeyFileChoice,
+) -> Result<()> {
+    if !config.accounts.contains_key(name) {
+        return Err(GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        });
+    }
+    if dry_run {
+        return preview_account_removal(config, name, key_file);
+    }
+
+    if !no_prompt {
+        let confirm = Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt(format!("Remove account '{}'?", name.red()))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            println!("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let account = config.accounts.remove(name).unwrap();
+
+    // Remove SSH config entry
+    ssh::remove_ssh_config_entry(&account)?;
+
+    config::save_config(config)?;
+
+    println!(
+        "{} Account '{}' removed successfully",
+        "✓".green().bold(),
+        name
+    );
+
+    if account.ssh_key_path.is_empty() {
+        return Ok(());
+    }
+    let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
+    if !expanded_key_path.exists() {
+        return Ok(());
+    }
+    if let Some(other) = config.accounts.values().find(|other| {
+        utils::expand_path(&other.ssh_key_path).ok().as_ref() == Some(&expanded_key_path)
+    }) {
+        println!(
+            "🔑 SSH key file kept: {} (still used by account '{}')",
+            expanded_key_path.display(),
+            other.name
+        );
+        return Ok(());
+    }
+
+    // The flags decide for scripts; otherwise ask, or keep with --no-prompt
+    let remove_key = match key_file {
+        KeyFileChoice::Delete => true,
+        KeyFileChoice::Keep => false,
+        KeyFileChoice::Ask if no_prompt => false,
+        KeyFileChoice::Ask => Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt("Remove SSH key file as well?")
+            .default(false)
+            .interact()?,
+    };
+    if remove_key {
+        fs::remove_file(&expanded_key_path)?;
+        let public_key_path = with_suffix(&expanded_key_path, ".pub");
+        if public_key_path.exists() {
+            fs::remove_file(&public_key_path)?;
+        }
+        println!("🗑️ SSH key file removed: {}", expanded_key_path.display());
+    } else {
+        println!("🔑 SSH key file kept: {}", expanded_key_path.display());
+    }
+
+    Ok(())
+}
+
+/// What `remove` does with the account's key file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFileChoice {
+    /// Ask at a prompt (kept with --no-prompt)
+    Ask,
+    /// Keep the key file (`--keep-key`)
+    Keep,
+    /// Delete the key pair (`--delete-key`)
+    Delete,
+}
+
+/// Print everything `remove` would change for an account, without changing anything
+fn preview_account_removal(config: &Config, name: &str, key_file: KeyFileChoice) -> Result<()> {
+    let account = &config.accounts[name];
+    println!("🔍 Dry run: removing account '{}' would:", name.cyan());
+    println!(
+        "  • Remove the account from {}",
+        config::get_config_file_path()?.display()
+    );
+
+    match ssh::ssh_config_entry(account, config.settings.ssh_include_mode)? {
+        Some(entry) => {
+            println!(
+                "  • Delete this block from {}:",
+                ssh::managed_ssh_config_path(config.settings.ssh_include_mode)?.display()
+            );
+            for line in entry.lines() {
+                println!("      {}", line.dimmed());
+            }
+        }
+        None => println!("  • Leave the SSH config unchanged (no entry for this account)"),
+    }
+
+    if account.ssh_key_path.is_empty() {
+        println!("  • Delete no key file (the account has no SSH key)");
+    } else {
+        let key_path = utils::expand_path(&account.ssh_key_path)?;
+        if key_path.exists() {
+            match key_file {
+                KeyFileChoice::Ask => println!(
+                    "  • Offer to delete the key file {} (only if confirmed at the prompt)",
+                    key_path.display()
+                ),
+                KeyFileChoice::Keep => {
+                    println!("  • Keep the key file {}", key_path.display())
+                }
+                KeyFileChoice::Delete => println!(
+                    "  • Delete the key file {} and its public key",
+                    key_path.display()
+                ),
+            }
+        } else {
+            println!(
+                "  • Delete no key file ({} does not exist)",
+                key_path.display()
+            );
+        }
+    }
+
+    let profile_manager = profiles::ProfileManager::new(config.clone())?;
+    let referencing = profile_manager.profiles_referencing(name);
+    if referencing.is_empty() {
+        println!("  • Affect no profiles");
+    } else {
+        println!(
+            "  • {} Leave these profiles referencing a missing account:",
+            "⚠".yellow()
+        );
+        for profile in referencing {
+            let role = if profile.default_account.as_deref() == Some(name) {
+                "member, default account"
+            } else {
+                "member"
+            };
+            println!("      {} ({})", profile.name.yellow(), role);
+        }
+    }
+
+    println!("No changes made. Run without --dry-run to remove the account");
+    Ok(())
+}
+
+/// Apply the account detected for the current repository, prompting when several match
+pub fn apply_detected_account(config: &Config, dry_run: bool) -> Result<()> {
+    let candidates = detection::detect_account_candidates(config)?;
+
+    let account_name = match candidates.len() {
+        0 => return detection::suggest_account(config),
+        1 => candidates[0].clone(),
+        _ => {
+            println!(
+                "💡 {} accounts match this repository",
+                candidates.len().to_string().bright_yellow()
+            );
+            let selection = Select::with_theme(&dialoguer::theme::ColorfulTheme::default())
+                .with_prompt("Select the account to apply")
+                .default(0)
+                .items(&candidates)
+                .interact()?;
+            candidates[selection].clone()
+        }
+    };
+
+    if dry_run {
+        println!(
+            "🔍 Would apply account '{}' to the current repository",
+            account_name.cyan()
+        );
+        println!("Run without --dry-run to apply changes");
+        return Ok(());
+    }
+
+    handle_account_subcommand(config, &account_name, None, SwitchVerify::Off)
+}
+
+/// Handle account subcommand (apply to current repo), optionally with a specific key
+pub fn handle_account_subcommand(
+    config: &Config,
+    name: &str,
+    key: Option<&str>,
+    verify: SwitchVerify,
+) -> Result<()> {
+    let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
+        name: name.to_string(),
+    })?;
+    let selected_key = key
+        .map(|selector| select_account_key(account, selector))
+        .transpose()?;
+
+    // Check if we're in a git repository
+    if !git::is_in_git_repository()? {
+        return Err(GitSwitchError::NotInGitRepository);
+    }
+
+    println!(
+        "🔧 Applying account '{}' to current repository",
+        account.name.cyan()
+    );
+    let snapshot = (verify == SwitchVerify::Strict).then(|| {
+        ConfigSnapshot::capture(
+            hooks::SwitchScope::Local,
+            &[
+                "user.name",
+                "user.email",
+                "commit.template",
+                "core.hooksPath",
+                "core.sshCommand",
+            ]
+            .into_iter()
+            .chain(validation::ALLOWED_GIT_SETTINGS.iter().copied())
+            .collect::<Vec<_>>(),
+        )
+    });
+
+    git::set_local_config(&account.git_name, &account.email)?;
+    apply_repository_settings(config, Path::new("."), account)?;
+    for (label, path) in [
+        ("📝 Commit template:", &account.commit_template),
+        ("🪝 Hooks path:", &account.hooks_path),
+    ] {
+        if let Some(path) = path {
+            let missing = if utils::expand_path(path)?.exists() {
+                String::new()
+            } else {
+                format!(" {}", "(not found)".yellow())
+            };
+            println!("{} {}{}", label, path.bright_white(), missing);
+        }
+    }
+
+    let ssh_key_path = selected_key.unwrap_or(&account.ssh_key_path);
+    let expanded_key_path = utils::expand_path(ssh_key_path)?;
+    if expanded_key_path.exists() {
+        git::set_ssh_command(ssh_key_path, config.strict_ssh_for(account))?;
+        println!("🔑 SSH configuration updated for this repository");
+    } else if selected_key.is_some() {
+        return Err(GitSwitchError::SshKeyGeneration {
+            message: format!("SSH key not found: {}", expanded_key_path.display()),
+        });
+    }
+    verify_switch(account, ssh_key_path, verify, snapshot.as_ref())?;
+
+    // Record repository usage analytics
+    if let Err(e) = analytics::record_repository_usage(&account.name) {
+        tracing::warn!("Failed to record repository usage analytics: {}", e);
+    }
+    if let Err(e) = analytics::record_switch(
+        &account.name,
+        hooks::SwitchScope::Local,
+        config.settings.history_retention_days,
+    ) {
+        tracing::warn!("Failed to record switch history: {}", e);
+    }
+
+    match selected_key {
+        Some(key_path) => println!(
+            "{} Repository configured for account '{}' using key {}",
+            "✓".green().bold(),
+            account.name.cyan(),
+            key_path.bright_white()
+        ),
+        None => println!(
+            "{} Repository configured for account '{}'",
+            "✓".green().bold(),
+            account.name.cyan()
+        ),
+    }
+    hooks::run_post_switch(config, account, hooks::SwitchScope::Local);
+    Ok(())
+}
+
+/// Apply an account globally, to the current repository, or both in one call;
+/// with neither scope given it applies to the repository when inside one
+pub fn switch_account(config: &Config, name: &str, global: bool, local: bool) -> Result<()> {
+    let in_repository = git::is_in_git_repository()?;
+    let local = local || (!global && in_repository);
+    // Check the repository first so that a failing local switch never leaves
+    // only the global half applied
+    if local && !in_repository {
+        return Err(GitSwitchError::NotInGitRepository);
+    }
+    if find_account(config, name).is_none() {
+        return Err(GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        });
+    }
+
+    if global || !local {
+        use_account_globally(config, name, AgentOptions::default(), SwitchVerify::Off)?;
+    }
+    if local {
+        handle_account_subcommand(config, name, None, SwitchVerify::Off)?;
+    }
+    Ok(())
+}
+
+/// Set the account's `commit.template`, `core.hooksPath` and allowlisted Git
+/// settings in a repository, with paths expanded; settings the account lacks are
+/// removed so that another account's policy does not linger. Values no account
+/// would write are left alone.
+pub fn apply_repository_settings(
+    config: &Config,
+    repo_path: &Path,
+    account: &Account,
+) -> Result<()> {
+    for (index, (key, path)) in repository_paths(account).into_iter().enumerate() {
+        if let Some(path) = path {
+            let expanded = utils::expand_path(path)?;
+            git::set_local_config_key_in(repo_path, key, &expanded.to_string_lossy())?;
+            continue;
+        }
+        // Only a value written for one of the accounts is cleared, so hooks set
+        // up by the repository itself (Husky, lefthook) keep working
+        let Ok(current) = git::get_local_config_key_in(repo_path, key) else {
+            continue;
+        };
+        let written_by_git_switch = config.accounts.values().any(|other| {
+            repository_paths(other)[index]
+                .1
+                .and_then(|path| utils::expand_path(path).ok())
+                .is_some_and(|path| path.to_string_lossy() == current)
+        });
+        if written_by_git_switch {
+            git::set_or_unset_local_config_key_in(repo_path, key, None)?;
+        }
+    }
+
+    // Checked again here: the config file may have been edited by hand
+    let mut settings = HashMap::new();
+    for (key, value) in &account.git_settings {
+        settings.insert(
+            validation::validate_git_setting(key, value)?,
+            value.as_str(),
+        );
+    }
+    for key in validation::ALLOWED_GIT_SETTINGS {
+        let managed = config.accounts.values().any(|other| {
+            other
+                .git_settings
+                .keys()
+                .any(|other_key| other_key.eq_ignore_ascii_case(key))
+        });
+        if managed {
+            git::set_or_unset_local_config_key_in(repo_path, key, settings.get(key).copied())?;
+        }
+    }
+    Ok(())
+}
+
+/// The path-valued Git settings an account can set in a repository
+fn repository_paths(account: &Account) -> [(&'static str, Option<&str>); 2] {
+    [
+        ("commit.template", account.commit_template.as_deref()),
+        ("core.hooksPath", account.hooks_path.as_deref()),
+    ]
+}
+
+/// Resolve `--key` to one of the account's keys.
+///
+/// The selector is an index (0 is the primary key, then the additional keys in
+/// order) or a path naming one of those keys.
+fn select_account_key<'a>(account: &'a Account, selector: &str) -> Result<&'a str> {
+    // Indexes stay fixed, so an account without a primary key has no key 0
+    let keys: Vec<&str> = std::iter::once(account.ssh_key_path.as_str())
+        .chain(account.additional_ssh_keys.iter().map(String::as_str))
+        .collect();
+
+    let selected = match selector.parse::<usize>() {
+        Ok(0) if account.ssh_key_path.is_empty() && !account.additional_ssh_keys.is_empty() => {
+            return Err(GitSwitchError::Other(format!(
+                "Account '{}' has no primary key; choose one of its additional keys (1-{})",
+                account.name,
+                account.additional_ssh_keys.len()
+            )));
+        }
+        Ok(index) => keys.get(index).copied().filter(|key| !key.is_empty()),
+        Err(_) => {
+            let wanted = utils::expand_path(selector)?;
+            keys.iter()
+                .copied()
+                .filter(|key| !key.is_empty())
+                .find(|key| {
+                    *key == selector || utils::expand_path(key).ok().as_ref() == Some(&wanted)
+                })
+        }
+    };
+
+    selected.ok_or_else(|| {
+        let available: Vec<String> = keys
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| !key.is_empty())
+            .map(|(index, key)| format!("{}: {}", index, key))
+            .collect();
+        GitSwitchError::Other(format!(
+            "Key '{}' does not belong to account '{}'. Available keys: {}",
+            selector,
+            account.name,
+            if available.is_empty() {
+                "none".to_string()
+            } else {
+                available.join(", ")
+            }
+        ))
+    })
+}
+
+/// Set or clear the password manager reference for an account's key passphrase
+pub fn set_passphrase_ref(config: &mut Config, name: &str, reference: &str) -> Result<()> {
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+
+    let reference = reference.trim();
+    account.passphrase_ref = if reference.is_empty() || reference.eq_ignore_ascii_case("none") {
+        None
+    } else {
+        Some(reference.to_string())
+    };
+    let cleared = account.passphrase_ref.is_none();
+    config::save_config(config)?;
+
+    if cleared {
+        println!(
+            "{} Passphrase reference cleared for account '{}'",
+            "✓".green().bold(),
+            name.cyan()
+        );
+    } else {
+        println!(
+            "{} Passphrase reference '{}' set for account '{}'",
+            "✓".green().bold(),
+            reference,
+            name.cyan()
+        );
+        if config.settings.secret_command.is_none() {
+            println!(
+                "{} Run {} to resolve it at use time",
+                "💡".bold(),
+                "git-switch config set secret-command \"<command> {ref}\"".bright_cyan()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Set or clear ("none") the branch an account sets as `init.defaultBranch`
+pub fn set_default_branch(config: &mut Config, name: &str, branch: &str) -> Result<()> {
+    let branch = branch.trim();
+    let branch = if branch.eq_ignore_ascii_case("none") {
+        None
+    } else {
+        validation::validate_branch_name(branch)?;
+        Some(branch.to_string())
+    };
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    account.default_branch = branch;
+    config::save_config(config)?;
+
+    match &config.accounts[name].default_branch {
+        Some(branch) => println!(
+            "{} Default branch '{}' set for account '{}'",
+            "✓".green().bold(),
+            branch,
+            name.cyan()
+        ),
+        None => println!(
+            "{} Default branch cleared for account '{}'",
+            "✓".green().bold(),
+            name.cyan()
+        ),
+    }
+    Ok(())
+}
+
+/// Set or clear ("none") the email domain that identifies an account
+pub fn set_email_domain(config: &mut Config, name: &str, domain: &str) -> Result<()> {
+    let domain = domain.trim().trim_start_matches('@').to_lowercase();
+    let domain = if domain == "none" {
+        None
+    } else {
+        validation::validate_email_domain(&domain)?;
+        Some(domain)
+    };
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    account.email_domain = domain;
+    config::save_config(config)?;
+
+    match &config.accounts[name].email_domain {
+        Some(domain) => println!(
+            "{} Email domain '{}' set for account '{}'",
+            "✓".green().bold(),
+            domain,
+            name.cyan()
+        ),
+        None => println!(
+            "{} Email domain cleared for account '{}'",
+            "✓".green().bold(),
+            name.cyan()
+        ),
+    }
+    Ok(())
+}
+
+/// Change one field of an account, revalidate the whole config and save it;
+/// `rewrite_ssh` regenerates the account's SSH config entry afterwards
+fn update_account_field<F>(
+    config: &mut Config,
+    name: &str,
+    rewrite_ssh: bool,
+    change: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut Account),
+{
+    let mut updated = config.clone();
+    let account =
+        updated
+            .accounts
+            .get_mut(name)
+            .ok_or_else(|| GitSwitchError::AccountNotFound {
+                name: name.to_string(),
+            })?;
+    change(account);
+    crate::backup::validate_config(&updated)?;
+    *config = updated;
+    config::save_config(config)?;
+
+    let account = &config.accounts[name];
+    if rewrite_ssh && !account.ssh_key_path.is_empty() {
+        ssh::replace_ssh_config_entry(account, config.settings.ssh_include_mode)?;
+        println!(
+            "{} SSH config entry updated (HostName {})",
+            "🔐".green(),
+            account.ssh_hostname()
+        );
+    }
+    Ok(())
+}
+
+/// Set an account's provider ("none" clears it) and rewrite its SSH config entry
+pub fn set_provider(config: &mut Config, name: &str, provider: &str) -> Result<()> {
+    let provider = provider.trim().to_lowercase();
+    let provider = if provider == "none" {
+        None
+    } else {
+        let templates = templates::get_templates();
+        if !templates.contains_key(&provider) {
+            let mut supported: Vec<&str> = templates.keys().map(String::as_str).collect();
+            supported.sort();
+            return Err(GitSwitchError::Other(format!(
+                "Unknown provider: {}. Supported: {}, none",
+                provider,
+                supported.join(", ")
+            )));
+        }
+        Some(provider)
+    };
+    let message = match &provider {
+        Some(provider) => format!("Provider for account '{}' set to '{}'", name, provider),
+        None => format!("Provider cleared for account '{}'", name),
+    };
+    update_account_field(config, name, true, |account| account.provider = provider)?;
+    println!("{} {}", "✓".green().bold(), message);
+    Ok(())
+}
+
+/// Set the SSH host name an account connects to ("none" falls back to the
+/// provider's host) and rewrite its SSH config entry
+pub fn set_host(config: &mut Config, name: &str, host: &str) -> Result<()> {
+    let host = host.trim().to_lowercase();
+    let host = if host == "none" {
+        None
+    } else {
+        validation::validate_ssh_host(&host)?;
+        Some(host)
+    };
+    let message = match &host {
+        Some(host) => format!("Host for account '{}' set to '{}'", name, host),
+        None => format!("Host cleared for account '{}'", name),
+    };
+    update_account_field(config, name, true, |account| account.host = host)?;
+    println!("{} {}", "✓".green().bold(), message);
+    Ok(())
+}
+
+/// Set an account's primary email
+pub fn set_email(config: &mut Config, name: &str, email: &str) -> Result<()> {
+    let email = email.trim();
+    validation::validate_email(email)?;
+    update_account_field(config, name, false, |account| {
+        account.email = email.to_string()
+    })?;
+    println!(
+        "{} Email for account '{}' set to '{}'",
+        "✓".green().bold(),
+        name.cyan(),
+        email
+    );
+    Ok(())
+}
+
+/// Set the name an account writes to `user.name`
+pub fn set_git_name(config: &mut Config, name: &str, git_name: &str) -> Result<()> {
+    let git_name = git_name.trim();
+    validation::validate_username(git_name)?;
+    update_account_field(config, name, false, |account| {
+        account.git_name = git_name.to_string()
+    })?;
+    println!(
+        "{} Git name for account '{}' set to '{}'",
+        "✓".green().bold(),
+        name.cyan(),
+        git_name
+    );
+    Ok(())
+}
+
+/// Set the provider handle an account is detected by in remote URLs
+pub fn set_login(config: &mut Config, name: &str, login: &str) -> Result<()> {
+    let login = login.trim();
+    validation::validate_login(login)?;
+    update_account_field(config, name, false, |account| {
+        account.login = login.to_string()
+    })?;
+    println!(
+        "{} Login for account '{}' set to '{}'",
+        "✓".green().bold(),
+        name.cyan(),
+        login
+    );
+    Ok(())
+}
+
+/// Move an account from the current config file into another one. The SSH
+/// config entry is shared by both files, so it stays as it is
+pub fn move_account(config: &mut Config, name: &str, target: &Path) -> Result<()> {
+    let target_path = utils::expand_path(
+        target
+            .to_str()
+            .ok_or_else(|| GitSwitchError::InvalidPath(target.to_path_buf()))?,
+    )?;
+    let source_path = config::get_config_file_path()?;
+    let same_file = match (
+        fs::canonicalize(&target_path),
+        fs::canonicalize(&source_path),
+    ) {
+        (Ok(target), Ok(source)) => target == source,
+        _ => target_path == source_path,
+    };
+    if same_file {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' is already in {}",
+            name,
+            target_path.display()
+        )));
+    }
+    let account =
+        config
+            .accounts
+            .get(name)
+            .cloned()
+            .ok_or_else(|| GitSwitchError::AccountNotFound {
+                name: name.to_string(),
+            })?;
+
+    let mut target_config = config::load_config_from(&target_path)?;
+    crate::backup::validate_config(&target_config)?;
+    if target_config.accounts.contains_key(name) {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' already exists in {}",
+            name,
+            target_path.display()
+        )));
+    }
+    target_config.accounts.insert(name.to_string(), account);
+    crate::backup::validate_config(&target_config)?;
+
+    // Write the target first so a failure never loses the account
+    config::save_config_to(&target_config, &target_path)?;
+    config.accounts.remove(name);
+    config::save_config(config)?;
+
+    println!(
+        "{} Account '{}' moved to {}",
+        "✓".green().bold(),
+        name.cyan(),
+        target_path.display()
+    );
+    println!(
+        "🔐 SSH config entry kept ({})",
+        ssh::host_alias(&target_config.accounts[name].slug())
+    );
+    Ok(())
+}
+
+/// Show which accounts a remote URL matches, and why, without needing a repository
+pub fn check_url(config: &Config, url: &str, json: bool) -> Result<()> {
+    let check = detection::check_url(config, url)?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&check).map_err(GitSwitchError::Json)?
+        );
+        return Ok(());
+    }
+
+    println!("🔍 Checking {}", check.url.bright_white());
+    if check.matches.is_empty() {
+        println!("{} No account matches this URL", "ℹ".blue());
+        return Ok(());
+    }
+    for url_match in &check.matches {
+        println!(
+            "  {} {} ({}%): {}",
+            "✓".green(),
+            url_match.account.cyan(),
+            (url_match.confidence * 100.0) as u8,
+            url_match.reason
+        );
+    }
+    if let Some(account) = &check.account {
+        println!("Detected account: {}", account.cyan().bold());
+    }
+    Ok(())
+}
+
+/// Set an allowlisted Git setting an account applies to repositories, or clear it ("none")
+pub fn set_git_setting(config: &mut Config, name: &str, key: &str, value: &str) -> Result<()> {
+    let value = value.trim();
+    let clear = value.eq_ignore_ascii_case("none");
+    let key = validation::validate_git_setting(key, value)?;
+    update_account_field(config, name, false, |account| {
+        if clear {
+            account.git_settings.remove(key);
+        } else {
+            account
+                .git_settings
+                .insert(key.to_string(), value.to_string());
+        }
+    })?;
+    if clear {
+        println!(
+            "{} {} cleared for account '{}'",
+            "✓".green().bold(),
+            key,
+            name.cyan()
+        );
+    } else {
+        println!(
+            "{} {} for account '{}' set to '{}'",
+            "✓".green().bold(),
+            key,
+            name.cyan(),
+            value
+        );
+    }
+    Ok(())
+}
+
+/// Set or clear ("none") the file an account applies as `commit.template`
+pub fn set_commit_template(config: &mut Config, name: &str, path: &str) -> Result<()> {
+    set_account_path_setting(config, name, path, "commit template", |account| {
+        &mut account.commit_template
+    })
+}
+
+/// Set or clear ("none") the directory an account applies as `core.hooksPath`
+pub fn set_hooks_path(config: &mut Config, name: &str, path: &str) -> Result<()> {
+    set_account_path_setting(config, name, path, "hooks path", |account| {
+        &mut account.hooks_path
+    })
+}
+
+fn set_account_path_setting(
+    config: &mut Config,
+    name: &str,
+    path: &str,
+    label: &str,
+    field: fn(&mut Account) -> &mut Option<String>,
+) -> Result<()> {
+    let path = path.trim();
+    let path = if path.eq_ignore_ascii_case("none") {
+        None
+    } else {
+        validation::validate_path_setting(label, path)?;
+        Some(path.to_string())
+    };
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    *field(account) = path.clone();
+    config::save_config(config)?;
+
+    match path {
+        Some(path) => println!(
+            "{} Account '{}' {} set to '{}'; applied with 'git-switch account {}'",
+            "✓".green().bold(),
+            name.cyan(),
+            label,
+            path,
+            name
+        ),
+        None => println!(
+            "{} Account '{}' {} cleared",
+            "✓".green().bold(),
+            name.cyan(),
+            label
+        ),
+    }
+    Ok(())
+}
+
+/// Record an additional email that identifies an account
+pub fn add_account_email(config: &mut Config, name: &str, email: &str) -> Result<()> {
+    let email = email.trim();
+    validation::validate_email(email)?;
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    if account.matches_email(email) {
+        println!(
+            "{} Account '{}' already uses email '{}'",
+            "ℹ".blue(),
+            name.cyan(),
+            email
+        );
+        return Ok(());
+    }
+    account.additional_emails.push(email.to_string());
+    config::save_config(config)?;
+
+    println!(
+        "{} Email '{}' added to account '{}'",
+        "✓".green().bold(),
+        email,
+        name.cyan()
+    );
+    Ok(())
+}
+
+/// File name prefix of keys generated by `add` and `account set-key`
+const DEFAULT_KEY_NAME: &str = "id_rsa";
+
+/// Path of the key git-switch generates for an account with this slug
+fn default_key_path(key_name: &str, slug: &str) -> String {
+    format!("~/.ssh/{}_{}", key_name, slug)
+}
+
+/// Whether `key_path` is a key git-switch generated for this slug, plain or from a template
+fn is_generated_key_path(key_path: &std::path::Path, slug: &str) -> Result<bool> {
+    let templates = templates::get_templates();
+    let key_names = std::iter::once(DEFAULT_KEY_NAME).chain(
+        templates
+            .values()
+            .map(|template| template.default_ssh_key_name.as_str()),
+    );
+    for key_name in key_names {
+        if utils::expand_path(&default_key_path(key_name, slug))? == key_path {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Slug for a new account, made unique by appending `_2`, `_3`, ... when another
+/// account, an SSH config host or (when a key named `generated_key_name` will be
+/// generated) a key file already uses it. Replacing an account with `--force` keeps its slug.
+fn unique_account_slug(
+    config: &Config,
+    name: &str,
+    generated_key_name: Option<&str>,
+) -> Result<String> {
+    if let Some(existing) = config.accounts.get(name) {
+        return Ok(existing.slug());
+    }
+
+    let ssh_config = ssh::read_ssh_configs();
+    let base = config::slugify(name);
+    for n in 1.. {
+        let candidate = if n == 1 {
+            base.clone()
+        } else {
+            format!("{}_{}", base, n)
+        };
+        let taken = config
+            .accounts
+            .values()
+            .any(|account| account.slug() == candidate)
+            || ssh::has_host_entry(&ssh_config, &ssh::host_alias(&candidate))
+            || match generated_key_name {
+                Some(key_name) => {
+                    utils::expand_path(&default_key_path(key_name, &candidate))?.exists()
+                }
+                None => false,
+            };
+        if !taken {
+            return Ok(candidate);
+        }
+    }
+    unreachable!("slug candidates are unbounded")
+}
+
+/// `path` with `suffix` appended to the file name, e.g. `id_rsa` -> `id_rsa.pub`
+pub(crate) fn with_suffix(path: &std::path::Path, suffix: &str) -> PathBuf {
+    let mut suffixed = path.as_os_str().to_owned();
+    suffixed.push(suffix);
+    PathBuf::from(suffixed)
+}
+
+/// Rotate an account's key: keep the old pair as `.old` and generate a new one at the same path
+pub fn regenerate_account_key(
+    config: &Config,
+    name: &str,
+    key_type: ssh::KeyType,
+    force: bool,
+) -> Result<()> {
+    let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
+        name: name.to_string(),
+    })?;
+    if account.ssh_key_path.is_empty() {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' has no SSH key to regenerate",
+            account.name
+        )));
+    }
+
+    let key_path = utils::expand_path(&account.ssh_key_path)?;
+    if !is_generated_key_path(&key_path, &account.slug())? && !force {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' uses an external key ({}); pass --force to replace it",
+            account.name,
+            key_path.display()
+        )));
+    }
+
+    let public_key_path = with_suffix(&key_path, ".pub");
+    let rotated = [
+        (key_path.clone(), with_suffix(&key_path, ".old")),
+        (
+            public_key_path.clone(),
+            with_suffix(&public_key_path, ".old"),
+        ),
+    ];
+    for (current, old) in &rotated {
+        if current.exists() {
+            fs::rename(current, old).map_err(GitSwitchError::Io)?;
+        }
+    }
+
+    if let Err(e) = ssh::generate_ssh_key_of_type(&key_path, key_type) {
+        // Put the previous pair back so the account keeps working
+        for (current, old) in &rotated {
+            if old.exists() {
+                let _ = fs::rename(old, current);
+            }
+        }
+        return Err(e);
+    }
+    ssh::replace_ssh_config_entry(account, config.settings.ssh_include_mode)?;
+
+    println!(
+        "{} New SSH key generated for account '{}'",
+        "✓".green().bold(),
+        account.name.cyan()
+    );
+    print_fingerprint(&key_path);
+    if rotated[0].1.exists() {
+        println!(
+            "🗄️  {} {}",
+            "Previous key:".bold(),
+            rotated[0].1.display().to_string().bright_black()
+        );
+    }
+
+    println!("\n{}", "📋 Your New Public Key".bold().yellow());
+    println!("{}", "─".repeat(40).bright_black());
+    ssh::display_public_key_formatted(&key_path)?;
+    print_key_upload_instructions(
+        account.provider.as_deref().unwrap_or_default(),
+        &account.name,
+    );
+    println!(
+        "{} Remove the old key from your provider once the new one is uploaded",
+        "⚠".yellow()
+    );
+    Ok(())
+}
+
+/// Make one of an account's additional keys its primary key, moving the former
+/// primary into the additional keys in its place
+pub fn promote_account_key(config: &mut Config, name: &str, selector: &str) -> Result<()> {
+    let account = config
+        .accounts
+        .get(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    let selected = select_account_key(account, selector)?.to_string();
+    if selected == account.ssh_key_path {
+        return Err(GitSwitchError::Other(format!(
+            "Key '{}' is already the primary key of account '{}'",
+            selected, name
+        )));
+    }
+    validation::validate_ssh_key(&utils::expand_path(&selected)?)?;
+
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    let position = account
+        .additional_ssh_keys
+        .iter()
+        .position(|key| *key == selected)
+        .unwrap_or_default();
+    account.additional_ssh_keys.remove(position);
+    let previous = std::mem::replace(&mut account.ssh_key_path, selected.clone());
+    if !previous.is_empty() {
+        account
+            .additional_ssh_keys
+            .insert(position, previous.clone());
+    }
+    config::save_config(config)?;
+    ssh::replace_ssh_config_entry(&config.accounts[name], config.settings.ssh_include_mode)?;
+
+    println!(
+        "{} Key promoted to primary for account '{}'",
+        "✓".green().bold(),
+        name.cyan()
+    );
+    println!("🔑 {} {}", "SSH Key:".bold(), selected.bright_white());
+    print_fingerprint(&utils::expand_path(&selected)?);
+    if !previous.is_empty() {
+        println!(
+            "🔐 {} {}",
+            "Additional Key:".bold(),
+            previous.bright_white()
+        );
+        println!(
+            "{} Remove the former primary with your provider once the new one is verified",
+            "ℹ".blue()
+        );
+    }
+    Ok(())
+}
+
+/// Where `key set` gets the account's key from
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// Generate a new key at the default path for the account
+    Generate(ssh::KeyType),
+    /// Adopt an existing key
+    Path(PathBuf),
+}
+
+/// Attach a key to an account, e.g. one created with `--no-key`
+pub fn set_account_key(config: &mut Config, name: &str, source: KeySource) -> Result<()> {
+    if !config.accounts.contains_key(name) {
+        return Err(GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        });
+    }
+
+    let (key_path_str, expanded_key_path) = match &source {
+        KeySource::Generate(key_type) => {
+            let key_path_str = default_key_path(DEFAULT_KEY_NAME, &config.accounts[name].slug());
+            let expanded_key_path = utils::expand_path(&key_path_str)?;
+            if expanded_key_path.exists() {
+                return Err(GitSwitchError::SshKeyGeneration {
+                    message: format!(
+                        "A key already exists at {}; use --path to adopt it or `key regenerate` to replace it",
+                        expanded_key_path.display()
+                    ),
+                });
+            }
+            ssh::generate_ssh_key_of_type(&expanded_key_path, *key_type)?;
+            (key_path_str, expanded_key_path)
+        }
+        KeySource::Path(path) => {
+            let key_path_str = path
+                .to_str()
+                .ok_or_else(|| GitSwitchError::InvalidPath(path.clone()))?
+                .to_string();
+            let expanded_key_path = utils::expand_path(&key_path_str)?;
+            validation::validate_ssh_key(&expanded_key_path)?;
+            (key_path_str, expanded_key_path)
+        }
+    };
+
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    let previous = std::mem::replace(&mut account.ssh_key_path, key_path_str.clone());
+    let provider = account.provider.clone();
+    config::save_config(config)?;
+    ssh::replace_ssh_config_entry(&config.accounts[name], config.settings.ssh_include_mode)?;
+
+    println!(
+        "{} SSH key {} for account '{}'",
+        "✓".green().bold(),
+        match source {
+            KeySource::Generate(_) => "generated",
+            KeySource::Path(_) => "attached",
+        },
+        name.cyan()
+    );
+    println!("🔑 {} {}", "SSH Key:".bold(), key_path_str.bright_white());
+    print_fingerprint(&expanded_key_path);
+    if !previous.is_empty() && previous != key_path_str {
+        println!(
+            "ℹ️  Previous key {} was left in place",
+            previous.bright_black()
+        );
+    }
+
+    if let KeySource::Generate(_) = source {
+        println!("\n{}", "📋 Your Public Key".bold().yellow());
+        println!("{}", "─".repeat(40).bright_black());
+        ssh::display_public_key_formatted(&expanded_key_path)?;
+        print_key_upload_instructions(provider.as_deref().unwrap_or_default(), name);
+    }
+    Ok(())
+}
+
+/// Copy an account's key pair to `output` (public key to `output.pub`), e.g. to
+/// carry it to another machine; the config backup never contains key material
+pub fn export_account_key(
+    config: &Config,
+    name: &str,
+    output: &std::path::Path,
+    force: bool,
+) -> Result<()> {
+    let account = config
+        .accounts
+        .get(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    if account.ssh_key_path.is_empty() {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' has no SSH key to export",
+            name
+        )));
+    }
+
+    let private_key = utils::expand_path(&account.ssh_key_path)?;
+    let public_key = with_suffix(&private_key, ".pub");
+    for source in [&private_key, &public_key] {
+        if !source.exists() {
+            return Err(GitSwitchError::InvalidSshKey {
+                message: format!("SSH key file not found: {}", source.display()),
+            });
+        }
+    }
+
+    let output_public = with_suffix(output, ".pub");
+    refuse_overwrite(&[output, &output_public], force)?;
+    utils::ensure_parent_dir_exists(output)?;
+    copy_key_file(&private_key, output, 0o600)?;
+    copy_key_file(&public_key, &output_public, 0o644)?;
+
+    println!(
+        "{} Exported SSH key of account '{}'",
+        "✓".green().bold(),
+        name.cyan()
+    );
+    println!("   Private key: {}", output.display());
+    println!("   Public key:  {}", output_public.display());
+    println!(
+        "{} The private key grants access to the account; keep it secret and delete the copy once imported",
+        "⚠".yellow()
+    );
+    Ok(())
+}
+
+/// Copy a key pair (`from` and `from.pub`) into the account's key path with
+/// 600/644 permissions, validating it before it replaces anything.
+///
+/// An account without a key gets the default key path and an SSH config entry.
+pub fn import_account_key(
+    config: &mut Config,
+    name: &str,
+    from: &std::path::Path,
+    force: bool,
+) -> Result<()> {
+    let account = config
+        .accounts
+        .get(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+    utils::require_openssh_program("ssh-keygen")?;
+
+    let from_public = with_suffix(from, ".pub");
+    for source in [from, from_public.as_path()] {
+        if !source.exists() {
+            return Err(GitSwitchError::InvalidSshKey {
+                message: format!("SSH key file not found: {}", source.display()),
+            });
+        }
+    }
+
+    let assigns_path = account.ssh_key_path.is_empty();
+    let key_path_str = if assigns_path {
+        default_key_path(DEFAULT_KEY_NAME, &account.slug())
+    } else {
+        account.ssh_key_path.clone()
+    };
+    let private_key = utils::expand_path(&key_path_str)?;
+    let public_key = with_suffix(&private_key, ".pub");
+    refuse_overwrite(&[&private_key, &public_key], force)?;
+    utils::ensure_parent_dir_exists(&private_key)?;
+
+    // Stage next to the destination so a bad pair never replaces a working one
+    let staged_private = with_suffix(&private_key, ".import");
+    let staged_public = with_suffix(&private_key, ".pub.import");
+    let staged = copy_key_file(from, &staged_private, 0o600)
+        .and_then(|()| copy_key_file(&from_public, &staged_public, 0o644))
+        .and_then(|()| {
+            validation::validate_ssh_key(&staged_private)?;
+            validation::validate_ssh_private_key_content(&fs::read_to_string(&staged_private)?)?;
+            validation::validate_ssh_public_key_file(&staged_public)?;
+            validation::verify_ssh_key_pair(&staged_private, &staged_public)
+        });
+    if let Err(e) = staged {
+        let _ = fs::remove_file(&staged_private);
+        let _ = fs::remove_file(&staged_public);
+        return Err(e);
+    }
+    fs::rename(&staged_private, &private_key)?;
+    fs::rename(&staged_public, &public_key)?;
+
+    if assigns_path {
+        if let Some(account) = config.accounts.get_mut(name) {
+            account.ssh_key_path = key_path_str.clone();
+        }
+        config::save_config(config)?;
+        ssh::replace_ssh_config_entry(&config.accounts[name], config.settings.ssh_include_mode)?;
+    }
+
+    println!(
+        "{} Imported SSH key for account '{}'",
+        "✓".green().bold(),
+        name.cyan()
+    );
+    println!("🔑 {} {}", "SSH Key:".bold(), key_path_str.bright_white());
+    print_fingerprint(&private_key);
+    Ok(())
+}
+
+/// Fail unless `force` when any of `paths` already exists
+fn refuse_overwrite(paths: &[&std::path::Path], force: bool) -> Result<()> {
+    match paths.iter().find(|path| path.exists()) {
+        Some(existing) if !force => Err(GitSwitchError::Other(format!(
+            "{} already exists; pass --force to overwrite it",
+            existing.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Copy a key file and give the copy `mode` (on Unix)
+fn copy_key_file(from: &std::path::Path, to: &std::path::Path, mode: u32) -> Result<()> {
+    write_key_file(to, &fs::read(from)?, mode)
+}
+
+/// Write key material to `path` with `mode` (on Unix), set before any content
+/// is written so the key is never readable by other users
+pub(crate) fn write_key_file(path: &std::path::Path, content: &[u8], mode: u32) -> Result<()> {
+    use std::io::Write;
+
+    utils::ensure_parent_dir_exists(path)?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode);
+    }
+    let mut file = options.open(path)?;
+    // The mode above only applies to new files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    file.write_all(content)?;
+    Ok(())
+}
+
+/// Mark an account as recently used (and optionally verified) without switching to it
+pub fn touch_account(config: &Config, name: &str, verified: bool) -> Result<()> {
+    if !config.accounts.contains_key(name) {
+        return Err(GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        });
+    }
+
+    let stamped = analytics::touch_account(name, verified)?;
+    println!(
+        "{} Account '{}' marked as used{} at {}",
+        "✓".green().bold(),
+        name.cyan(),
+        if verified { " and verified" } else { "" },
+        stamped.dimmed()
+    );
+    Ok(())
+}
+
+/// Add a custom remote URL pattern to an account
+pub fn add_url_pattern(config: &mut Config, name: &str, pattern: &str) -> Result<()> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(GitSwitchError::Other(
+            "URL pattern cannot be empty".to_string(),
+        ));
+    }
+
+    let account = config
+        .accounts
+        .get_mut(name)
+        .ok_or_else(|| GitSwitchError::AccountNotFound {
+            name: name.to_string(),
+        })?;
+
+    if account.url_patterns.iter().any(|p| p == pattern) {
+        println!(
+            "{} Account '{}' already has URL pattern '{}'",
+            "ℹ".blue(),
+            name.cyan(),
+            pattern
+        );
+        return Ok(());
+    }
+
+    account.url_patterns.push(pattern.to_string());
+    config::save_config(config)?;
+
+    println!(
+        "{} URL pattern '{}' added to account '{}'",
+        "✓".green().bold(),
+        pattern.cyan(),
+        name.cyan()
+    );
+    Ok(())
+}
+
+/// Which remote URLs the remote subcommand should convert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteScope {
+    Both,
+    Fetch,
+    Push,
+}
+
+/// Handle remote subcommand (convert between HTTPS and SSH)
+pub fn handle_remote_subcommand(https: bool, ssh: bool, scope: RemoteScope) -> Result<()> {
+    if !git::is_in_git_repository()? {
+        return Err(GitSwitchError::NotInGitRepository);
+    }
+
+    let convert = |url: &str| {
+        if https {
+            convert_to_https(url)
+        } else if ssh {
+            convert_to_ssh(url)
+        } else {
+            Err(GitSwitchError::Other(
+                "Specify either --https or --ssh".to_string(),
+            ))
+        }
+    };
+
+    let fetch_url = git::get_remote_url("origin")?;
+    let push_url = git::get_git_remote_url_of_kind("origin", git::RemoteUrlKind::Push)
+        .unwrap_or_else(|_| fetch_url.clone());
+
+    match scope {
+        RemoteScope::Both if fetch_url == push_url => {
+            println!("Current remote URL: {}", git::redact_url(&fetch_url).cyan());
+            let new_url = convert(&fetch_url)?;
+            git::set_remote_url("origin", &new_url)?;
+            // An explicit push URL equal to the fetch URL must not keep the old protocol
+            if git::get_local_config_key("remote.origin.pushurl").is_ok() {
+                git::set_remote_push_url("origin", &new_url)?;
+            }
+            println!(
+                "{} Remote URL updated to: {}",
+                "✓".green().bold(),
+                git::redact_url(&new_url).cyan()
+            );
+        }
+        RemoteScope::Both => {
+            // Converting both to one URL would clobber the separate push URL
+            println!(
+                "{} Fetch and push URLs differ; converting each separately (use --fetch or --push to target one)",
+                "⚠".yellow()
+            );
+            println!("Current fetch URL: {}", git::redact_url(&fetch_url).cyan());
+            println!("Current push URL: {}", git::redact_url(&push_url).cyan());
+            let new_fetch_url = convert(&fetch_url)?;
+            let new_push_url = convert(&push_url)?;
+            git::set_remote_url("origin", &new_fetch_url)?;
+            git::set_remote_push_url("origin", &new_push_url)?;
+            println!(
+                "{} Fetch URL updated to: {}",
+                "✓".green().bold(),
+                git::redact_url(&new_fetch_url).cyan()
+            );
+            println!(
+                "{} Push URL updated to: {}",
+                "✓".green().bold(),
+                git::redact_url(&new_push_url).cyan()
+            );
+        }
+        RemoteScope::Fetch => {
+            println!("Current fetch URL: {}", git::redact_url(&fetch_url).cyan());
+            let new_url = convert(&fetch_url)?;
+            // Without an explicit push URL git pushes to the fetch URL, so pin it first
+            if fetch_url == push_url {
+                git::set_remote_push_url("origin", &push_url)?;
+            }
+            git::set_remote_url("origin", &new_url)?;
+            println!(
+                "{} Fetch URL updated to: {}",
+                "✓".green().bold(),
+                git::redact_url(&new_url).cyan()
+            );
+        }
+        RemoteScope::Push => {
+            println!("Current push URL: {}", git::redact_url(&push_url).cyan());
+            let new_url = convert(&push_url)?;
+            git::set_remote_push_url("origin", &new_url)?;
+            println!(
+                "{} Push URL updated to: {}",
+                "✓".green().bold(),
+                git::redact_url(&new_url).cyan()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Add a remote whose URL routes through an account's SSH host alias.
+///
+/// Without `account` the account is detected from the URL.
+pub fn add_remote(config: &Config, name: &str, url: &str, account: Option<&str>) -> Result<()> {
+    if !git::is_in_git_repository()? {
+        return Err(GitSwitchError::NotInGitRepository);
+    }
+
+    let account_name = match account {
+        Some(account) => account.to_string(),
+        None => detection::detect_account_for_remote_url(config, url)?.ok_or_else(|| {
+            GitSwitchError::Other(format!(
+                "No account matches '{}'; pass --account to choose one",
+                url
+            ))
+        })?,
+    };
+    let account =
+        config
+            .accounts
+            .get(&account_name)
+            .ok_or_else(|| GitSwitchError::AccountNotFound {
+                name: account_name.clone(),
+            })?;
+    if account.ssh_key_path.is_empty() {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' has no SSH key, so it has no host alias; run 'git-switch key set {} --generate' first",
+            account.name, account.name
+        )));
+    }
+
+    let aliased_url = with_host_alias(url, &ssh::host_alias(&account.slug()))?;
+    git::add_git_remote(name, &aliased_url)?;
+    println!(
+        "{} Remote '{}' added for account '{}': {}",
+        "✓".green().bold(),
+        name,
+        account.name.cyan(),
+        aliased_url.cyan()
+    );
+    Ok(())
+}
+
+/// Rewrite `url` as an SSH URL whose host is `alias`
+fn with_host_alias(url: &str, alias: &str) -> Result<String> {
+    let ssh_url = convert_to_ssh(url)?;
+    let (_, path) = ssh_url.split_once(':').ok_or_else(|| {
+        GitSwitchError::Other(format!(
+            "Cannot convert URL to SSH: {}",
+            git::redact_url(url)
+        ))
+    })?;
+    Ok(format!("git@{}:{}", alias, path))
+}
+
+/// The SSH URL prefix host aliases stand in for (every alias points at github.com)
+const URL_REWRITE_ORIGINAL: &str = "git@github.com:";
+
+/// `url.<base>` under which an account's `insteadOf` rewrite is stored
+fn url_rewrite_base(account: &Account) -> String {
+    format!("git@{}:", ssh::host_alias(&account.slug()))
+}
+
+/// Account with a key whose URL rewrite is being managed
+fn url_rewrite_account<'a>(config: &'a Config, name: &str) -> Result<&'a Account> {
+    let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
+        name: name.to_string(),
+    })?;
+    if account.ssh_key_path.is_empty() {
+        return Err(GitSwitchError::Other(format!(
+            "Account '{}' has no SSH key, so it has no host alias; run 'git-switch key set {} --generate' first",
+            account.name, account.name
+        )));
+    }
+    Ok(account)
+}
+
+/// Install a global `insteadOf` rewrite so every `git@github.com:` URL uses the account's host alias
+pub fn setup_url_rewrite(config: &Config, name: &str) -> Result<()> {
+    let account = url_rewrite_account(config, name)?;
+    let base = url_rewrite_base(account);
+
+    // git applies only one rewrite per prefix, so other accounts' rewrites conflict
+    let conflicting: Vec<String> = git::global_url_rewrites()?
+        .into_iter()
+        .filter(|(other, original)| original == URL_REWRITE_ORIGINAL && *other != base)
+        .map(|(other, _)| {
+            config
+                .accounts
+                .values()
+                .find(|a| url_rewrite_base(a) == other)
+                .map(|a| format!("account '{}'", a.name))
+                .unwrap_or(other)
+        })
+        .collect();
+
+    git::set_global_config_key(&format!("url.{}.insteadOf", base), URL_REWRITE_ORIGINAL)?;
+    println!(
+        "{} {} URLs now route through {} (account '{}')",
+        "✓".green().bold(),
+        URL_REWRITE_ORIGINAL.cyan(),
+        base.cyan(),
+        account.name
+    );
+    if !conflicting.is_empty() {
+        println!(
+            "{} Other rewrites also replace {} ({}); git applies only one of them, so remove the others with {}",
+            "⚠".yellow(),
+            URL_REWRITE_ORIGINAL,
+            conflicting.join(", "),
+            "git-switch remove-url-rewrite <account>".cyan()
+        );
+    }
+    Ok(())
+}
+
+/// Remove the global `insteadOf` rewrite installed by `setup-url-rewrite`
+pub fn remove_url_rewrite(config: &Config, name: &str) -> Result<()> {
+    let account = url_rewrite_account(config, name)?;
+    let key = format!("url.{}.insteadOf", url_rewrite_base(account));
+    if git::unset_global_config_key(&key)? {
+        println!(
+            "{} URL rewrite for account '{}' removed",
+            "✓".green().bold(),
+            account.name
+        );
+    } else {
+        println!(
+            "{} No URL rewrite is installed for account '{}'",
+            "ℹ".blue(),
+            account.name
+        );
+    }
+    Ok(())
+}
+
+/// Convert remote URL to HTTPS format; credentials in an HTTPS URL are kept
+fn convert_to_https(url: &str) -> Result<String> {
+    if url.starts_with("https://") {
+        return Ok(url.to_string());
+    }
+
+    if url.starts_with("git@") {
+        let parts: Vec<&str> = url.splitn(2, ':').collect();
+        if parts.len() == 2 {
+            let host = parts[0].trim_start_matches("git@");
+            let path = parts[1].trim_end_matches(".git");
+            return Ok(format!("https://{}/{}.git", host, path));
+        }
+    }
+
+    Err(GitSwitchError::Other(format!(
+        "Cannot convert URL to HTTPS: {}",
+        git::redact_url(url)
+    )))
+}
+
+/// Convert remote URL to SSH format, dropping any credentials since SSH authenticates with keys
+fn convert_to_ssh(url: &str) -> Result<String> {
+    if url.starts_with("git@") {
+        return Ok(url.to_string());
+    }
+
+    let (bare_url, _) = git::split_url_userinfo(url);
+    if bare_url.starts_with("https://") {
+        let url_without_protocol = bare_url.trim_start_matches("https://");
+        let parts: Vec<&str> = url_without_protocol.splitn(2, '/').collect();
+        if parts.len() == 2 {
+            let host = parts[0];
+            let path = parts[1].trim_end_matches(".git");
+            return Ok(format!("git@{}:{}.git", host, path));
+        }
+    }
+
+    Err(GitSwitchError::Other(format!(
+        "Cannot convert URL to SSH: {}",
+        git::redact_url(url)
+    )))
+}
+
+/// Handle whoami subcommand
+pub fn handle_whoami_subcommand(config: &Config, explain: bool) -> Result<()> {
+    println!("{}", "Current Git Identity".bold().cyan());
+    println!("{}", "─".repeat(25));
+
+    // Show global config
+    if let Ok((global_name, global_email)) = git::get_global_config() {
+        println!("\n🌍 Global Configuration:");
+        println!("  Name: {}", global_name);
+        println!("  Email: {}", global_email);
+        if let Some(active) = state::load_state()?.active_profile {
+            println!(
+                "  Active profile: {} {}",
+                active.name.green(),
+                format!("(account: {})", active.account).dimmed()
+            );
+        }
+
+        // Try to find matching account
+        if let Some(account) = account_for_email(config, &global_email) {
+            println!(
+                "  Account: {} {}",
+                account.name.green(),
+                "(matched)".dimmed()
+            );
+        } else {
+            println!(
+                "  Account: {} {}",
+                "None".yellow(),
+                "(no match found)".dimmed()
+            );
+        }
+    }
+
+    // Show local config if in a repository
+    if git::is_in_git_repository()? {
+        // The config reported below belongs to this repository, wherever in it we are
+        if let Ok(root) = git::get_repo_root() {
+            println!(
+                "\n📂 Repository: {}",
+                root.display().to_string().bright_white()
+            );
+            if let Some(main) = git::main_worktree_root() {
+                println!(
+                    "  Worktree of: {} {}",
+                    main.display(),
+                    "(repository config is shared with it)".dimmed()
+                );
+            }
+        }
+        if let Ok((local_name, local_email)) = git::get_local_config() {
+            println!("\n📁 Repository Configuration:");
+            println!("  Name: {}", local_name);
+            println!("  Email: {}", local_email);
+
+            if let Some(account) = account_for_email(config, &local_email) {
+                println!(
+                    "  Account: {} {}",
+                    account.name.green(),
+                    "(matched)".dimmed()
+                );
+            } else {
+                println!(
+                    "  Account: {} {}",
+                    "None".yellow(),
+                    "(no match found)".dimmed()
+                );
+            }
+        }
+
+        if let Ok(branch) = git::get_current_branch() {
+            println!("\n🌿 Branch: {}", branch);
+        }
+
+        // Show remote URL
+        if let Ok(remote_url) = git::get_remote_url("origin") {
+            println!("\n🔗 Remote URL:");
+            println!("  {}", git::redact_url(&remote_url));
+        }
+    } else {
+        println!("\n{} Not in a Git repository", "ℹ".blue());
+    }
+
+    if explain {
+        explain_whoami(config)?;
+    }
+
+    Ok(())
+}
+
+/// The identity Git commits with: the local email when set, otherwise the global one
+struct EffectiveIdentity {
+    email: String,
+    scope: &'static str,
+    name: Option<String>,
+    account: Option<String>,
+}
+
+fn effective_identity(config: &Config, in_repo: bool) -> Option<EffectiveIdentity> {
+    let non_empty = |value: Result<String>| value.ok().filter(|v| !v.is_empty());
+
+    let local_email = if in_repo {
+        non_empty(git::get_local_config_key("user.email"))
+    } else {
+        None
+    };
+    let (email, scope) = match local_email {
+        Some(email) => (email, "local"),
+        None => (
+            non_empty(git::get_global_config_key("user.email"))?,
+            "global",
+        ),
+    };
+    let name = if scope == "local" {
+        non_empty(git::get_local_config_key("user.name"))
+            .or_else(|| non_empty(git::get_global_config_key("user.name")))
+    } else {
+        non_empty(git::get_global_config_key("user.name"))
+    };
+    let account = account_for_email(config, &email).map(|account| account.name.clone());
+    Some(EffectiveIdentity {
+        email,
+        scope,
+        name,
+        account,
+    })
+}
+
+/// Print the effective identity on one line for shell prompts, e.g.
+/// `work <john@company.com> (local)`; prints nothing when no email is set.
+///
+/// The account name is shown when the email matches an account, otherwise `user.name`.
+pub fn handle_whoami_short(config: &Config) -> Result<()> {
+    let Some(identity) = effective_identity(config, git::is_in_git_repository()?) else {
+        return Ok(());
+    };
+    match identity.account.or(identity.name) {
+        Some(label) => println!("{} <{}> ({})", label, identity.email, identity.scope),
+        None => println!("<{}> ({})", identity.email, identity.scope),
+    }
+    Ok(())
+}
+
+/// Placeholders understood by `whoami --template`
+pub const WHOAMI_PLACEHOLDERS: &[&str] = &[
+    "account", "name", "email", "scope", "remote", "provider", "mismatch",
+];
+
+/// Split a `whoami --template` into literal text and placeholder names, rejecting
+/// unknown placeholders; a `{` without a closing `}` is literal text
+fn parse_whoami_template(template: &str) -> Result<Vec<(String, Option<String>)>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[open + 1..open + close];
+        if !WHOAMI_PLACEHOLDERS.contains(&placeholder) {
+            return Err(GitSwitchError::Other(format!(
+                "Unknown placeholder: {{{}}}. Supported: {}",
+                placeholder,
+                WHOAMI_PLACEHOLDERS
+                    .iter()
+                    .map(|name| format!("{{{}}}", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+        parts.push((rest[..open].to_string(), Some(placeholder.to_string())));
+        rest = &rest[open + close + 1..];
+    }
+    parts.push((rest.to_string(), None));
+    Ok(parts)
+}
+
+/// Print the effective identity through a user template such as
+/// `{scope}:{account} <{email}>`.
+///
+/// Placeholders with no value render empty: `{remote}` and `{provider}` outside
+/// a repository or without an `origin`, `{account}` when the email matches no
+/// account. `{mismatch}` is `!` when the account detected for the repository
+/// differs from the one in use.
+pub fn handle_whoami_template(config: &Config, template: &str) -> Result<()> {
+    let parts = parse_whoami_template(template)?;
+
+    let in_repo = git::is_in_git_repository()?;
+    let identity = effective_identity(config, in_repo);
+    let remote = if in_repo {
+        git::get_remote_url("origin").ok()
+    } else {
+        None
+    };
+    let mismatch = match (&identity, in_repo) {
+        (
+            Some(EffectiveIdentity {
+                account: Some(current),
+                ..
+            }),
+            true,
+        ) => detection::detect_account_from_remote(config)?
+            .is_some_and(|detected| detected != *current),
+        _ => false,
+    };
+
+    let mut line = String::new();
+    for (text, placeholder) in parts {
+        line.push_str(&text);
+        let value = match placeholder.as_deref() {
+            Some("account") => identity.as_ref().and_then(|i| i.account.clone()),
+            Some("name") => identity.as_ref().and_then(|i| i.name.clone()),
+            Some("email") => identity.as_ref().map(|i| i.email.clone()),
+            Some("scope") => identity.as_ref().map(|i| i.scope.to_string()),
+            Some("remote") => remote.as_deref().map(git::redact_url),
+            Some("provider") => remote
+                .as_deref()
+                .and_then(detection::provider_for_url)
+                .map(str::to_string),
+            Some("mismatch") => mismatch.then(|| "!".to_string()),
+            _ => None,
+        };
+        line.push_str(&value.unwrap_or_default());
+    }
+    println!("{}", line);
+    Ok(())
+}
+
+/// Print the accounts whose email matches `email`, including near misses that differ only by case
+fn explain_email_match<'a>(config: &'a Config, email: &str) -> Option<&'a Account> {
+    let mut exact: Vec<&Account> = config
+        .accounts
+        .values()
+        .filter(|acc| acc.matches_email(email))
+        .collect();
+    exact.sort_by(|a, b| a.name.cmp(&b.name));
+    let names: Vec<&str> = exact.iter().map(|acc| acc.name.as_str()).collect();
+
+    if names.is_empty() {
+        println!("     Accounts with this email: {}", "none".yellow());
+    } else {
+        println!(
+            "     Accounts with this email: {}",
+            names.join(", ").green()
+        );
+    }
+    if names.len() > 1 {
+        println!(
+            "     {} Several accounts share this email, so the match is ambiguous",
+            "⚠".yellow()
+        );
+    }
+    for near in config
+        .accounts
+        .values()
+        .filter(|acc| !acc.matches_email(email) && acc.email.eq_ignore_ascii_case(email))
+    {
+        println!(
+            "     {} Account '{}' has '{}', which differs only by case; emails must match exactly",
+            "⚠".yellow(),
+            near.name,
+            near.email
+        );
+    }
+
+    account_for_email(config, email)
+}
+
+/// Walk through how `whoami` arrived at its account matches
+fn explain_whoami(config: &Config) -> Result<()> {
+    println!("\n{}", "🔎 How the account was chosen".bold().cyan());
+    println!("{}", "─".repeat(30));
+
+    println!("  1. Global email (git config --global user.email):");
+    let global_account = match git::get_global_config() {
+        Ok((_, email)) => {
+            println!("     {}", email);
+            explain_email_match(config, &email)
+        }
+        Err(_) => {
+            println!("     {}", "not set".yellow());
+            None
+        }
+    };
+
+    if !git::is_in_git_repository()? {
+        println!("  2. Not in a Git repository, so only the global identity applies");
+        print_whoami_verdict(global_account, "global email", None);
+        return Ok(());
+    }
+
+    println!("  2. Repository email (git config --local user.name/user.email):");
+    let local_account = match git::get_local_config() {
+        Ok((_, email)) => {
+            println!("     {}", email);
+            Some(explain_email_match(config, &email))
+        }
+        Err(_) => {
+            println!(
+                "     {} (both name and email must be set locally); Git uses the global identity",
+                "not set".yellow()
+            );
+            None
+        }
+    };
+
+    println!("  3. Remote detection (origin URL):");
+    let detected = match git::get_remote_url("origin") {
+        Ok(url) => {
+            println!("     {}", git::redact_url(&url));
+            let (candidates, source) = detection::match_remote_url(config, &url);
+            match source {
+                Some(detection::MatchSource::UrlPattern) => println!(
+                    "     Matched by URL pattern: {}",
+                    candidates.join(", ").green()
+                ),
+                Some(detection::MatchSource::Provider) => println!(
+                    "     Matched by provider (no URL pattern matched): {}",
+                    candidates.join(", ").green()
+                ),
+                None => println!(
+                    "     {}",
+                    "No account's URL patterns or provider match this remote".yellow()
+                ),
+            }
+            Some(candidates)
+        }
+        Err(_) => {
+            println!("     {}", "No origin remote".yellow());
+            None
+        }
+    };
+
+    match local_account {
+        Some(account) => print_whoami_verdict(account, "repository email", detected.as_deref()),
+        None => print_whoami_verdict(global_account, "global email", detected.as_deref()),
+    }
+    Ok(())
+}
+
+/// Final line of `whoami --explain`, comparing the email match with remote detection
+fn print_whoami_verdict(account: Option<&Account>, source: &str, detected: Option<&[String]>) {
+    print!("  {} ", "Verdict:".bold());
+    match account {
+        Some(account) => println!("account '{}' (matched by {})", account.name.green(), source),
+        None => println!(
+            "{} (the {} matches no account)",
+            "no account".yellow(),
+            source
+        ),
+    }
+
+    let Some(detected) = detected else {
+        return;
+    };
+    match (account, detected) {
+        (_, []) => {}
+        (Some(account), detected) if detected.contains(&account.name) => {
+            println!("     Remote detection agrees");
+        }
+        (_, detected) => println!(
+            "     {} Remote detection suggests {}; run {} to apply it",
+            "⚠".yellow(),
+            detected.join(" or ").cyan(),
+            "git-switch detect --apply".bright_cyan()
+        ),
+    }
+}
+
+/// Handle config set subcommand
+pub fn handle_config_set_subcommand(
+    config: &mut Config,
+    key: SettingKey,
+    value: &str,
+) -> Result<()> {
+    match key {
+        SettingKey::DefaultProvider => {
+            let provider = value.to_lowercase();
+            if provider == "none" {
+                config.settings.default_provider = None;
+            } else {
+                let known = templates::get_templates();
+                if !known.contains_key(&provider) {
+                    let mut names: Vec<&String> = known.keys().collect();
+                    names.sort();
+                    let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+                    return Err(GitSwitchError::Other(format!(
+                        "Unknown provider: {}. Supported: {}, none",
+                        value,
+                        names.join(", ")
+                    )));
+                }
+                config.settings.default_provider = Some(provider);
+            }
+        }
+        SettingKey::StrictStartup => {
+            config.settings.strict_startup = config::parse_bool_setting(value)?;
+        }
+        SettingKey::StrictSsh => {
+            config.settings.strict_ssh = config::parse_bool_setting(value)?;
+        }
+        SettingKey::SshIncludeMode => {
+            config.settings.ssh_include_mode = config::parse_bool_setting(value)?;
+        }
+        SettingKey::UseSshAgent => {
+            config.settings.use_ssh_agent = config::parse_bool_setting(value)?;
+        }
+        SettingKey::ColoredOutput => {
+            config.settings.colored_output = config::parse_bool_setting(value)?;
+        }
+        SettingKey::ShowProgress => {
+            config.settings.show_progress = config::parse_bool_setting(value)?;
+        }
+        SettingKey::AutoMigrate => {
+            config.settings.auto_migrate = config::parse_bool_setting(value)?;
+        }
+        SettingKey::SecretCommand => {
+            config.settings.secret_command = if value.eq_ignore_ascii_case("none") {
+                None
+            } else if value.contains("{ref}") {
+                Some(value.to_string())
+            } else {
+                return Err(GitSwitchError::Other(format!(
+                    "Invalid secret command: {}. It must contain {{ref}}, e.g. \"op read {{ref}}\"",
+                    value
+                )));
+            };
+        }
+        SettingKey::PostSwitchCommand => {
+            config.settings.post_switch_command = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(value.to_string())
+            };
+        }
+        SettingKey::SshAddLifetime => {
+            config.settings.ssh_add_lifetime = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(value.parse().map_err(|_| {
+                    GitSwitchError::Other(format!(
+                        "Invalid lifetime: {}. Use a number of seconds or none",
+                        value
+                    ))
+                })?)
+            };
+        }
+        SettingKey::HistoryRetentionDays => {
+            config.settings.history_retention_days =
+                value.parse().ok().filter(|days| *days > 0).ok_or_else(|| {
+                    GitSwitchError::Other(format!(
+                        "Invalid retention: {}. Use a number of days greater than 0",
+                        value
+                    ))
+                })?;
+        }
     }
 
-    let account = config.accounts.remove(name).unwrap();
-
-    // Remove SSH config entry
-    ssh::remove_ssh_config_entry(name)?;
-
     config::save_config(config)?;
-
     println!(
-        "{} Account '{}' removed successfully",
+        "{} Setting '{}' updated to '{}'",
         "✓".green().bold(),
-        name
+        key.as_str().cyan(),
+        value
     );
-
-    // Ask if user wants to remove SSH key file
-    if !no_prompt {
-        let remove_key = Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-            .with_prompt("Remove SSH key file as well?")
-            .default(false)
-            .interact()?;
-
-        if remove_key {
-            let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
-            if expanded_key_path.exists() {
-                fs::remove_file(&expanded_key_path)?;
-                println!("🗑️ SSH key file removed");
-            }
-        }
-    }
-
     Ok(())
 }
 
-/// Handle account subcommand (apply to current repo)
-pub fn handle_account_subcommand(config: &Config, name: &str) -> Result<()> {
-    let account = find_account(config, name).ok_or_else(|| GitSwitchError::AccountNotFound {
-        name: name.to_string(),
-    })?;
+/// Handle migrate subcommand: report pending config migrations and apply them on demand
+pub fn handle_migrate_subcommand(config: &mut Config, apply: bool) -> Result<()> {
+    let config_path = config::get_config_file_path()?;
+    let raw = if config_path.exists() {
+        toml::from_str::<toml::Value>(&utils::read_document(&config_path)?).ok()
+    } else {
+        None
+    };
 
-    // Check if we're in a git repository
-    if !git::is_in_git_repository()? {
-        return Err(GitSwitchError::NotInGitRepository);
+    let mut migrated = config.clone();
+    let changes = config::migrate_config(&mut migrated, raw.as_ref());
+    if changes.is_empty() {
+        println!(
+            "{} Configuration is up to date (version {})",
+            "✓".green().bold(),
+            config.version
+        );
+        return Ok(());
     }
 
     println!(
-        "🔧 Applying account '{}' to current repository",
-        account.name.cyan()
+        "📋 {} {}",
+        "Migration for".bold(),
+        config_path.display().to_string().bright_white()
     );
+    for change in &changes {
+        println!("  • {}", change);
+    }
 
-    git::set_local_config(&account.username, &account.email)?;
-
-    let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
-    if expanded_key_path.exists() {
-        git::set_ssh_command(&account.ssh_key_path)?;
-        println!("🔑 SSH configuration updated for this repository");
+    if apply {
+        config::save_config(&migrated)?;
+        *config = migrated;
+        println!(
+            "{} Configuration migrated to version {}",
+            "✓".green().bold(),
+            config.version
+        );
+    } else {
+        println!(
+            "\n{} Run {} to apply these changes",
+            "ℹ".blue(),
+            "git-switch migrate --apply".cyan()
+        );
     }
+    Ok(())
+}
 
-    // Record repository usage analytics
-    if let Err(e) = analytics::record_repository_usage(&account.name) {
-        tracing::warn!("Failed to record repository usage analytics: {}", e);
+/// Resolved locations of the files git-switch reads and writes
+#[derive(Debug, Serialize)]
+struct ConfigPaths {
+    config: PathBuf,
+    profiles: PathBuf,
+    analytics: PathBuf,
+    history: PathBuf,
+    state: PathBuf,
+    backups: PathBuf,
+    ssh_config: PathBuf,
+    /// Where host entries are written; the main SSH config unless include mode is on
+    ssh_hosts: PathBuf,
+}
+
+/// Handle config path subcommand
+pub fn handle_config_path_subcommand(config: &Config, json: bool) -> Result<()> {
+    let paths = ConfigPaths {
+        config: config::get_config_file_path()?,
+        profiles: config.get_profiles_path()?,
+        analytics: analytics::get_analytics_file_path()?,
+        history: analytics::get_history_file_path()?,
+        state: state::get_state_file_path()?,
+        backups: crate::backup::get_backups_dir()?,
+        ssh_config: ssh::get_ssh_config_file_path()?,
+        ssh_hosts: ssh::managed_ssh_config_path(config.settings.ssh_include_mode)?,
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&paths).map_err(GitSwitchError::Json)?
+        );
+        return Ok(());
     }
 
-    println!(
-        "{} Repository configured for account '{}'",
-        "✓".green().bold(),
-        account.name.cyan()
-    );
+    for (icon, label, path) in [
+        ("⚙️ ", "Config file:", &paths.config),
+        ("👥", "Profiles:", &paths.profiles),
+        ("📊", "Analytics:", &paths.analytics),
+        ("📜", "History:", &paths.history),
+        ("🧭", "State:", &paths.state),
+        ("💾", "Backups:", &paths.backups),
+        ("🔐", "SSH config:", &paths.ssh_config),
+        ("🌐", "SSH hosts:", &paths.ssh_hosts),
+    ] {
+        let missing = if path.exists() {
+            String::new()
+        } else {
+            format!(" {}", "(not created yet)".dimmed())
+        };
+        println!(
+            "{} {:<13} {}{}",
+            icon,
+            label.bold(),
+            path.display().to_string().bright_white(),
+            missing
+        );
+    }
     Ok(())
 }
 
-/// Handle remote subcommand (convert between HTTPS and SSH)
-pub fn handle_remote_subcommand(https: bool, ssh: bool) -> Result<()> {
-    if !git::is_in_git_repository()? {
-        return Err(GitSwitchError::NotInGitRepository);
-    }
+/// The editor for `config edit`: `$VISUAL`, then `$EDITOR`, then the platform default
+fn resolve_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
 
-    let current_url = git::get_remote_url("origin")?;
-    println!("Current remote URL: {}", current_url.cyan());
+/// Open the config file in an editor and keep the edit only if it still loads
+/// and passes validation; otherwise the file from before the edit is restored
+pub fn handle_config_edit_subcommand(config: &Config) -> Result<()> {
+    let config_path = config::get_config_file_path()?;
+    if !config_path.exists() {
+        config::save_config(config)?;
+    }
+    let original = fs::read_to_string(&config_path)?;
+    let backup_path = PathBuf::from(format!("{}.pre-edit", config_path.display()));
+    fs::write(&backup_path, &original)?;
 
-    let new_url = if https {
-        convert_to_https(&current_url)?
-    } else if ssh {
-        convert_to_ssh(&current_url)?
+    // The editor may carry arguments (`code --wait`), so it goes through the shell
+    let editor = resolve_editor();
+    let command_line = format!(
+        "{} {}",
+        editor,
+        utils::shell_quote(&config_path.to_string_lossy())
+    );
+    let status = if cfg!(windows) {
+        std::process::Command::new("cmd")
+            .args(["/C", &command_line])
+            .status()
     } else {
-        return Err(GitSwitchError::Other(
-            "Specify either --https or --ssh".to_string(),
-        ));
+        std::process::Command::new("sh")
+            .args(["-c", &command_line])
+            .status()
     };
 
-    git::set_remote_url("origin", &new_url)?;
-    println!(
-        "{} Remote URL updated to: {}",
-        "✓".green().bold(),
-        new_url.cyan()
-    );
-    Ok(())
-}
+    let outcome = match status {
+        Ok(status) if status.success() => {
+            config::load_config().and_then(|edited| crate::backup::validate_config(&edited))
+        }
+        Ok(status) => Err(GitSwitchError::Other(format!(
+            "Editor '{}' exited with {}",
+            editor, status
+        ))),
+        Err(e) => Err(GitSwitchError::Other(format!(
+            "Could not start editor '{}': {}",
+            editor, e
+        ))),
+    };
 
-/// Convert remote URL to HTTPS format
-fn convert_to_https(url: &str) -> Result<String> {
-    if url.starts_with("https://") {
-        return Ok(url.to_string());
+    if let Err(e) = outcome {
+        fs::copy(&backup_path, &config_path)?;
+        fs::remove_file(&backup_path)?;
+        eprintln!(
+            "{} The edit was rejected and the previous config restored",
+            "⚠".yellow()
+        );
+        return Err(e);
     }
+    fs::remove_file(&backup_path)?;
 
-    if url.starts_with("git@") {
-        let parts: Vec<&str> = url.splitn(2, ':').collect();
-        if parts.len() == 2 {
-            let host = parts[0].trim_start_matches("git@");
-            let path = parts[1].trim_end_matches(".git");
-            return Ok(format!("https://{}/{}.git", host, path));
-        }
+    if fs::read_to_string(&config_path)? == original {
+        println!("{} No changes made", "ℹ".blue());
+    } else {
+        println!(
+            "{} Config saved and validated: {}",
+            "✓".green(),
+            config_path.display()
+        );
     }
+    Ok(())
+}
 
-    Err(GitSwitchError::Other(format!(
-        "Cannot convert URL to HTTPS: {}",
-        url
-    )))
+/// Identity audit entry for one repository
+#[derive(Debug, Serialize)]
+struct RepoIdentity {
+    path: PathBuf,
+    user_name: Option<String>,
+    user_email: Option<String>,
+    account: Option<String>,
+    suggested_account: Option<String>,
+    mismatch: bool,
 }
 
-/// Convert remote URL to SSH format
-fn convert_to_ssh(url: &str) -> Result<String> {
-    if url.starts_with("git@") {
-        return Ok(url.to_string());
+/// Handle whoami --all-repos: audit the identity of every repository below the current directory
+pub fn handle_whoami_all_repos(config: &Config, max_depth: usize, json: bool) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let mut repos = repository::find_git_repositories(&root, max_depth, false)?;
+    repos.sort();
+
+    let identities: Vec<RepoIdentity> = repos
+        .into_iter()
+        .map(|path| {
+            let user_name = git::get_local_config_key_in(&path, "user.name").ok();
+            let user_email = git::get_local_config_key_in(&path, "user.email").ok();
+            let account = user_email.as_ref().and_then(|email| {
+                config
+                    .accounts
+                    .values()
+                    .find(|acc| acc.matches_email(email))
+                    .map(|acc| acc.name.clone())
+            });
+            let suggested_account = git::get_local_config_key_in(&path, "remote.origin.url")
+                .ok()
+                .and_then(|url| detection::detect_account_for_remote_url(config, &url).ok())
+                .flatten();
+            let mismatch = matches!(
+                (&account, &suggested_account),
+                (Some(current), Some(suggested)) if current != suggested
+            );
+            RepoIdentity {
+                path,
+                user_name,
+                user_email,
+                account,
+                suggested_account,
+                mismatch,
+            }
+        })
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&identities).map_err(GitSwitchError::Json)?
+        );
+        return Ok(());
     }
 
-    if url.starts_with("https://") {
-        let url_without_protocol = url.trim_start_matches("https://");
-        let parts: Vec<&str> = url_without_protocol.splitn(2, '/').collect();
-        if parts.len() == 2 {
-            let host = parts[0];
-            let path = parts[1].trim_end_matches(".git");
-            return Ok(format!("git@{}:{}.git", host, path));
-        }
+    if identities.is_empty() {
+        println!("{} No Git repositories found", "ℹ".blue());
+        return Ok(());
     }
 
-    Err(GitSwitchError::Other(format!(
-        "Cannot convert URL to SSH: {}",
-        url
-    )))
-}
+    // One tab-separated line per repository so the output can be sorted and grepped
+    for identity in &identities {
+        let status = if identity.mismatch {
+            format!(
+                "MISMATCH (expected {})",
+                identity.suggested_account.as_deref().unwrap_or("unknown")
+            )
+            .red()
+            .to_string()
+        } else {
+            "ok".green().to_string()
+        };
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            identity
+                .path
+                .strip_prefix(&root)
+                .unwrap_or(&identity.path)
+                .display(),
+            identity.user_name.as_deref().unwrap_or("-"),
+            identity.user_email.as_deref().unwrap_or("-"),
+            identity.account.as_deref().unwrap_or("unknown"),
+            status
+        );
+    }
 
-/// Handle whoami subcommand
-pub fn handle_whoami_subcommand(config: &Config) -> Result<()> {
-    println!("{}", "Current Git Identity".bold().cyan());
-    println!("{}", "─".repeat(25));
+    Ok(())
+}
 
-    // Show global config
-    if let Ok((global_name, global_email)) = git::get_global_config() {
-        println!("\n🌍 Global Configuration:");
-        println!("  Name: {}", global_name);
-        println!("  Email: {}", global_email);
+/// Hashes listed per unknown email before `audit-commits` summarizes the rest
+const AUDIT_HASHES_SHOWN: usize = 10;
 
-        // Try to find matching account
-        if let Some(account) = config
-            .accounts
-            .values()
-            .find(|acc| acc.email == global_email)
-        {
-            println!(
-                "  Account: {} {}",
-                account.name.green(),
-                "(matched)".dimmed()
-            );
-        } else {
-            println!(
-                "  Account: {} {}",
-                "None".yellow(),
-                "(no match found)".dimmed()
-            );
-        }
+/// List commits whose author email matches no account, grouped by email.
+///
+/// `path` inside a repository audits that repository; otherwise every repository
+/// found below `path` (up to `max_depth`) is audited.
+pub fn audit_commits(
+    config: &Config,
+    path: &std::path::Path,
+    since: Option<&str>,
+    max_depth: usize,
+) -> Result<()> {
+    let mut repos = match git::repository_root_of(path) {
+        Some(root) => vec![root],
+        None => repository::find_git_repositories(path, max_depth, false)?,
+    };
+    repos.sort();
+    if repos.is_empty() {
+        println!("{} No Git repositories found", "ℹ".blue());
+        return Ok(());
     }
 
-    // Show local config if in a repository
-    if git::is_in_git_repository()? {
-        if let Ok((local_name, local_email)) = git::get_local_config() {
-            println!("\n📁 Repository Configuration:");
-            println!("  Name: {}", local_name);
-            println!("  Email: {}", local_email);
+    let mut total_commits = 0;
+    let mut unknown_commits = 0;
+    let mut unknown_emails = std::collections::BTreeSet::new();
+    for repo in &repos {
+        let commits = git::commit_author_emails_in(repo, since)?;
+        total_commits += commits.len();
 
-            if let Some(account) = config
+        let mut by_email: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (hash, email) in commits {
+            if !config
                 .accounts
                 .values()
-                .find(|acc| acc.email == local_email)
+                .any(|acc| acc.matches_email(&email))
             {
+                by_email.entry(email).or_default().push(hash);
+            }
+        }
+        if by_email.is_empty() {
+            continue;
+        }
+
+        println!("\n📁 {}", repo.display().to_string().bold());
+        for (email, hashes) in &by_email {
+            println!(
+                "  {} {} {}",
+                "✗".red().bold(),
+                email.yellow(),
+                format!(
+                    "({} commit{})",
+                    hashes.len(),
+                    if hashes.len() == 1 { "" } else { "s" }
+                )
+                .dimmed()
+            );
+            for hash in hashes.iter().take(AUDIT_HASHES_SHOWN) {
+                println!("      {}", &hash[..hash.len().min(12)]);
+            }
+            if hashes.len() > AUDIT_HASHES_SHOWN {
                 println!(
-                    "  Account: {} {}",
-                    account.name.green(),
-                    "(matched)".dimmed()
-                );
-            } else {
-                println!(
-                    "  Account: {} {}",
-                    "None".yellow(),
-                    "(no match found)".dimmed()
+                    "      {}",
+                    format!("... and {} more", hashes.len() - AUDIT_HASHES_SHOWN).dimmed()
                 );
             }
+            unknown_commits += hashes.len();
         }
+        unknown_emails.extend(by_email.into_keys());
+    }
 
-        // Show remote URL
-        if let Ok(remote_url) = git::get_remote_url("origin") {
-            println!("\n🔗 Remote URL:");
-            println!("  {}", remote_url);
-        }
+    if unknown_commits == 0 {
+        println!(
+            "{} All {} commits in {} repositories were authored by a configured account",
+            "✓".green().bold(),
+            total_commits,
+            repos.len()
+        );
     } else {
-        println!("\n{} Not in a Git repository", "ℹ".blue());
+        println!(
+            "\n{} {} of {} commits in {} repositories were authored by {} unknown emails",
+            "⚠".yellow().bold(),
+            unknown_commits,
+            total_commits,
+            repos.len(),
+            unknown_emails.len()
+        );
     }
-
     Ok(())
 }
 
+/// Number of SSH authentication tests run at once
+const AUTH_TEST_WORKERS: usize = 4;
+
+/// Outcome of testing one account's SSH authentication
+enum AuthOutcome {
+    Passed,
+    Failed(String),
+    /// The provider could not be reached, so registration is unknown
+    Offline(String),
+    Skipped(&'static str),
+}
+
+/// Result row for `auth test`
+struct AuthTestResult {
+    account: String,
+    host: String,
+    outcome: AuthOutcome,
+    latency: Option<std::time::Duration>,
+}
+
+/// Run `auth test` for every account, keyed by account name, for `list --check-providers`
+fn check_key_registration(config: &Config) -> Result<HashMap<String, AuthOutcome>> {
+    let accounts = sorted_accounts(config);
+    if accounts
+        .iter()
+        .any(|account| !account.ssh_key_path.is_empty())
+    {
+        utils::require_openssh_program("ssh")?;
+    }
+    println!(
+        "{} Checking key registration for {} accounts...",
+        "🔎".bold(),
+        accounts.len()
+    );
+    Ok(run_auth_tests(&accounts)
+        .into_iter()
+        .map(|result| (result.account, result.outcome))
+        .collect())
+}
+
+/// Registration indicator shown next to an account in `list --check-providers`
+fn registration_tag(outcome: &AuthOutcome) -> ColoredString {
+    match outcome {
+        AuthOutcome::Passed => "🔓 registered".green(),
+        AuthOutcome::Failed(_) => "🔒 not registered".red(),
+        AuthOutcome::Offline(_) => "skipped (offline)".dimmed(),
+        AuthOutcome::Skipped(reason) => format!("skipped ({})", reason).dimmed(),
+    }
+}
+
+/// Test every account's authentication, `AUTH_TEST_WORKERS` at a time, sorted by account name
+fn run_auth_tests(accounts: &[&Account]) -> Vec<AuthTestResult> {
+    // Workers pull accounts from a shared cursor; output is only printed once all finish
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let mut results: Vec<AuthTestResult> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..AUTH_TEST_WORKERS.min(accounts.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        let Some(account) = accounts.get(index) else {
+                            break;
+                        };
+                        done.push(test_account_auth(account));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+    results.sort_by(|a, b| a.account.cmp(&b.account));
+    results
+}
+
 /// Handle auth test subcommand
-pub fn handle_auth_test_subcommand(config: &Config) -> Result<()> {
+pub fn handle_auth_test_subcommand(config: &Config, group: Option<&str>) -> Result<()> {
+    let accounts = match group {
+        Some(group) => accounts_in_group(config, group)?,
+        None => {
+            let mut accounts: Vec<&Account> = config.accounts.values().collect();
+            accounts.sort_by(|a, b| a.name.cmp(&b.name));
+            accounts
+        }
+    };
     println!("{}", "Testing SSH Authentication".bold().cyan());
     println!("{}", "─".repeat(30));
 
-    for (name, account) in &config.accounts {
-        print!("Testing account '{}' ... ", name.cyan());
-        io::stdout().flush()?;
+    if accounts.is_empty() {
+        println!("{} No accounts configured", "ℹ".blue());
+        return Ok(());
+    }
+    if accounts
+        .iter()
+        .any(|account| !account.ssh_key_path.is_empty())
+    {
+        utils::require_openssh_program("ssh")?;
+    }
+    println!(
+        "Testing {} accounts ({} at a time)...",
+        accounts.len(),
+        AUTH_TEST_WORKERS.min(accounts.len())
+    );
 
-        let expanded_key_path = utils::expand_path(&account.ssh_key_path)?;
-        if !expanded_key_path.exists() {
-            println!("{} (key not found)", "✗".red());
-            continue;
-        }
+    let results = run_auth_tests(&accounts);
+    print_auth_results(&results);
 
-        // Test SSH connection based on provider
-        let test_result = match account.provider.as_deref() {
-            Some("github") => test_ssh_connection("git@github.com"),
-            Some("gitlab") => test_ssh_connection("git@gitlab.com"),
-            Some("bitbucket") => test_ssh_connection("git@bitbucket.org"),
-            _ => test_ssh_connection("git@github.com"), // Default to GitHub
-        };
+    let failed = results
+        .iter()
+        .filter(|r| matches!(r.outcome, AuthOutcome::Failed(_) | AuthOutcome::Offline(_)))
+        .count();
+    if failed > 0 {
+        return Err(GitSwitchError::SshCommand {
+            command: "ssh -T".to_string(),
+            message: format!(
+                "{} of {} accounts failed authentication",
+                failed,
+                results.len()
+            ),
+        });
+    }
+    Ok(())
+}
 
-        match test_result {
-            Ok(_) => println!("{}", "✓".green()),
-            Err(_) => println!("{}", "✗".red()),
-        }
+/// Test one account against its provider's host with only its own key
+fn test_account_auth(account: &Account) -> AuthTestResult {
+    test_account_key_auth(account, &account.ssh_key_path)
+}
+
+/// Test one of an account's keys against its provider's host
+fn test_account_key_auth(account: &Account, key_path: &str) -> AuthTestResult {
+    let host = format!("git@{}", account.ssh_hostname());
+    let result = |outcome, latency| AuthTestResult {
+        account: account.name.clone(),
+        host: host.clone(),
+        outcome,
+        latency,
+    };
+
+    if key_path.is_empty() {
+        return result(AuthOutcome::Skipped("no SSH key"), None);
     }
+    let expanded_key_path = match utils::expand_path(key_path) {
+        Ok(path) if path.exists() => path,
+        _ => return result(AuthOutcome::Failed("key not found".to_string()), None),
+    };
 
-    Ok(())
+    let started = std::time::Instant::now();
+    let outcome = match test_ssh_connection(&host, &expanded_key_path) {
+        Ok(()) => AuthOutcome::Passed,
+        Err(e) if is_connection_error(&e.to_string()) => AuthOutcome::Offline(e.to_string()),
+        Err(e) => AuthOutcome::Failed(e.to_string()),
+    };
+    result(outcome, Some(started.elapsed()))
+}
+
+/// Whether ssh failed to reach the host at all, as opposed to rejecting the key
+fn is_connection_error(message: &str) -> bool {
+    const MARKERS: [&str; 6] = [
+        "Could not resolve hostname",
+        "Connection timed out",
+        "Operation timed out",
+        "Connection refused",
+        "Network is unreachable",
+        "No route to host",
+    ];
+    MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Render the aggregated `auth test` table
+fn print_auth_results(results: &[AuthTestResult]) {
+    let name_width = results
+        .iter()
+        .map(|r| r.account.len())
+        .chain(["ACCOUNT".len()])
+        .max()
+        .unwrap_or(0);
+    let host_width = results
+        .iter()
+        .map(|r| r.host.len())
+        .chain(["HOST".len()])
+        .max()
+        .unwrap_or(0);
+
+    println!(
+        "\n{}",
+        format!(
+            "{:<name_width$}  {:<host_width$}  {:<8}  {}",
+            "ACCOUNT", "HOST", "RESULT", "LATENCY"
+        )
+        .bold()
+    );
+    for r in results {
+        let (label, color_label) = match &r.outcome {
+            AuthOutcome::Passed => ("✓ ok", "✓ ok".green()),
+            AuthOutcome::Failed(_) | AuthOutcome::Offline(_) => ("✗ failed", "✗ failed".red()),
+            AuthOutcome::Skipped(_) => ("- skipped", "- skipped".dimmed()),
+        };
+        let latency = r
+            .latency
+            .map(|d| format!("{}ms", d.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        // Pad the plain text first so color codes don't break the alignment
+        let padding = " ".repeat(8usize.saturating_sub(label.chars().count()));
+        println!(
+            "{:<name_width$}  {:<host_width$}  {}{}  {}",
+            r.account.cyan(),
+            r.host,
+            color_label,
+            padding,
+            latency.dimmed()
+        );
+        match &r.outcome {
+            AuthOutcome::Failed(reason) | AuthOutcome::Offline(reason) => {
+                println!("    {}", reason.trim().dimmed())
+            }
+            AuthOutcome::Skipped(reason) => println!("    {}", reason.dimmed()),
+            AuthOutcome::Passed => {}
+        }
+    }
 }
 
-fn test_ssh_connection(host: &str) -> Result<()> {
-    let output = std::process::Command::new("ssh")
-        .args([
+fn test_ssh_connection(host: &str, key_path: &std::path::Path) -> Result<()> {
+    let key_path = key_path
+        .to_str()
+        .ok_or_else(|| GitSwitchError::InvalidPath(key_path.to_path_buf()))?;
+    let output = utils::run_command_with_full_output(
+        "ssh",
+        &[
             "-T",
+            "-i",
+            key_path,
+            "-o",
+            "IdentitiesOnly=yes",
+            "-o",
+            "BatchMode=yes",
             "-o",
             "ConnectTimeout=5",
             "-o",
             "StrictHostKeyChecking=no",
             host,
-        ])
-        .output()?;
+        ],
+        None,
+    )?;
 
     // For Git hosting services, successful authentication often returns with exit code 1
     // but includes specific messages in stderr
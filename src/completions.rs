@@ -1,14 +1,51 @@
-use clap::Command;
+use clap::{Command, ValueEnum};
 use clap_complete::{Shell, generate};
+use clap_complete_nushell::Nushell;
 use std::io;
 
+/// Shells completions can be generated for: clap's built-in ones plus nushell,
+/// which has its own generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+    Elvish,
+    Nushell,
+}
+
+impl CompletionShell {
+    /// The matching built-in clap shell, if any
+    fn builtin(self) -> Option<Shell> {
+        match self {
+            CompletionShell::Bash => Some(Shell::Bash),
+            CompletionShell::Zsh => Some(Shell::Zsh),
+            CompletionShell::Fish => Some(Shell::Fish),
+            CompletionShell::PowerShell => Some(Shell::PowerShell),
+            CompletionShell::Elvish => Some(Shell::Elvish),
+            CompletionShell::Nushell => None,
+        }
+    }
+}
+
 /// Generate shell completion scripts
-pub fn generate_completions(shell: Shell, cmd: &mut Command) {
-    generate(shell, cmd, "git-switch", &mut io::stdout());
+pub fn generate_completions(shell: CompletionShell, cmd: &mut Command) {
+    match shell.builtin() {
+        Some(builtin) => generate(builtin, cmd, "git-switch", &mut io::stdout()),
+        None => generate(Nushell, cmd, "git-switch", &mut io::stdout()),
+    }
 }
 
 /// Print installation instructions for each shell
-pub fn print_installation_instructions(shell: Shell) {
+pub fn print_installation_instructions(shell: CompletionShell) {
+    let Some(shell) = shell.builtin() else {
+        println!("# To install nushell completions, save them and source the file from config.nu:");
+        println!("# git-switch completions nushell | save -f ~/.config/nushell/git-switch.nu");
+        println!("# source ~/.config/nushell/git-switch.nu");
+        return;
+    };
     match shell {
         Shell::Bash => {
             println!("# To install bash completions, add the following to your ~/.bashrc:");
@@ -1,16 +1,30 @@
 use crate::error::{GitSwitchError, Result};
-use crate::utils::{ensure_parent_dir_exists, read_file_content, write_file_content};
+use crate::utils::{self, ensure_parent_dir_exists, home_dir, read_document, write_file_content};
+use crate::validation::validate_login;
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const CONFIG_FILE_NAME_TOML: &str = ".git-switch-config.toml";
 const CONFIG_FILE_NAME_JSON: &str = ".git-switch-config.json"; // Legacy support
+const PROFILES_FILE_NAME: &str = "profiles.toml";
+/// Environment variable overriding the config file location (set by `--config`)
+pub const CONFIG_PATH_ENV: &str = "GIT_SWITCH_CONFIG";
+/// Environment variable disabling every SSH agent interaction (set by `--no-ssh-agent`)
+pub const NO_SSH_AGENT_ENV: &str = "GIT_SWITCH_NO_SSH_AGENT";
+/// Environment variable disabling automatic config migration (set by `--no-migrate`)
+pub const NO_MIGRATE_ENV: &str = "GIT_SWITCH_NO_MIGRATE";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Account {
     pub name: String,
-    pub username: String,
+    /// Name written to `user.name`, e.g. "John Doe"
+    #[serde(default)]
+    pub git_name: String,
+    /// Provider handle, e.g. "jdoe", matched against the owner in remote URLs
+    #[serde(default)]
+    pub login: String,
     pub email: String,
     pub ssh_key_path: String,
     /// Optional SSH key paths for multiple keys per account
@@ -22,10 +36,84 @@ pub struct Account {
     /// Account groups/organizations
     #[serde(default)]
     pub groups: Vec<String>,
+    /// Remote URL substrings or globs that identify this account
+    #[serde(default)]
+    pub url_patterns: Vec<String>,
+    /// Seconds the key stays in the SSH agent after `use` (overrides the global setting)
+    #[serde(default)]
+    pub ssh_add_lifetime: Option<u64>,
+    /// Password manager reference for the key passphrase, resolved with `secret_command`
+    #[serde(default)]
+    pub passphrase_ref: Option<String>,
+    /// Isolate `core.sshCommand` from `~/.ssh/config` (overrides the global setting)
+    #[serde(default)]
+    pub strict_ssh: Option<bool>,
+    /// Branch name set as `init.defaultBranch` when the account is used globally
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Earlier emails still recognized as this account; `user.email` always uses `email`
+    #[serde(default)]
+    pub additional_emails: Vec<String>,
+    /// File set as `commit.template` when the account is applied to a repository
+    #[serde(default)]
+    pub commit_template: Option<String>,
+    /// Directory set as `core.hooksPath` when the account is applied to a repository
+    #[serde(default)]
+    pub hooks_path: Option<String>,
+    /// Organization email domain, e.g. "company.com"; any committer email in it suggests this account
+    #[serde(default)]
+    pub email_domain: Option<String>,
+    /// Unique identifier used in the default key filename and the SSH host alias
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// SSH host name, e.g. a GitHub Enterprise server; defaults to the provider's host
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Allowlisted Git settings such as `push.default`, applied to repositories with the identity
+    #[serde(default)]
+    pub git_settings: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+impl Account {
+    /// The account's slug; accounts created before slugs were stored derive it from the name
+    pub fn slug(&self) -> String {
+        self.slug
+            .clone()
+            .unwrap_or_else(|| self.name.replace(" ", "_").to_lowercase())
+    }
+
+    /// Whether `email` is this account's primary or one of its additional emails
+    pub fn matches_email(&self, email: &str) -> bool {
+        self.email == email || self.additional_emails.iter().any(|e| e == email)
+    }
+
+    /// Whether `email` belongs to the account's email domain or one of its subdomains
+    pub fn matches_email_domain(&self, email: &str) -> bool {
+        let (Some(domain), Some((_, email_domain))) = (&self.email_domain, email.rsplit_once('@'))
+        else {
+            return false;
+        };
+        let email_domain = email_domain.to_lowercase();
+        email_domain == *domain || email_domain.ends_with(&format!(".{}", domain))
+    }
+
+    /// Host name the account's SSH entry connects to: its own host, else its
+    /// provider's, else github.com
+    pub fn ssh_hostname(&self) -> String {
+        self.host
+            .clone()
+            .or_else(|| {
+                self.provider
+                    .as_deref()
+                    .and_then(crate::templates::provider_host)
+            })
+            .unwrap_or_else(|| "github.com".to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
+    #[serde(deserialize_with = "deserialize_accounts")]
     pub accounts: HashMap<String, Account>,
     /// Configuration version for migration purposes
     #[serde(default = "default_config_version")]
@@ -35,19 +123,156 @@ pub struct Config {
     pub settings: GlobalSettings,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// An account as stored by any version: configs written before `git_name` and
+/// `login` were split have a single `username` that served as both
+#[derive(Deserialize)]
+struct StoredAccount {
+    #[serde(flatten)]
+    account: Account,
+    #[serde(default)]
+    username: Option<String>,
+}
+
+/// Read the accounts table, filling `git_name` and `login` from a legacy
+/// `username`; `login` stays empty when the username is not a valid handle
+fn deserialize_accounts<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, Account>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let stored: HashMap<String, StoredAccount> = HashMap::deserialize(deserializer)?;
+    stored
+        .into_iter()
+        .map(
+            |(
+                name,
+                StoredAccount {
+                    mut account,
+                    username,
+                },
+            )| {
+                if let Some(username) = username {
+                    if account.git_name.is_empty() {
+                        account.git_name = username.clone();
+                    }
+                    // A display name such as "John Doe" is not a provider handle
+                    if account.login.is_empty() && validate_login(&username).is_ok() {
+                        account.login = username;
+                    }
+                }
+                if account.git_name.is_empty() {
+                    return Err(serde::de::Error::missing_field("git_name"));
+                }
+                Ok((name, account))
+            },
+        )
+        .collect()
+}
+
+/// Normalize an account name for filenames and host aliases: lowercase ASCII
+/// letters, digits and hyphens, with any other run of characters as one `_`
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_matches('_');
+    if slug.is_empty() {
+        "account".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// A fresh config is created at the current version, in SSH include mode
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            accounts: HashMap::new(),
+            version: default_config_version(),
+            settings: GlobalSettings {
+                ssh_include_mode: true,
+                ..GlobalSettings::default()
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GlobalSettings {
     /// Default provider for new accounts
     pub default_provider: Option<String>,
     /// Auto-detect account based on remote URL
     #[serde(default)]
     pub auto_detect_account: bool,
-    /// Use colored output
+    /// Use colored output when neither a flag nor NO_COLOR/CLICOLOR_FORCE decides
     #[serde(default = "default_true")]
     pub colored_output: bool,
-    /// Show progress indicators
+    /// Show progress indicators (on a terminal) unless `--progress` says otherwise
     #[serde(default = "default_true")]
     pub show_progress: bool,
+    /// Profile whose default account is used when detection finds no account
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    /// Treat startup validation warnings (e.g. no SSH agent) as errors
+    #[serde(default)]
+    pub strict_startup: bool,
+    /// Default seconds a key stays in the SSH agent after `use`
+    #[serde(default)]
+    pub ssh_add_lifetime: Option<u64>,
+    /// Command template printing a key passphrase, e.g. `op read {ref}`
+    #[serde(default)]
+    pub secret_command: Option<String>,
+    /// Emit `core.sshCommand` with `-F /dev/null -o IdentitiesOnly=yes` so only the account key is used
+    #[serde(default)]
+    pub strict_ssh: bool,
+    /// Load keys into the SSH agent on `use`; when false git-switch never talks to the agent
+    #[serde(default = "default_true")]
+    pub use_ssh_agent: bool,
+    /// Shell command run after a successful switch, e.g. `notify-send {account} {scope}`
+    #[serde(default)]
+    pub post_switch_command: Option<String>,
+    /// Keep host entries in ~/.ssh/git-switch.config, included from ~/.ssh/config,
+    /// instead of editing ~/.ssh/config itself. On for new installs; configs from
+    /// older versions keep writing to ~/.ssh/config until it is turned on.
+    #[serde(default)]
+    pub ssh_include_mode: bool,
+    /// Days switch history is kept before `history prune` (or a full log) drops it
+    #[serde(default = "default_history_retention_days")]
+    pub history_retention_days: u32,
+    /// Rewrite a legacy JSON config as TOML when it is loaded
+    #[serde(default = "default_true")]
+    pub auto_migrate: bool,
+}
+
+impl Default for GlobalSettings {
+    fn default() -> Self {
+        Self {
+            default_provider: None,
+            auto_detect_account: false,
+            colored_output: true,
+            show_progress: true,
+            default_profile: None,
+            strict_startup: false,
+            ssh_add_lifetime: None,
+            secret_command: None,
+            strict_ssh: false,
+            use_ssh_agent: true,
+            post_switch_command: None,
+            ssh_include_mode: false,
+            history_retention_days: default_history_retention_days(),
+            auto_migrate: true,
+        }
+    }
+}
+
+fn default_history_retention_days() -> u32 {
+    365
 }
 
 fn default_config_version() -> String {
@@ -58,61 +283,96 @@ fn default_true() -> bool {
     true
 }
 
+/// Parse a boolean setting value
+pub fn parse_bool_setting(value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(GitSwitchError::Other(format!(
+            "Invalid boolean value: {}. Use true or false",
+            value
+        ))),
+    }
+}
+
 pub fn get_config_file_path() -> Result<PathBuf> {
-    if let Some(home_dir) = home::home_dir() {
-        // Prefer TOML format
-        let toml_path = home_dir.join(CONFIG_FILE_NAME_TOML);
-        if toml_path.exists() {
-            return Ok(toml_path);
-        }
+    if let Some(path) = std::env::var_os(CONFIG_PATH_ENV).filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
 
-        // Check for legacy JSON format
-        let json_path = home_dir.join(CONFIG_FILE_NAME_JSON);
-        if json_path.exists() {
-            return Ok(json_path);
-        }
+    let home_dir = home_dir()?;
 
-        // Default to TOML for new installations
-        Ok(toml_path)
-    } else {
-        Err(GitSwitchError::HomeDirectoryNotFound)
+    // Prefer TOML format
+    let toml_path = home_dir.join(CONFIG_FILE_NAME_TOML);
+    if toml_path.exists() {
+        return Ok(toml_path);
     }
+
+    // Check for legacy JSON format
+    let json_path = home_dir.join(CONFIG_FILE_NAME_JSON);
+    if json_path.exists() {
+        return Ok(json_path);
+    }
+
+    // Default to TOML for new installations
+    Ok(toml_path)
 }
 
 pub fn load_config() -> Result<Config> {
-    let config_path = get_config_file_path()?;
+    load_config_from(&get_config_file_path()?)
+}
+
+/// Load the config stored at `config_path`; a missing file is an empty config
+pub fn load_config_from(config_path: &Path) -> Result<Config> {
     if !config_path.exists() {
         return Ok(Config::default());
     }
 
-    let content = read_file_content(&config_path)?;
+    let content = read_document(config_path)?;
+    let corrupted = |message: String| GitSwitchError::CorruptedConfig { message };
 
     // Try TOML first, then JSON for backwards compatibility
-    let mut config = if config_path.extension().and_then(|s| s.to_str()) == Some("toml") {
-        toml::from_str(&content).map_err(GitSwitchError::Toml)?
-    } else {
+    let config = if config_path.extension().and_then(|s| s.to_str()) == Some("json") {
         // JSON format (legacy)
-        let json_config: Config = serde_json::from_str(&content).map_err(GitSwitchError::Json)?;
+        let json_config: Config = serde_json::from_str(&content)
+            .map_err(|e| corrupted(utils::json_parse_error(config_path, &content, &e)))?;
 
-        // Migrate to TOML format
-        migrate_to_toml(&json_config)?;
+        // Migrate to TOML format unless the file is to be kept as it is
+        if auto_migrate_enabled(&json_config) {
+            migrate_to_toml(&json_config, config_path)?;
+        }
         json_config
+    } else {
+        toml::from_str(&content)
+            .map_err(|e| corrupted(utils::toml_parse_error(config_path, &content, &e)))?
     };
 
-    // Migrate old config versions
-    migrate_config(&mut config)?;
+    // Old versions are only reported here; `git-switch migrate --apply` upgrades them
+    if migration_pending(&config) {
+        tracing::warn!("Config version {} needs migration", config.version);
+    }
 
     Ok(config)
 }
 
 pub fn save_config(config: &Config) -> Result<()> {
-    let config_path = get_config_file_path()?;
+    save_config_to(config, &get_config_file_path()?)
+}
+
+/// Save `config` to `config_path`, as TOML even when the path names a legacy
+/// JSON file, unless automatic migration is off
+pub fn save_config_to(config: &Config, config_path: &Path) -> Result<()> {
+    let is_json = config_path.extension().and_then(|s| s.to_str()) == Some("json");
+    if is_json && !auto_migrate_enabled(config) {
+        let content = serde_json::to_string_pretty(config).map_err(GitSwitchError::Json)?;
+        return write_file_content(config_path, &content);
+    }
 
     // Always save in TOML format for new saves
-    let toml_path = if config_path.extension().and_then(|s| s.to_str()) == Some("json") {
+    let toml_path = if is_json {
         config_path.with_extension("toml")
     } else {
-        config_path
+        config_path.to_path_buf()
     };
 
     ensure_parent_dir_exists(&toml_path)?;
@@ -120,13 +380,17 @@ pub fn save_config(config: &Config) -> Result<()> {
     write_file_content(&toml_path, &content)
 }
 
+/// Whether a legacy JSON config may be rewritten as TOML: off with
+/// `--no-migrate` or the `auto-migrate` setting
+fn auto_migrate_enabled(config: &Config) -> bool {
+    config.settings.auto_migrate && std::env::var_os(NO_MIGRATE_ENV).is_none()
+}
+
 /// Migrate JSON config to TOML format
-fn migrate_to_toml(config: &Config) -> Result<()> {
+fn migrate_to_toml(config: &Config, json_path: &Path) -> Result<()> {
     tracing::info!("Migrating configuration from JSON to TOML format");
 
-    let home_dir = home::home_dir().ok_or(GitSwitchError::HomeDirectoryNotFound)?;
-    let json_path = home_dir.join(CONFIG_FILE_NAME_JSON);
-    let toml_path = home_dir.join(CONFIG_FILE_NAME_TOML);
+    let toml_path = json_path.with_extension("toml");
 
     // Save as TOML
     ensure_parent_dir_exists(&toml_path)?;
@@ -136,7 +400,7 @@ fn migrate_to_toml(config: &Config) -> Result<()> {
     // Backup old JSON config
     if json_path.exists() {
         let backup_path = json_path.with_extension("json.backup");
-        std::fs::rename(&json_path, &backup_path)?;
+        std::fs::rename(json_path, &backup_path)?;
         tracing::info!("Old JSON config backed up to: {}", backup_path.display());
     }
 
@@ -144,41 +408,175 @@ fn migrate_to_toml(config: &Config) -> Result<()> {
     Ok(())
 }
 
-/// Migrate old config versions to current version
-fn migrate_config(config: &mut Config) -> Result<()> {
-    let current_version = &config.version;
-
-    if current_version.is_empty() || current_version == "1.0" {
-        tracing::info!("Migrating config from version {} to 2.0", current_version);
+/// Whether the config predates the current version and should be migrated
+pub fn migration_pending(config: &Config) -> bool {
+    config.version.is_empty() || config.version == "1.0"
+}
 
-        // Add default values for new fields
-        for account in config.accounts.values_mut() {
-            if account.additional_ssh_keys.is_empty() {
-                account.additional_ssh_keys = Vec::new();
+/// Tell the user to run `git-switch migrate --apply` when `config` is outdated
+pub fn warn_if_migration_pending(config: &Config) {
+    if migration_pending(config) {
+        eprintln!(
+            "{} Config version {} is outdated; run 'git-switch migrate --apply' to upgrade it",
+            "⚠".yellow(),
+            if config.version.is_empty() {
+                "(none)"
+            } else {
+                config.version.as_str()
             }
-            if account.groups.is_empty() {
-                account.groups = Vec::new();
+        );
+    }
+}
+
+/// Migrate old config versions to the current version.
+///
+/// Returns a description of each change; `raw` is the file as written, used to
+/// report which fields are filled in with defaults.
+pub fn migrate_config(config: &mut Config, raw: Option<&toml::Value>) -> Vec<String> {
+    let mut changes = Vec::new();
+    if !migration_pending(config) {
+        return changes;
+    }
+
+    let current_version = if config.version.is_empty() {
+        "unversioned"
+    } else {
+        config.version.as_str()
+    };
+    changes.push(format!(
+        "version: {} → {}",
+        current_version,
+        default_config_version()
+    ));
+
+    let mut names: Vec<String> = config.accounts.keys().cloned().collect();
+    names.sort();
+    for name in names {
+        let account = config.accounts.get_mut(&name).expect("name from keys");
+
+        // Fields added in 2.0 default to empty when missing
+        let raw_account = raw
+            .and_then(|raw| raw.get("accounts"))
+            .and_then(|accounts| accounts.get(&name));
+        if let Some(raw_account) = raw_account {
+            let missing: Vec<&str> = ["additional_ssh_keys", "groups"]
+                .into_iter()
+                .filter(|field| raw_account.get(field).is_none())
+                .collect();
+            if !missing.is_empty() {
+                changes.push(format!(
+                    "account '{}': default {} added",
+                    name,
+                    missing.join(", ")
+                ));
             }
-            if account.provider.is_none() {
-                // Try to detect provider from email domain
-                if account.email.contains("@github.com") {
-                    account.provider = Some("github".to_string());
-                } else if account.email.contains("@gitlab.com") {
-                    account.provider = Some("gitlab".to_string());
-                }
+        }
+
+        if account.provider.is_none() {
+            // Try to detect provider from email domain
+            let provider = if account.email.contains("@github.com") {
+                Some("github")
+            } else if account.email.contains("@gitlab.com") {
+                Some("gitlab")
+            } else {
+                None
+            };
+            if let Some(provider) = provider {
+                account.provider = Some(provider.to_string());
+                changes.push(format!(
+                    "account '{}': provider inferred as {}",
+                    name, provider
+                ));
             }
         }
+    }
 
-        config.version = "2.0".to_string();
-        tracing::info!("Config migration to version 2.0 completed");
+    if raw.is_some_and(|raw| raw.get("settings").is_none()) {
+        changes.push("settings: default global settings added".to_string());
     }
 
-    Ok(())
+    config.version = default_config_version();
+    changes
+}
+
+/// Settings that can be changed with `git-switch config set`
+#[derive(Debug, Clone, Copy)]
+pub enum SettingKey {
+    DefaultProvider,
+    StrictStartup,
+    SshAddLifetime,
+    SecretCommand,
+    StrictSsh,
+    UseSshAgent,
+    ColoredOutput,
+    ShowProgress,
+    PostSwitchCommand,
+    SshIncludeMode,
+    HistoryRetentionDays,
+    AutoMigrate,
+}
+
+impl SettingKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingKey::DefaultProvider => "default-provider",
+            SettingKey::StrictStartup => "strict-startup",
+            SettingKey::SshAddLifetime => "ssh-add-lifetime",
+            SettingKey::SecretCommand => "secret-command",
+            SettingKey::StrictSsh => "strict-ssh",
+            SettingKey::UseSshAgent => "use-ssh-agent",
+            SettingKey::ColoredOutput => "colored-output",
+            SettingKey::ShowProgress => "show-progress",
+            SettingKey::PostSwitchCommand => "post-switch-command",
+            SettingKey::SshIncludeMode => "ssh-include-mode",
+            SettingKey::HistoryRetentionDays => "history-retention-days",
+            SettingKey::AutoMigrate => "auto-migrate",
+        }
+    }
+}
+
+impl std::str::FromStr for SettingKey {
+    type Err = GitSwitchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "default-provider" => Ok(SettingKey::DefaultProvider),
+            "strict-startup" => Ok(SettingKey::StrictStartup),
+            "ssh-add-lifetime" => Ok(SettingKey::SshAddLifetime),
+            "secret-command" => Ok(SettingKey::SecretCommand),
+            "strict-ssh" => Ok(SettingKey::StrictSsh),
+            "use-ssh-agent" => Ok(SettingKey::UseSshAgent),
+            "colored-output" => Ok(SettingKey::ColoredOutput),
+            "show-progress" => Ok(SettingKey::ShowProgress),
+            "post-switch-command" => Ok(SettingKey::PostSwitchCommand),
+            "ssh-include-mode" => Ok(SettingKey::SshIncludeMode),
+            "history-retention-days" => Ok(SettingKey::HistoryRetentionDays),
+            "auto-migrate" => Ok(SettingKey::AutoMigrate),
+            _ => Err(GitSwitchError::Other(format!(
+                "Unknown setting: {}. Supported: default-provider, strict-startup, ssh-add-lifetime, secret-command, strict-ssh, use-ssh-agent, colored-output, show-progress, post-switch-command, ssh-include-mode, history-retention-days, auto-migrate",
+                s
+            ))),
+        }
+    }
 }
 
 impl Config {
-    pub fn get_profiles_path(&self) -> std::path::PathBuf {
-        let home_dir = home::home_dir().expect("Home directory should be available");
-        home_dir.join("profiles.toml")
+    /// Profiles live next to the config file (the home directory by default)
+    pub fn get_profiles_path(&self) -> Result<PathBuf> {
+        let config_path = get_config_file_path()?;
+        let config_dir = config_path.parent().ok_or_else(|| {
+            GitSwitchError::Other("Could not determine config directory".to_string())
+        })?;
+        Ok(config_dir.join(PROFILES_FILE_NAME))
+    }
+
+    /// Whether the account's `core.sshCommand` should ignore the user's SSH config
+    pub fn strict_ssh_for(&self, account: &Account) -> bool {
+        account.strict_ssh.unwrap_or(self.settings.strict_ssh)
+    }
+
+    /// Whether git-switch may talk to the SSH agent (`--no-ssh-agent` or `use_ssh_agent = false` turn it off)
+    pub fn ssh_agent_enabled(&self) -> bool {
+        self.settings.use_ssh_agent && std::env::var_os(NO_SSH_AGENT_ENV).is_none()
     }
 }
@@ -1,27 +1,245 @@
 use crate::config::Config;
 use crate::error::Result;
 use crate::git;
+use crate::profiles::ProfileManager;
+use crate::ssh;
 use colored::*;
+use serde::Serialize;
 
 /// Auto-detect account based on remote URL
 pub fn detect_account_from_remote(config: &Config) -> Result<Option<String>> {
+    Ok(detect_account_candidates(config)?.into_iter().next())
+}
+
+/// Accounts matched by one remote of the current repository
+#[derive(Debug, Clone)]
+pub struct RemoteSuggestion {
+    pub remote: String,
+    pub url: String,
+    pub accounts: Vec<String>,
+}
+
+/// The remotes whose URL matches at least one account, `origin` first and the
+/// rest in `git remote` order
+pub fn remote_suggestions(config: &Config) -> Vec<RemoteSuggestion> {
+    let mut remotes = git::list_remotes().unwrap_or_default();
+    remotes.sort_by_key(|(name, _)| name != "origin");
+    remotes
+        .into_iter()
+        .filter_map(|(remote, url)| {
+            let accounts = match_remote_url(config, &url).0;
+            (!accounts.is_empty()).then_some(RemoteSuggestion {
+                remote,
+                url,
+                accounts,
+            })
+        })
+        .collect()
+}
+
+/// All accounts that match the current repository's remotes.
+///
+/// The first remote that matches anything decides, with `origin` preferred.
+/// Custom URL patterns take precedence; provider matches are only considered
+/// when no pattern matches. The last commit's author email is a weaker signal:
+/// it narrows several remote matches down and is used alone when the remote
+/// matches nothing. Weaker still is the repository's committer email falling
+/// in an account's email domain, consulted only when neither settles on one
+/// account. Names are sorted for stable output.
+pub fn detect_account_candidates(config: &Config) -> Result<Vec<String>> {
     if !git::is_in_git_repository()? {
-        return Ok(None);
+        return Ok(Vec::new());
     }
 
-    let remote_url = git::get_remote_url("origin").ok();
-    if let Some(url) = remote_url {
-        // Try to match accounts based on SSH key or provider
-        for (name, account) in &config.accounts {
-            if let Some(provider) = &account.provider {
-                if url_matches_provider(&url, provider) {
-                    return Ok(Some(name.clone()));
-                }
+    let remote_candidates = remote_suggestions(config)
+        .into_iter()
+        .next()
+        .map(|suggestion| suggestion.accounts)
+        .unwrap_or_default();
+    if remote_candidates.len() == 1 {
+        return Ok(remote_candidates);
+    }
+    let candidates = match git::last_commit_author_email() {
+        Some(email) => narrow_by_commit_email(config, remote_candidates, &email),
+        None => remote_candidates,
+    };
+    if candidates.len() == 1 {
+        return Ok(candidates);
+    }
+    let committer_email = git::get_local_config_key("user.email")
+        .ok()
+        .or_else(git::last_commit_author_email);
+    Ok(match committer_email {
+        Some(email) => narrow_by_email_domain(config, candidates, &email),
+        None => candidates,
+    })
+}
+
+/// Like `narrow_by_commit_email`, matching the email against account email domains
+fn narrow_by_email_domain(config: &Config, candidates: Vec<String>, email: &str) -> Vec<String> {
+    let mut by_domain: Vec<String> = config
+        .accounts
+        .iter()
+        .filter(|(name, account)| {
+            account.matches_email_domain(email)
+                && (candidates.is_empty() || candidates.contains(name))
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    if by_domain.is_empty() {
+        return candidates;
+    }
+    by_domain.sort();
+    by_domain
+}
+
+/// The single account whose email domain contains `email`, if exactly one does
+pub fn detect_account_by_email_domain(config: &Config, email: &str) -> Option<String> {
+    let mut matches = config
+        .accounts
+        .iter()
+        .filter(|(_, account)| account.matches_email_domain(email));
+    match (matches.next(), matches.next()) {
+        (Some((name, _)), None) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Keep the candidates whose email matches the last commit's author; with no
+/// candidates, every account matching that email is one
+fn narrow_by_commit_email(config: &Config, candidates: Vec<String>, email: &str) -> Vec<String> {
+    let mut by_email: Vec<String> = config
+        .accounts
+        .iter()
+        .filter(|(name, account)| {
+            account.matches_email(email) && (candidates.is_empty() || candidates.contains(name))
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    if by_email.is_empty() {
+        return candidates;
+    }
+    by_email.sort();
+    by_email
+}
+
+/// Which rule produced a set of detection candidates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchSource {
+    UrlPattern,
+    Provider,
+}
+
+/// Accounts matching `url`, with the rule that matched them (None when nothing did)
+pub fn match_remote_url(config: &Config, url: &str) -> (Vec<String>, Option<MatchSource>) {
+    let mut source = Some(MatchSource::UrlPattern);
+
+    let (url, _) = git::split_url_userinfo(url);
+    let url = url.as_str();
+    let lowercase_url = url.to_lowercase();
+    let mut candidates: Vec<String> = config
+        .accounts
+        .iter()
+        .filter(|(_, account)| {
+            account
+                .url_patterns
+                .iter()
+                .any(|pattern| url_matches_pattern(&lowercase_url, &pattern.to_lowercase()))
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    if candidates.is_empty() {
+        // Try to match accounts based on provider
+        source = Some(MatchSource::Provider);
+        candidates = config
+            .accounts
+            .iter()
+            .filter(|(_, account)| {
+                account
+                    .provider
+                    .as_deref()
+                    .is_some_and(|provider| url_matches_provider(url, provider))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+
+    if candidates.is_empty() {
+        source = None;
+    }
+    candidates.sort();
+    (candidates, source)
+}
+
+/// Match a remote URL against the accounts' custom URL patterns.
+///
+/// When several accounts match, the longest matching pattern wins and ties go
+/// to the first account by name, so the result never depends on map order.
+pub fn detect_account_by_url_pattern(config: &Config, remote_url: &str) -> Option<String> {
+    let remote_url = git::split_url_userinfo(remote_url).0.to_lowercase();
+    config
+        .accounts
+        .iter()
+        .filter_map(|(name, account)| {
+            account
+                .url_patterns
+                .iter()
+                .filter(|pattern| url_matches_pattern(&remote_url, &pattern.to_lowercase()))
+                .map(|pattern| pattern.len())
+                .max()
+                .map(|length| (length, name))
+        })
+        .max_by(|(a_length, a_name), (b_length, b_name)| {
+            a_length.cmp(b_length).then_with(|| b_name.cmp(a_name))
+        })
+        .map(|(_, name)| name.clone())
+}
+
+/// Check if URL matches a pattern: a glob when it contains `*` or `?`, otherwise a substring
+fn url_matches_pattern(url: &str, pattern: &str) -> bool {
+    if pattern.contains('*') || pattern.contains('?') {
+        glob_matches(pattern.as_bytes(), url.as_bytes())
+    } else {
+        url.contains(pattern)
+    }
+}
+
+/// Glob match where `*` is any run of bytes and `?` any one byte. Only the last
+/// `*` is ever revisited, so patterns with many stars stay cheap.
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text it has consumed up to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
             }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
         }
     }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
 
-    Ok(None)
+/// The known provider hosting `url`, e.g. `github`
+pub fn provider_for_url(url: &str) -> Option<&'static str> {
+    let url = git::split_url_userinfo(url).0.to_lowercase();
+    ["github", "gitlab", "bitbucket"]
+        .into_iter()
+        .find(|provider| url_matches_provider(&url, provider))
 }
 
 /// Check if URL matches a provider
@@ -34,11 +252,63 @@ fn url_matches_provider(url: &str, provider: &str) -> bool {
     }
 }
 
+/// Resolve the default profile's default account as (profile, account)
+fn default_profile_account(config: &Config) -> Result<Option<(String, String)>> {
+    if config.settings.default_profile.is_none() {
+        return Ok(None);
+    }
+    let profile_manager = ProfileManager::new(config.clone())?;
+    Ok(profile_manager
+        .default_profile_account()
+        .map(|(profile, account)| (profile.name.clone(), account.to_string())))
+}
+
 /// Suggest account based on current repository
 pub fn suggest_account(config: &Config) -> Result<()> {
     if let Some(account_name) = detect_account_from_remote(config)? {
+        let suggestions = if git::is_in_git_repository()? {
+            remote_suggestions(config)
+        } else {
+            Vec::new()
+        };
+        match suggestions
+            .iter()
+            .find(|suggestion| suggestion.accounts.contains(&account_name))
+        {
+            Some(suggestion) => println!(
+                "💡 Detected account '{}' for this repository (from remote '{}')",
+                account_name.cyan(),
+                suggestion.remote
+            ),
+            None => println!(
+                "💡 Detected account '{}' for this repository",
+                account_name.cyan()
+            ),
+        }
+        println!(
+            "Use {} to apply this account",
+            format!("git-switch account {}", account_name).cyan()
+        );
+
+        // A fork and its upstream may belong to different identities
+        if suggestions
+            .iter()
+            .any(|suggestion| !suggestion.accounts.contains(&account_name))
+        {
+            println!("{} Remotes suggest different accounts:", "⚠".yellow());
+            for suggestion in &suggestions {
+                println!(
+                    "  {} → {} ({})",
+                    suggestion.remote.bold(),
+                    suggestion.accounts.join(", ").cyan(),
+                    git::redact_url(&suggestion.url).dimmed()
+                );
+            }
+        }
+    } else if let Some((profile_name, account_name)) = default_profile_account(config)? {
         println!(
-            "💡 Detected account '{}' for this repository",
+            "💡 No account detected; default profile '{}' suggests account '{}'",
+            profile_name.cyan(),
             account_name.cyan()
         );
         println!(
@@ -67,19 +337,19 @@ pub fn check_account_mismatch(config: &Config) -> Result<()> {
         let current_account = config
             .accounts
             .values()
-            .find(|acc| acc.email == local_email)
+            .find(|acc| acc.matches_email(&local_email))
             .map(|acc| acc.name.clone());
 
-        if let (Some(suggested_name), Some(current_name)) = (suggested, current_account) {
-            if suggested_name != current_name {
-                println!("{} Account mismatch detected!", "⚠".yellow().bold());
-                println!("  Current: {}", current_name.red());
-                println!("  Suggested: {}", suggested_name.green());
-                println!(
-                    "  Use {} to switch",
-                    format!("git-switch account {}", suggested_name).cyan()
-                );
-            }
+        if let (Some(suggested_name), Some(current_name)) = (suggested, current_account)
+            && suggested_name != current_name
+        {
+            println!("{} Account mismatch detected!", "⚠".yellow().bold());
+            println!("  Current: {}", current_name.red());
+            println!("  Suggested: {}", suggested_name.green());
+            println!(
+                "  Use {} to switch",
+                format!("git-switch account {}", suggested_name).cyan()
+            );
         }
     }
 
@@ -88,24 +358,52 @@ pub fn check_account_mismatch(config: &Config) -> Result<()> {
 
 // Repository discovery and bulk operations are now handled by the repository.rs module
 
+/// Host of a remote URL, e.g. `github.com-work` for `git@github.com-work:org/repo.git`
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split_once('@').map_or(rest, |(_, rest)| rest);
+    rest.split([':', '/'])
+        .next()
+        .filter(|host| !host.is_empty())
+}
+
+/// The account whose SSH host alias (see `ssh::host_alias`) the URL points at
+pub fn detect_account_by_host_alias(config: &Config, remote_url: &str) -> Option<String> {
+    let host = url_host(remote_url)?.to_lowercase();
+    config
+        .accounts
+        .iter()
+        .find(|(_, account)| ssh::host_alias(&account.slug()) == host)
+        .map(|(name, _)| name.clone())
+}
+
 /// Detect account for a specific repository based on remote URL
 pub fn detect_account_for_remote_url(config: &Config, remote_url: &str) -> Result<Option<String>> {
+    // Custom URL patterns are explicit user configuration, so they win
+    if let Some(name) = detect_account_by_url_pattern(config, remote_url) {
+        return Ok(Some(name));
+    }
+    // A host alias names its account outright
+    if let Some(name) = detect_account_by_host_alias(config, remote_url) {
+        return Ok(Some(name));
+    }
+
     // Parse the remote URL to extract the provider and repository info
-    let remote_url = remote_url.to_lowercase();
+    let remote_url = git::split_url_userinfo(remote_url).0.to_lowercase();
 
     // GitHub patterns
     if remote_url.contains("github.com") {
         for (account_name, account) in &config.accounts {
-            if let Some(ref provider) = account.provider {
-                if provider.to_lowercase() == "github" {
-                    return Ok(Some(account_name.clone()));
-                }
+            if let Some(ref provider) = account.provider
+                && provider.to_lowercase() == "github"
+            {
+                return Ok(Some(account_name.clone()));
             }
-            // Also check if the username in the URL matches
-            if let Some(github_user) = extract_github_username(&remote_url) {
-                if account.username == github_user {
-                    return Ok(Some(account_name.clone()));
-                }
+            // Also check if the URL owner is the account's login
+            if let Some(github_user) = extract_github_username(&remote_url)
+                && account.login.eq_ignore_ascii_case(&github_user)
+            {
+                return Ok(Some(account_name.clone()));
             }
         }
     }
@@ -113,15 +411,15 @@ pub fn detect_account_for_remote_url(config: &Config, remote_url: &str) -> Resul
     // GitLab patterns
     if remote_url.contains("gitlab.com") {
         for (account_name, account) in &config.accounts {
-            if let Some(ref provider) = account.provider {
-                if provider.to_lowercase() == "gitlab" {
-                    return Ok(Some(account_name.clone()));
-                }
+            if let Some(ref provider) = account.provider
+                && provider.to_lowercase() == "gitlab"
+            {
+                return Ok(Some(account_name.clone()));
             }
-            if let Some(gitlab_user) = extract_gitlab_username(&remote_url) {
-                if account.username == gitlab_user {
-                    return Ok(Some(account_name.clone()));
-                }
+            if let Some(gitlab_user) = extract_gitlab_username(&remote_url)
+                && account.login.eq_ignore_ascii_case(&gitlab_user)
+            {
+                return Ok(Some(account_name.clone()));
             }
         }
     }
@@ -129,15 +427,15 @@ pub fn detect_account_for_remote_url(config: &Config, remote_url: &str) -> Resul
     // Bitbucket patterns
     if remote_url.contains("bitbucket.org") {
         for (account_name, account) in &config.accounts {
-            if let Some(ref provider) = account.provider {
-                if provider.to_lowercase() == "bitbucket" {
-                    return Ok(Some(account_name.clone()));
-                }
+            if let Some(ref provider) = account.provider
+                && provider.to_lowercase() == "bitbucket"
+            {
+                return Ok(Some(account_name.clone()));
             }
-            if let Some(bitbucket_user) = extract_bitbucket_username(&remote_url) {
-                if account.username == bitbucket_user {
-                    return Ok(Some(account_name.clone()));
-                }
+            if let Some(bitbucket_user) = extract_bitbucket_username(&remote_url)
+                && account.login.eq_ignore_ascii_case(&bitbucket_user)
+            {
+                return Ok(Some(account_name.clone()));
             }
         }
     }
@@ -205,3 +503,81 @@ fn extract_bitbucket_username(url: &str) -> Option<String> {
     }
     None
 }
+
+/// One account that a URL matches, and why
+#[derive(Debug, Serialize)]
+pub struct UrlMatch {
+    pub account: String,
+    pub confidence: f32, // 0.0 to 1.0, as in `repo discover`
+    pub reason: String,
+}
+
+/// Every rule that matches an arbitrary remote URL, for `account check-url`
+#[derive(Debug, Serialize)]
+pub struct UrlCheck {
+    pub url: String,
+    /// What `repo discover` and `remote add` would pick
+    pub account: Option<String>,
+    /// Strongest match first
+    pub matches: Vec<UrlMatch>,
+}
+
+/// Explain how each account relates to `url` without needing a repository
+pub fn check_url(config: &Config, url: &str) -> Result<UrlCheck> {
+    let (stripped, _) = git::split_url_userinfo(url);
+    let lowercase_url = stripped.to_lowercase();
+    let host = url_host(&lowercase_url).map(str::to_string);
+
+    let mut matches = Vec::new();
+    for (name, account) in &config.accounts {
+        let reason = if let Some(pattern) = account
+            .url_patterns
+            .iter()
+            .find(|pattern| url_matches_pattern(&lowercase_url, &pattern.to_lowercase()))
+        {
+            Some((1.0, format!("URL pattern '{}' matches", pattern)))
+        } else if host.as_deref() == Some(ssh::host_alias(&account.slug()).as_str()) {
+            Some((
+                1.0,
+                format!(
+                    "host is the account's SSH alias '{}'",
+                    host.as_deref().unwrap_or_default()
+                ),
+            ))
+        } else if let Some(provider) = account
+            .provider
+            .as_deref()
+            .filter(|provider| url_matches_provider(&lowercase_url, &provider.to_lowercase()))
+        {
+            Some((0.9, format!("provider '{}' matches the host", provider)))
+        } else {
+            [
+                extract_github_username(&lowercase_url),
+                extract_gitlab_username(&lowercase_url),
+                extract_bitbucket_username(&lowercase_url),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|user| user.eq_ignore_ascii_case(&account.login))
+            .map(|user| (0.9, format!("URL owner '{}' is the account's login", user)))
+        };
+        if let Some((confidence, reason)) = reason {
+            matches.push(UrlMatch {
+                account: name.clone(),
+                confidence,
+                reason,
+            });
+        }
+    }
+    matches.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.account.cmp(&b.account))
+    });
+
+    Ok(UrlCheck {
+        url: git::redact_url(url),
+        account: detect_account_for_remote_url(config, url)?,
+        matches,
+    })
+}
@@ -0,0 +1,261 @@
+//! Environment health checks behind `git-switch doctor`.
+//!
+//! `run_checks` is the single source of results; the human and JSON
+//! renderers only format what it returns.
+
+use crate::backup;
+use crate::config::{self, Config};
+use crate::error::{GitSwitchError, Result};
+use crate::ssh;
+use crate::utils;
+use crate::validation;
+use colored::*;
+use serde::Serialize;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// One doctor check and its result
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    /// What was checked: a program, file or account
+    pub target: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl DoctorCheck {
+    fn new(
+        name: &str,
+        target: impl Into<String>,
+        status: CheckStatus,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            target: target.into(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Counts per status
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorSummary {
+    pub ok: usize,
+    pub warn: usize,
+    pub fail: usize,
+}
+
+#[derive(Serialize)]
+struct DoctorReport<'a> {
+    checks: &'a [DoctorCheck],
+    summary: DoctorSummary,
+}
+
+fn summarize(checks: &[DoctorCheck]) -> DoctorSummary {
+    let mut summary = DoctorSummary::default();
+    for check in checks {
+        match check.status {
+            CheckStatus::Ok => summary.ok += 1,
+            CheckStatus::Warn => summary.warn += 1,
+            CheckStatus::Fail => summary.fail += 1,
+        }
+    }
+    summary
+}
+
+/// Run every check against the environment and configuration
+pub fn run_checks(config: &Config) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    checks.push(match validation::validate_git_installation() {
+        Ok(()) => DoctorCheck::new("program", "git", CheckStatus::Ok, "found"),
+        Err(e) => DoctorCheck::new("program", "git", CheckStatus::Fail, e.to_string()),
+    });
+    for program in ["ssh", "ssh-keygen", "ssh-add"] {
+        checks.push(match utils::require_openssh_program(program) {
+            Ok(()) => DoctorCheck::new("program", program, CheckStatus::Ok, "found"),
+            Err(e) => DoctorCheck::new("program", program, CheckStatus::Fail, e.to_string()),
+        });
+    }
+
+    checks.push(if !config.ssh_agent_enabled() {
+        DoctorCheck::new(
+            "ssh-agent",
+            "SSH_AUTH_SOCK",
+            CheckStatus::Ok,
+            "disabled (use_ssh_agent = false or --no-ssh-agent)",
+        )
+    } else {
+        match validation::validate_ssh_agent() {
+            Ok(()) => DoctorCheck::new("ssh-agent", "SSH_AUTH_SOCK", CheckStatus::Ok, "running"),
+            Err(e) => DoctorCheck::new(
+                "ssh-agent",
+                "SSH_AUTH_SOCK",
+                CheckStatus::Warn,
+                e.to_string(),
+            ),
+        }
+    });
+
+    let config_target = config::get_config_file_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| "config".to_string());
+    checks.push(match backup::validate_config(config) {
+        Ok(()) => DoctorCheck::new(
+            "config",
+            config_target,
+            CheckStatus::Ok,
+            format!(
+                "{} accounts, version {}",
+                config.accounts.len(),
+                config.version
+            ),
+        ),
+        Err(e) => DoctorCheck::new("config", config_target, CheckStatus::Fail, e.to_string()),
+    });
+    if config::migration_pending(config) {
+        checks.push(DoctorCheck::new(
+            "config-version",
+            config.version.clone(),
+            CheckStatus::Warn,
+            "outdated; run 'git-switch migrate --apply'",
+        ));
+    }
+
+    checks.extend(account_checks_for(config));
+    checks
+}
+
+/// Key and SSH config checks for every account, sorted by account name
+pub fn account_checks_for(config: &Config) -> Vec<DoctorCheck> {
+    let ssh_config = ssh::read_ssh_configs();
+    let mut names: Vec<&String> = config.accounts.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .flat_map(|name| account_checks(name, &config.accounts[name], &ssh_config))
+        .collect()
+}
+
+/// Key and SSH config checks for one account
+fn account_checks(name: &str, account: &config::Account, ssh_config: &str) -> Vec<DoctorCheck> {
+    if account.ssh_key_path.is_empty() {
+        return vec![DoctorCheck::new(
+            "ssh-key",
+            name,
+            CheckStatus::Ok,
+            "no key configured",
+        )];
+    }
+
+    let key_path = match utils::expand_path(&account.ssh_key_path) {
+        Ok(path) => path,
+        Err(e) => {
+            return vec![DoctorCheck::new(
+                "ssh-key",
+                name,
+                CheckStatus::Fail,
+                e.to_string(),
+            )];
+        }
+    };
+    if !key_path.exists() {
+        return vec![DoctorCheck::new(
+            "ssh-key",
+            name,
+            CheckStatus::Fail,
+            format!("key not found: {}", key_path.display()),
+        )];
+    }
+
+    let mut checks = vec![match validation::validate_ssh_key(&key_path) {
+        Ok(()) => DoctorCheck::new(
+            "ssh-key",
+            name,
+            CheckStatus::Ok,
+            key_path.display().to_string(),
+        ),
+        Err(e) => DoctorCheck::new("ssh-key", name, CheckStatus::Fail, e.to_string()),
+    }];
+    checks.push(match ssh::read_public_key(&key_path) {
+        Ok(_) => DoctorCheck::new("public-key", name, CheckStatus::Ok, "found"),
+        Err(e) => DoctorCheck::new("public-key", name, CheckStatus::Warn, e.to_string()),
+    });
+    let alias = ssh::host_alias(&account.slug());
+    let host = format!("Host {}", alias);
+    checks.push(if ssh::has_host_entry(ssh_config, &alias) {
+        DoctorCheck::new("ssh-config", name, CheckStatus::Ok, host)
+    } else {
+        DoctorCheck::new(
+            "ssh-config",
+            name,
+            CheckStatus::Warn,
+            format!("no '{}' entry in ~/.ssh/config", host),
+        )
+    });
+    checks
+}
+
+/// Run the checks and print them; any failed check makes the command fail
+pub fn run_doctor(config: &Config, json: bool) -> Result<()> {
+    let checks = run_checks(config);
+    let summary = summarize(&checks);
+    let failed = summary.fail;
+
+    if json {
+        let report = DoctorReport {
+            checks: &checks,
+            summary,
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(GitSwitchError::Json)?
+        );
+    } else {
+        print_checks(&checks, &summary);
+    }
+
+    if failed > 0 {
+        return Err(GitSwitchError::DoctorFailed { failed });
+    }
+    Ok(())
+}
+
+fn print_checks(checks: &[DoctorCheck], summary: &DoctorSummary) {
+    println!("{}", "🩺 GitSwitch Doctor".bold().cyan());
+    println!("{}", "─".repeat(30));
+
+    let name_width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let target_width = checks.iter().map(|c| c.target.len()).max().unwrap_or(0);
+    for check in checks {
+        let icon = match check.status {
+            CheckStatus::Ok => "✓".green().bold(),
+            CheckStatus::Warn => "⚠".yellow().bold(),
+            CheckStatus::Fail => "✗".red().bold(),
+        };
+        println!(
+            "{} {:<name_width$}  {:<target_width$}  {}",
+            icon,
+            check.name,
+            check.target,
+            check.message.dimmed()
+        );
+    }
+
+    println!(
+        "\n{} ok, {} warnings, {} failed",
+        summary.ok.to_string().green(),
+        summary.warn.to_string().yellow(),
+        summary.fail.to_string().red()
+    );
+}
@@ -0,0 +1,103 @@
+//! Passphrase encryption for backups.
+//!
+//! The key is derived from the passphrase with Argon2id and a random salt; the
+//! data is sealed with ChaCha20-Poly1305, so a wrong passphrase and a tampered
+//! file are both rejected when decrypting. The result is a small JSON envelope
+//! holding the parameters needed to decrypt it.
+
+use crate::error::{GitSwitchError, Result};
+use argon2::Argon2;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Marks an encrypted git-switch file
+const ENVELOPE_FORMAT: &str = "git-switch-encrypted";
+const ENVELOPE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    format: String,
+    version: u32,
+    kdf: String,
+    cipher: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| GitSwitchError::Other(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Whether `content` is an encrypted envelope rather than a plain document
+pub fn is_encrypted(content: &str) -> bool {
+    serde_json::from_str::<Envelope>(content)
+        .is_ok_and(|envelope| envelope.format == ENVELOPE_FORMAT)
+}
+
+/// Encrypt `plaintext` with a passphrase into an envelope
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rng().fill_bytes(&mut salt);
+    rand::rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| GitSwitchError::Other("Encryption failed".to_string()))?;
+
+    let envelope = Envelope {
+        format: ENVELOPE_FORMAT.to_string(),
+        version: ENVELOPE_VERSION,
+        kdf: "argon2id".to_string(),
+        cipher: "chacha20poly1305".to_string(),
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&envelope).map_err(GitSwitchError::Json)
+}
+
+/// Decrypt an envelope; fails on a wrong passphrase or a modified file
+pub fn decrypt(content: &str, passphrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let corrupted = |message: &str| GitSwitchError::CorruptedConfig {
+        message: message.to_string(),
+    };
+    let envelope: Envelope =
+        serde_json::from_str(content).map_err(|_| corrupted("not an encrypted git-switch file"))?;
+    if envelope.format != ENVELOPE_FORMAT || envelope.version != ENVELOPE_VERSION {
+        return Err(corrupted("unsupported encrypted file format"));
+    }
+
+    let decode = |value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|_| corrupted("invalid base64 in encrypted file"))
+    };
+    let salt = decode(&envelope.salt)?;
+    let nonce = decode(&envelope.nonce)?;
+    if nonce.len() != NONCE_LEN {
+        return Err(corrupted("invalid nonce in encrypted file"));
+    }
+    let ciphertext = decode(&envelope.ciphertext)?;
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()));
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map(Zeroizing::new)
+        .map_err(|_| corrupted("could not decrypt: wrong passphrase or the file was modified"))
+}
@@ -48,7 +48,9 @@ pub enum GitSwitchError {
     #[error("SSH command failed: {command} - {message}")]
     SshCommand { command: String, message: String },
 
-    #[error("Home directory not found. Please ensure the HOME environment variable is set.")]
+    #[error(
+        "Home directory not found. Set the HOME environment variable (USERPROFILE on Windows) or pass --config <path>."
+    )]
     HomeDirectoryNotFound,
 
     #[error("Path expansion failed for: {path}")]
@@ -93,10 +95,25 @@ pub enum GitSwitchError {
     #[error("Git is not installed or accessible")]
     GitNotInstalled,
 
+    #[error("{program} not found; install OpenSSH. {}", openssh_install_hint())]
+    OpenSshNotFound { program: String },
+
+    #[error("{failed} doctor check(s) failed")]
+    DoctorFailed { failed: usize },
+
+    #[error("Private key {private_key} does not match its public key {public_key}")]
+    KeyPairMismatch {
+        private_key: String,
+        public_key: String,
+    },
+
     #[error("Keyring error: {message}")]
     #[allow(dead_code)]
     Keyring { message: String },
 
+    #[error("Secret command failed: {message}")]
+    SecretCommand { message: String },
+
     #[error("Backup operation failed: {message}")]
     BackupFailed { message: String },
 
@@ -114,6 +131,17 @@ pub enum GitSwitchError {
     Other(String),
 }
 
+/// Platform-specific advice for installing the OpenSSH client tools
+fn openssh_install_hint() -> &'static str {
+    if cfg!(windows) {
+        "Enable the \"OpenSSH Client\" optional feature (Settings > Apps > Optional features) or install Git for Windows, then make sure it is on PATH."
+    } else if cfg!(target_os = "macos") {
+        "OpenSSH ships with macOS; make sure /usr/bin is on PATH."
+    } else {
+        "Install your distribution's OpenSSH client package (e.g. openssh-client or openssh) and make sure it is on PATH."
+    }
+}
+
 /// Result type alias for git-switch
 pub type Result<T, E = GitSwitchError> = std::result::Result<T, E>;
 
@@ -150,8 +178,55 @@ impl GitSwitchError {
             Self::RestoreFailed { .. } => 20,
             Self::MigrationFailed { .. } => 21,
             Self::SerializationError(_) => 23,
+            Self::SecretCommand { .. } => 24,
+            Self::OpenSshNotFound { .. } => 25,
+            Self::DoctorFailed { .. } => 26,
+            Self::KeyPairMismatch { .. } => 27,
             Self::NotInGitRepository => 13,
             Self::Other(_) => 100, // General error
         }
     }
+
+    /// Variant name, reported as `kind` in `--json` errors
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "Io",
+            Self::Json(_) => "Json",
+            Self::Toml(_) => "Toml",
+            Self::TomlSer(_) => "TomlSer",
+            Self::Clap(_) => "Clap",
+            Self::Dialog(_) => "Dialog",
+            Self::AccountNotFound { .. } => "AccountNotFound",
+            Self::AccountExists { .. } => "AccountExists",
+            Self::ProfileNotFound { .. } => "ProfileNotFound",
+            Self::ProfileAlreadyExists { .. } => "ProfileAlreadyExists",
+            Self::AccountNotInProfile { .. } => "AccountNotInProfile",
+            Self::InvalidDefaultAccount { .. } => "InvalidDefaultAccount",
+            Self::NoRepositoriesDiscovered => "NoRepositoriesDiscovered",
+            Self::SshKeyGeneration { .. } => "SshKeyGeneration",
+            Self::SshCommand { .. } => "SshCommand",
+            Self::HomeDirectoryNotFound => "HomeDirectoryNotFound",
+            Self::PathExpansion { .. } => "PathExpansion",
+            Self::InvalidPath(_) => "InvalidPath",
+            Self::CommandExecution { .. } => "CommandExecution",
+            Self::NotInGitRepository => "NotInGitRepository",
+            Self::GitCommandFailed { .. } => "GitCommandFailed",
+            Self::GitRemoteUrlNotFound { .. } => "GitRemoteUrlNotFound",
+            Self::CorruptedConfig { .. } => "CorruptedConfig",
+            Self::SshAgentNotRunning => "SshAgentNotRunning",
+            Self::InvalidEmail { .. } => "InvalidEmail",
+            Self::InvalidSshKey { .. } => "InvalidSshKey",
+            Self::GitNotInstalled => "GitNotInstalled",
+            Self::OpenSshNotFound { .. } => "OpenSshNotFound",
+            Self::DoctorFailed { .. } => "DoctorFailed",
+            Self::KeyPairMismatch { .. } => "KeyPairMismatch",
+            Self::Keyring { .. } => "Keyring",
+            Self::SecretCommand { .. } => "SecretCommand",
+            Self::BackupFailed { .. } => "BackupFailed",
+            Self::RestoreFailed { .. } => "RestoreFailed",
+            Self::MigrationFailed { .. } => "MigrationFailed",
+            Self::SerializationError(_) => "SerializationError",
+            Self::Other(_) => "Other",
+        }
+    }
 }
@@ -0,0 +1,63 @@
+//! User commands run around account switches.
+//!
+//! `post_switch_command` is a template such as `notify-send "{account}"`, run
+//! through the shell after a successful switch. Placeholder values are quoted
+//! so each reaches the command as a single argument. A failing hook is only a
+//! warning: the switch itself has already happened.
+
+use crate::config::{Account, Config};
+use crate::utils::{run_shell_command, shell_quote};
+use colored::*;
+
+/// Where a switch was applied, available to hooks as `{scope}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchScope {
+    /// Global Git config (`use`)
+    Global,
+    /// The current repository (`account`)
+    Local,
+}
+
+impl SwitchScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwitchScope::Global => "global",
+            SwitchScope::Local => "local",
+        }
+    }
+}
+
+/// Fill in `{account}`, `{username}` (the Git user.name), `{login}`, `{email}` and `{scope}`
+fn render_command(template: &str, account: &Account, scope: SwitchScope) -> String {
+    template
+        .replace("{account}", &shell_quote(&account.name))
+        .replace("{username}", &shell_quote(&account.git_name))
+        .replace("{login}", &shell_quote(&account.login))
+        .replace("{email}", &shell_quote(&account.email))
+        .replace("{scope}", scope.as_str())
+}
+
+/// Run the configured post-switch command, if any, warning when it fails
+pub fn run_post_switch(config: &Config, account: &Account, scope: SwitchScope) {
+    let Some(template) = &config.settings.post_switch_command else {
+        return;
+    };
+    let command = render_command(template, account, scope);
+
+    let failure = match run_shell_command(&command) {
+        Ok(output) if output.status.success() => return,
+        Ok(output) => format!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => e.to_string(),
+    };
+    tracing::warn!("post-switch command '{}' failed: {}", command, failure);
+    println!(
+        "{} post-switch command '{}' {}",
+        "⚠".yellow(),
+        command,
+        failure
+    );
+}
@@ -4,17 +4,24 @@ mod commands;
 mod completions;
 mod config;
 mod detection;
+mod doctor;
+mod encryption;
 mod error;
 mod git;
+mod hooks;
 mod manpages;
 mod profiles;
 mod repository;
+mod secrets;
 mod ssh;
+mod state;
+mod status;
 mod templates;
 mod utils;
 mod validation;
 
 use crate::backup::ExportFormat;
+use crate::config::SettingKey;
 use crate::error::GitSwitchError;
 use crate::error::Result;
 use clap::{CommandFactory, Parser, Subcommand};
@@ -40,6 +47,35 @@ struct Cli {
     /// Disable colored output
     #[clap(long, global = true)]
     no_color: bool,
+    /// Force colored output even when not writing to a terminal
+    #[clap(long, global = true, conflicts_with = "no_color")]
+    force_color: bool,
+    /// Skip startup validation, including the Git installation check (use at your own risk)
+    #[clap(long, global = true)]
+    no_verify: bool,
+    /// Use a specific config file instead of the one in the home directory
+    #[clap(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Seconds before a git/ssh subprocess is killed (0 disables the timeout);
+    /// commands that may prompt at the terminal, like ssh-add, are not killed
+    #[clap(long, global = true, value_name = "SECONDS")]
+    command_timeout: Option<u64>,
+    /// Never talk to the SSH agent: no ssh-add on switches and no agent check at startup
+    #[clap(long, global = true)]
+    no_ssh_agent: bool,
+    /// Answer yes to confirmation prompts (remove, analytics clear)
+    #[clap(long, global = true)]
+    yes: bool,
+    /// Keep a legacy JSON config as JSON instead of rewriting it as TOML
+    #[clap(long, global = true)]
+    no_migrate: bool,
+    /// When to draw spinners and progress bars: always, never or auto (when stdout is a terminal)
+    #[clap(long, global = true, value_name = "WHEN")]
+    progress: Option<utils::ProgressMode>,
+    /// Machine-readable output: JSON results where a command supports them
+    /// (list, whoami --all-repos, doctor, status, config path, repo discover) and errors as JSON on stderr
+    #[clap(long, global = true)]
+    json: bool,
 }
 
 /// Defines the available subcommands.
@@ -48,31 +84,118 @@ enum Commands {
     /// Adds a new Git account
     Add {
         /// Name of the account (e.g., "personal", "work")
-        name: String,
-        /// Username for Git config (e.g., "John Doe")
-        username: String,
+        #[clap(required_unless_present = "stdin")]
+        name: Option<String>,
+        /// Name written to user.name (e.g., "John Doe")
+        #[clap(required_unless_present = "stdin")]
+        username: Option<String>,
         /// Email for Git config (e.g., "john.doe@example.com")
-        email: String,
+        #[clap(required_unless_present = "stdin")]
+        email: Option<String>,
         /// Optional path to the SSH key for this account
-        #[clap(long)]
+        #[clap(long, conflicts_with = "no_key")]
         ssh_key_path: Option<PathBuf>,
+        /// Create the account without an SSH key
+        #[clap(long)]
+        no_key: bool,
+        /// Replace an existing account with the same name
+        #[clap(long)]
+        force: bool,
+        /// Fail instead of warning when another account already uses the email
+        #[clap(long)]
+        strict: bool,
+        /// Copy the public key to the clipboard after creation
+        #[clap(long, conflicts_with_all = ["no_key", "stdin"])]
+        copy_key: bool,
+        /// Open the provider's key upload page in the browser after creation
+        #[clap(long, conflicts_with_all = ["no_key", "stdin"])]
+        open: bool,
+        /// Read a TOML/JSON list of account definitions from stdin
+        #[clap(long, conflicts_with_all = ["interactive", "name", "username", "email"])]
+        stdin: bool,
         /// Use interactive mode for account creation
         #[clap(long, short)]
         interactive: bool,
         /// Provider preset (github, gitlab, bitbucket)
         #[clap(long)]
         provider: Option<String>,
+        /// Provider handle matched against remote URLs (defaults to the Git name)
+        #[clap(long, conflicts_with = "stdin")]
+        login: Option<String>,
+        /// Branch set as init.defaultBranch when the account is used globally
+        #[clap(long, value_name = "BRANCH")]
+        default_branch: Option<String>,
+        /// File set as commit.template when the account is applied to a repository
+        #[clap(long, value_name = "PATH")]
+        commit_template: Option<String>,
+        /// Directory set as core.hooksPath when the account is applied to a repository
+        #[clap(long, value_name = "PATH")]
+        hooks_path: Option<String>,
+        /// Git setting applied to repositories with the identity, e.g. push.default=current
+        /// (repeatable; push.*, pull.rebase, pull.ff, fetch.prune and a few others are allowed)
+        #[clap(long = "git-setting", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        git_settings: Vec<(String, String)>,
     },
     /// Lists all configured Git accounts
     List {
         /// Show detailed information
         #[clap(long, short)]
         detailed: bool,
+        /// Nest accounts under their provider
+        #[clap(long)]
+        tree: bool,
+        /// Nest accounts under their provider or group (provider, group)
+        #[clap(long, value_name = "GROUPING")]
+        group_by: Option<commands::ListGrouping>,
+        /// Check whether each account's key is registered with its provider (uses the network)
+        #[clap(long)]
+        check_providers: bool,
+        /// One `name<TAB>username<TAB>email<TAB>provider` line per account, for scripts
+        #[clap(long, conflicts_with_all = ["detailed", "tree", "group_by", "check_providers"])]
+        porcelain: bool,
+        /// Like --porcelain, but end each record with NUL instead of a newline (for xargs -0)
+        #[clap(long, conflicts_with_all = ["detailed", "tree", "group_by", "check_providers"])]
+        null: bool,
     },
     /// Switches to a specified Git account for the current repository
     Use {
         /// Name of the account to use
+        #[clap(required_unless_present_any = ["group", "unset_temp"])]
+        name: Option<String>,
+        /// Use the only account in this group
+        #[clap(long, conflicts_with = "name")]
+        group: Option<String>,
+        /// Seconds before the key expires from the SSH agent
+        #[clap(long, conflicts_with = "no_agent")]
+        lifetime: Option<u64>,
+        /// Don't load the key into the SSH agent
+        #[clap(long)]
+        no_agent: bool,
+        /// Print exports for this shell only, to run as `eval "$(git-switch use --temp <name>)"`;
+        /// Git config is not changed
+        #[clap(long, conflicts_with_all = ["lifetime", "no_agent"])]
+        temp: bool,
+        /// Print the command that undoes `--temp`
+        #[clap(long, conflicts_with_all = ["name", "group", "lifetime", "no_agent", "temp"])]
+        unset_temp: bool,
+        /// Test SSH authentication after switching and warn if it fails
+        #[clap(long, conflicts_with_all = ["temp", "unset_temp"])]
+        verify: bool,
+        /// Like --verify, but switch back to the previous identity if authentication fails
+        #[clap(long, conflicts_with_all = ["temp", "unset_temp"])]
+        verify_strict: bool,
+    },
+    /// Switch to an account globally (--global), in this repository (--local) or both;
+    /// without flags, the repository when inside one and the global config otherwise
+    Switch {
+        /// Name of the account to switch to
         name: String,
+        /// Make the account the global Git identity
+        #[clap(long)]
+        global: bool,
+        /// Apply the account to the current repository
+        #[clap(long)]
+        local: bool,
     },
     /// Removes a configured Git account
     Remove {
@@ -81,23 +204,61 @@ enum Commands {
         /// Skip confirmation prompt
         #[clap(long, short = 'y', action)]
         no_prompt: bool,
+        /// Show what would be removed, including affected profiles, without changing anything
+        #[clap(long)]
+        dry_run: bool,
+        /// Delete the account's SSH key pair without asking
+        #[clap(long, conflicts_with = "keep_key")]
+        delete_key: bool,
+        /// Keep the account's SSH key file without asking
+        #[clap(long)]
+        keep_key: bool,
     },
     /// Manages account settings for the current repository (applies account to current repo)
-    Account {
-        /// Name of the account to apply to the current repository
-        name: String,
+    Account(AccountOpts),
+    /// Modifies the remote URL protocol for the current repository, or adds a remote
+    Remote(RemoteOpts),
+    /// Make every git@github.com: URL (e.g. in `git clone`) use an account's host alias
+    SetupUrlRewrite {
+        /// Account whose host alias the URLs are rewritten to
+        account: String,
     },
-    /// Modifies the remote URL protocol for the current repository
-    Remote {
-        /// Switch remote to HTTPS
-        #[clap(long, conflicts_with = "ssh")]
-        https: bool,
-        /// Switch remote to SSH
-        #[clap(long, conflicts_with = "https")]
-        ssh: bool,
+    /// Remove the URL rewrite installed by setup-url-rewrite
+    RemoveUrlRewrite {
+        /// Account whose rewrite is removed
+        account: String,
     },
     /// Shows the current Git identity and remote status
-    Whoami,
+    Whoami {
+        /// Audit the identity of every repository below the current directory
+        #[clap(long)]
+        all_repos: bool,
+        /// Maximum depth to search with --all-repos
+        #[clap(long, default_value_t = 5, requires = "all_repos")]
+        max_depth: usize,
+        /// Explain step by step how the account was matched
+        #[clap(long, conflicts_with = "all_repos")]
+        explain: bool,
+        /// Print only `account <email> (scope)` on one line, e.g. for a shell prompt
+        #[clap(long, conflicts_with_all = ["all_repos", "explain"])]
+        short: bool,
+        /// Print one line from a template, e.g. "{scope}:{account} <{email}>".
+        /// Placeholders: {account}, {name}, {email}, {scope}, {remote}, {provider}, {mismatch}
+        #[clap(long, conflicts_with_all = ["all_repos", "explain", "short"])]
+        template: Option<String>,
+    },
+    /// List commits whose author email matches no configured account
+    AuditCommits {
+        /// Repository to audit, or a directory to search for repositories
+        #[clap(long, default_value = ".")]
+        path: PathBuf,
+        /// Only audit commits after this revision (since..HEAD)
+        #[clap(long, value_name = "REV")]
+        since: Option<String>,
+        /// Maximum depth to search when --path is not inside a repository
+        #[clap(long, default_value_t = 5)]
+        max_depth: usize,
+    },
     /// Authentication related commands
     Auth(AuthOpts),
     /// Backup and restore commands
@@ -108,15 +269,42 @@ enum Commands {
     Template(TemplateOpts),
     /// Analytics and usage statistics
     Analytics(AnalyticsOpts),
+    /// Manage the switch history log
+    History(HistoryOpts),
+    /// Upgrade a config written by an older version
+    #[clap(group(clap::ArgGroup::new("mode").required(true).args(["check", "apply"])))]
+    Migrate {
+        /// Report what the migration would change without writing anything
+        #[clap(long)]
+        check: bool,
+        /// Apply the migration and save the config
+        #[clap(long)]
+        apply: bool,
+    },
     /// Repository detection and suggestions
-    Detect,
+    Detect {
+        /// Apply the detected account to the current repository
+        #[clap(long)]
+        apply: bool,
+        /// Show which account would be applied without changing anything
+        #[clap(long, requires = "apply")]
+        dry_run: bool,
+    },
+    /// SSH key management
+    Key(KeyOpts),
+    /// Check the environment, configuration and account keys for problems
+    Doctor,
+    /// Summarize the global and repository identities and what to do next
+    Status,
+    /// Global settings management
+    Config(ConfigOpts),
     /// Repository discovery and bulk operations
     Repo(RepoOpts),
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
         #[clap(value_enum)]
-        shell: clap_complete::Shell,
+        shell: completions::CompletionShell,
     },
     /// Generate man pages
     Man {
@@ -126,6 +314,221 @@ enum Commands {
     },
 }
 
+#[derive(Parser, Debug)]
+#[clap(args_conflicts_with_subcommands = true)]
+struct RemoteOpts {
+    #[clap(subcommand)]
+    command: Option<RemoteCommands>,
+    /// Switch remote to HTTPS
+    #[clap(long, conflicts_with = "ssh")]
+    https: bool,
+    /// Switch remote to SSH
+    #[clap(long, conflicts_with = "https")]
+    ssh: bool,
+    /// Only convert the fetch URL
+    #[clap(long, conflicts_with = "push")]
+    fetch: bool,
+    /// Only convert the push URL
+    #[clap(long, conflicts_with = "fetch")]
+    push: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum RemoteCommands {
+    /// Add a remote that uses the account's SSH host alias
+    Add {
+        /// Remote name, e.g. "fork"
+        name: String,
+        /// Remote URL (HTTPS or SSH)
+        url: String,
+        /// Account to route the remote through (detected from the URL if omitted)
+        #[clap(long)]
+        account: Option<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct AccountOpts {
+    #[clap(subcommand)]
+    command: Option<AccountCommands>,
+    /// Name of the account to apply to the current repository
+    #[clap(required = true)]
+    name: Option<String>,
+    /// Use one of the account's keys instead of the primary (index, 0 = primary, or path)
+    #[clap(long, value_name = "PATH_OR_INDEX")]
+    key: Option<String>,
+    /// Test SSH authentication after applying and warn if it fails
+    #[clap(long)]
+    verify: bool,
+    /// Like --verify, but restore the previous repository identity if authentication fails
+    #[clap(long)]
+    verify_strict: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum AccountCommands {
+    /// Show account details including the SSH key fingerprint
+    Show {
+        /// Account name
+        account: String,
+        /// Copy the public key to the clipboard
+        #[clap(long)]
+        copy_key: bool,
+    },
+    /// Add a remote URL pattern (substring or glob) used to detect this account
+    AddUrlPattern {
+        /// Account name
+        account: String,
+        /// URL substring or glob, e.g. "ghe.corp.example" or "*corp*/team/*"
+        pattern: String,
+    },
+    /// Set the password manager reference for the key passphrase ("none" clears it)
+    SetPassphraseRef {
+        /// Account name
+        account: String,
+        /// Reference passed to the secret-command setting, e.g. "op://vault/item/password"
+        reference: String,
+    },
+    /// Set the branch used as init.defaultBranch for this account ("none" clears it)
+    SetDefaultBranch {
+        /// Account name
+        account: String,
+        /// Branch name, e.g. "main"
+        branch: String,
+    },
+    /// Set the file applied as commit.template in repositories ("none" clears it)
+    SetCommitTemplate {
+        /// Account name
+        account: String,
+        /// Template file, e.g. "~/.config/git/work-template.txt"
+        path: String,
+    },
+    /// Set the directory applied as core.hooksPath in repositories ("none" clears it)
+    SetHooksPath {
+        /// Account name
+        account: String,
+        /// Hooks directory, e.g. "~/work/git-hooks"
+        path: String,
+    },
+    /// Set an allowlisted Git setting such as push.default applied to repositories ("none" clears it)
+    SetGitSetting {
+        /// Account name
+        account: String,
+        /// Setting key, e.g. push.default
+        key: String,
+        /// Setting value, e.g. current
+        value: String,
+    },
+    /// Set the email domain (e.g. "company.com") used to detect this account ("none" clears it)
+    SetDomain {
+        /// Account name
+        account: String,
+        /// Domain of the organization's committer emails
+        domain: String,
+    },
+    /// Change an account's provider and rewrite its SSH config entry ("none" clears it)
+    SetProvider {
+        /// Account name
+        account: String,
+        /// Provider, e.g. github or gitlab
+        provider: String,
+    },
+    /// Set the SSH host name, e.g. a GitHub Enterprise server ("none" uses the provider's)
+    SetHost {
+        /// Account name
+        account: String,
+        /// Host name, e.g. github.example.com
+        host: String,
+    },
+    /// Change an account's email
+    SetEmail {
+        /// Account name
+        account: String,
+        /// New email
+        email: String,
+    },
+    /// Change the name an account writes to user.name
+    #[clap(alias = "set-username")]
+    SetGitName {
+        /// Account name
+        account: String,
+        /// New Git name, e.g. "John Doe"
+        git_name: String,
+    },
+    /// Change the provider handle an account is detected by in remote URLs
+    SetLogin {
+        /// Account name
+        account: String,
+        /// Provider handle, e.g. jdoe
+        login: String,
+    },
+    /// Move an account into another config file, keeping its SSH config entry
+    Move {
+        /// Account name
+        account: String,
+        /// Config file that receives the account (created if missing)
+        #[clap(long, value_name = "CONFIG_PATH")]
+        to: PathBuf,
+    },
+    /// Show which accounts a remote URL would be detected as, and why
+    CheckUrl {
+        /// Remote URL, e.g. git@github.com:org/repo.git
+        url: String,
+    },
+    /// Check that the account's private and public key are valid and belong together
+    VerifyPair {
+        /// Account name
+        account: String,
+    },
+    /// Mark an account as used now without switching to it
+    Touch {
+        /// Account name
+        account: String,
+        /// Also record that the account's key was verified now
+        #[clap(long)]
+        verified: bool,
+    },
+    /// Copy the account's key pair to a file (and <file>.pub) to move it to another machine
+    ExportKey {
+        /// Account name
+        account: String,
+        /// Destination of the private key; the public key is written next to it as .pub
+        #[clap(long, value_name = "PATH")]
+        output: PathBuf,
+        /// Overwrite existing files
+        #[clap(long)]
+        force: bool,
+    },
+    /// Copy an exported key pair into the account's key path and validate it
+    ImportKey {
+        /// Account name
+        account: String,
+        /// Private key to import; its public key is read from <path>.pub
+        #[clap(long, value_name = "PATH")]
+        from: PathBuf,
+        /// Overwrite the account's existing key files
+        #[clap(long)]
+        force: bool,
+    },
+    /// Recognize another email (e.g. a previous provider email) as this account
+    AddEmail {
+        /// Account name
+        account: String,
+        /// Email address
+        email: String,
+    },
+    /// Show recent account switches, newest first
+    History {
+        /// Only show switches to this account
+        #[clap(long)]
+        account: Option<String>,
+        /// Maximum number of switches to show
+        #[clap(long, default_value = "20")]
+        limit: usize,
+    },
+}
+
 #[derive(Parser, Debug)]
 struct AuthOpts {
     #[clap(subcommand)]
@@ -135,7 +538,54 @@ struct AuthOpts {
 #[derive(Subcommand, Debug)]
 enum AuthCommands {
     /// Tests SSH authentication for the currently configured account or a specific key
-    Test,
+    Test {
+        /// Only test the accounts in this group
+        #[clap(long)]
+        group: Option<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+struct KeyOpts {
+    #[clap(subcommand)]
+    command: KeyCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum KeyCommands {
+    /// Replace an account's key with a new one, keeping the old pair as .old
+    Regenerate {
+        /// Account name
+        account: String,
+        /// Key type (rsa, ed25519, ecdsa)
+        #[clap(long, default_value = "rsa")]
+        key_type: ssh::KeyType,
+        /// Also replace keys that git-switch did not generate
+        #[clap(long)]
+        force: bool,
+    },
+    /// Make one of an account's additional keys its primary key
+    Promote {
+        /// Account name
+        account: String,
+        /// Additional key to promote, as a path or an index (0 is the primary)
+        key: String,
+    },
+    /// Attach a key to an account, generating a new one or adopting an existing one
+    #[clap(group(clap::ArgGroup::new("source").required(true).args(["generate", "path"])))]
+    Set {
+        /// Account name
+        account: String,
+        /// Generate a new key at the default path
+        #[clap(long)]
+        generate: bool,
+        /// Adopt an existing private key
+        #[clap(long, value_name = "PATH")]
+        path: Option<PathBuf>,
+        /// Key type for --generate (rsa, ed25519, ecdsa)
+        #[clap(long, default_value = "rsa", requires = "generate")]
+        key_type: ssh::KeyType,
+    },
 }
 
 #[derive(Parser, Debug)]
@@ -151,9 +601,32 @@ enum BackupCommands {
         /// Path to save the backup file
         #[clap(long, short)]
         output: Option<PathBuf>,
+        /// Keep only the newest N default backups, pruning older ones (at least 1)
+        #[clap(long, value_parser = parse_keep_count)]
+        keep: Option<usize>,
+        /// Encrypt the backup with a passphrase (prompted, or GIT_SWITCH_BACKUP_PASSPHRASE)
+        #[clap(long)]
+        encrypt: bool,
+        /// Bundle each account's private and public key (requires --encrypt)
+        #[clap(long)]
+        include_keys: bool,
     },
+    /// List available default backups
+    List,
     /// Restore configuration from a backup file
     Restore {
+        /// Path to the backup file
+        #[clap(required_unless_present = "latest")]
+        backup_file: Option<PathBuf>,
+        /// Restore the most recent default backup
+        #[clap(long, conflicts_with = "backup_file")]
+        latest: bool,
+        /// Show the account changes without restoring
+        #[clap(long)]
+        preview: bool,
+    },
+    /// Check that a backup loads and passes validation, without restoring it
+    Verify {
         /// Path to the backup file
         backup_file: PathBuf,
     },
@@ -172,6 +645,18 @@ enum BackupCommands {
         /// Merge with existing accounts instead of replacing
         #[clap(long, short)]
         merge: bool,
+        /// Show the account changes without importing
+        #[clap(long)]
+        preview: bool,
+        /// Prefix added to every imported account name (implies --merge)
+        #[clap(long)]
+        prefix: Option<String>,
+        /// Suffix added to every imported account name (implies --merge)
+        #[clap(long)]
+        suffix: Option<String>,
+        /// TOML file of `old = "new"` account names to import under (implies --merge)
+        #[clap(long, value_name = "FILE", conflicts_with_all = ["prefix", "suffix"])]
+        rename_map: Option<PathBuf>,
     },
 }
 
@@ -206,6 +691,9 @@ enum ProfileCommands {
         /// Override the default account
         #[clap(long, short)]
         account: Option<String>,
+        /// Apply the profile's account to the global Git config and mark it active
+        #[clap(long, short)]
+        global: bool,
     },
     /// Update an existing profile
     Update {
@@ -224,11 +712,37 @@ enum ProfileCommands {
         #[clap(long)]
         default: Option<String>,
     },
+    /// Rename a profile, keeping its accounts and history
+    Rename {
+        /// Current profile name
+        old: String,
+        /// New profile name
+        new: String,
+    },
+    /// Copy a profile under a new name
+    Clone {
+        /// Profile to copy
+        source: String,
+        /// Name of the new profile
+        dest: String,
+        /// Default account for the new profile (must be a member)
+        #[clap(long)]
+        default: Option<String>,
+    },
     /// Remove a profile
     Remove {
         /// Profile name
         name: String,
     },
+    /// Set or clear the default profile used when no account is detected
+    Default {
+        /// Profile name
+        #[clap(required_unless_present = "clear")]
+        name: Option<String>,
+        /// Clear the default profile
+        #[clap(long, conflicts_with = "name")]
+        clear: bool,
+    },
     /// Show profile statistics
     Stats,
 }
@@ -248,11 +762,23 @@ enum TemplateCommands {
         /// Template name (github, gitlab, bitbucket, etc.)
         template: String,
         /// Account name
-        name: String,
+        #[clap(required_unless_present = "interactive")]
+        name: Option<String>,
         /// Username
-        username: String,
+        #[clap(required_unless_present = "interactive")]
+        username: Option<String>,
         /// Email address
-        email: String,
+        #[clap(required_unless_present = "interactive")]
+        email: Option<String>,
+        /// Prompt for the name, username and email instead
+        #[clap(long, short, conflicts_with_all = ["name", "username", "email"])]
+        interactive: bool,
+        /// Use an existing SSH key instead of generating one
+        #[clap(long, conflicts_with = "no_key")]
+        ssh_key_path: Option<PathBuf>,
+        /// Create the account without an SSH key
+        #[clap(long)]
+        no_key: bool,
     },
 }
 
@@ -267,7 +793,51 @@ enum AnalyticsCommands {
     /// Show usage analytics
     Show,
     /// Clear analytics data
-    Clear,
+    Clear {
+        /// Report how many accounts' stats would be cleared without deleting anything
+        #[clap(long)]
+        dry_run: bool,
+        /// Copy the analytics file to <file>.backup before clearing it
+        #[clap(long)]
+        backup: bool,
+    },
+}
+
+#[derive(Parser, Debug)]
+struct HistoryOpts {
+    #[clap(subcommand)]
+    command: HistoryCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommands {
+    /// Drop switches older than the history-retention-days setting
+    Prune {
+        /// Remove the old switches instead of only reporting them
+        #[clap(long)]
+        apply: bool,
+    },
+}
+
+#[derive(Parser, Debug)]
+struct ConfigOpts {
+    #[clap(subcommand)]
+    command: ConfigCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Change a global setting
+    Set {
+        /// Setting name (default-provider)
+        key: SettingKey,
+        /// New value for the setting
+        value: String,
+    },
+    /// Print the resolved locations of config, profiles, analytics and SSH files
+    Path,
+    /// Open the config file in $VISUAL or $EDITOR, restoring it if the edit is invalid
+    Edit,
 }
 
 #[derive(Parser, Debug)]
@@ -286,6 +856,18 @@ enum RepoCommands {
         /// Maximum depth to search
         #[clap(long, short, default_value_t = 5)]
         max_depth: usize,
+        /// Also search dot directories such as ~/.config (slower)
+        #[clap(long)]
+        hidden: bool,
+        /// Print each repository as a JSON line as soon as it is analyzed
+        #[clap(long)]
+        stream: bool,
+        /// Only list repositories suggested for this account that do not use it yet
+        #[clap(long)]
+        account: Option<String>,
+        /// Number of repositories analyzed at once while the walk continues
+        #[clap(long, value_name = "N", default_value_t = repository::DEFAULT_DISCOVER_PARALLELISM)]
+        parallel: usize,
     },
     /// List discovered repositories
     List,
@@ -297,20 +879,74 @@ enum RepoCommands {
         /// Force application even for low-confidence matches
         #[clap(long)]
         force: bool,
+        /// Ask whether to apply, skip or pick another account for each low-confidence match
+        #[clap(long, conflicts_with = "force")]
+        interactive_per_conflict: bool,
+        /// Number of repositories configured at once
+        #[clap(long, value_name = "N", default_value_t = repository::DEFAULT_APPLY_PARALLELISM)]
+        parallel: usize,
+        /// Discover repositories under this directory first
+        #[clap(long)]
+        path: Option<std::path::PathBuf>,
     },
     /// Generate a report of repository analysis
     Report {
         /// Output path for the report
         #[clap(long, short)]
         output: Option<std::path::PathBuf>,
+        /// Discover repositories under this directory first
+        #[clap(long)]
+        path: Option<std::path::PathBuf>,
+        /// Add a section listing repositories added, removed or with a changed identity
+        /// since the saved snapshot
+        #[clap(long)]
+        compare_previous: bool,
+        /// Save this scan as the snapshot for later --compare-previous reports
+        #[clap(long)]
+        snapshot: bool,
     },
     /// Interactive repository configuration
     Interactive,
 }
 
+/// Parse a `KEY=VALUE` argument
+fn parse_key_value(arg: &str) -> std::result::Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))
+}
+
+/// Parse a `--keep` count, which must leave at least one backup
+fn parse_keep_count(arg: &str) -> std::result::Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(count) => Ok(count),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 /// Main function to run the git-switch application.
 fn main() {
-    if let Err(e) = run_cli() {
+    // ssh-add runs git-switch as SSH_ASKPASS for passphrases from a secret command
+    if secrets::run_askpass() {
+        return;
+    }
+
+    let cli = Cli::parse();
+    let json = cli.json;
+    if let Err(e) = run_cli(cli) {
+        let git_switch_error = e.downcast_ref::<GitSwitchError>();
+        if json {
+            let (code, kind) = git_switch_error
+                .map(|err| (err.exit_code(), err.kind()))
+                .unwrap_or((1, "Unknown"));
+            eprintln!(
+                "{}",
+                serde_json::json!({ "error": e.to_string(), "code": code, "kind": kind })
+            );
+            exit(code);
+        }
+
         let error_msg = if std::env::var("NO_COLOR").is_ok() {
             format!("Error: {}", e)
         } else {
@@ -319,7 +955,7 @@ fn main() {
         eprintln!("{}", error_msg);
 
         // Attempt to downcast anyhow::Error to GitSwitchError
-        if let Some(git_switch_error) = e.downcast_ref::<GitSwitchError>() {
+        if let Some(git_switch_error) = git_switch_error {
             exit(git_switch_error.exit_code());
         } else {
             // If it's not a GitSwitchError, exit with a generic code
@@ -328,9 +964,27 @@ fn main() {
     }
 }
 
+/// Central color control. The flags beat the NO_COLOR and CLICOLOR_FORCE
+/// environment variables, which beat the `colored_output` setting; `colored`
+/// weighs whatever is left against TTY detection.
+fn configure_colors(no_color: bool, force_color: bool) {
+    if no_color {
+        unsafe {
+            std::env::set_var("NO_COLOR", "1");
+        }
+        colored::control::set_override(false);
+    } else if force_color {
+        unsafe {
+            std::env::remove_var("NO_COLOR");
+        }
+        colored::control::set_override(true);
+    }
+}
+
 /// Helper function to contain the main CLI logic.
-fn run_cli() -> Result<(), anyhow::Error> {
-    let cli = Cli::parse();
+fn run_cli(cli: Cli) -> Result<(), anyhow::Error> {
+    let json = cli.json;
+    let yes = cli.yes;
 
     // Initialize logging
     if cli.verbose {
@@ -338,72 +992,422 @@ fn run_cli() -> Result<(), anyhow::Error> {
     }
 
     // Set color preference
-    if cli.no_color {
+    configure_colors(cli.no_color, cli.force_color);
+
+    // Override the config file location
+    if let Some(config_path) = &cli.config {
         unsafe {
-            std::env::set_var("NO_COLOR", "1");
+            std::env::set_var(config::CONFIG_PATH_ENV, config_path);
         }
     }
 
-    // Perform startup validation
-    if let Err(e) = validation::validate_startup() {
-        tracing::warn!("Startup validation failed: {}", e);
+    // Override the subprocess timeout
+    if let Some(timeout) = cli.command_timeout {
+        unsafe {
+            std::env::set_var(utils::COMMAND_TIMEOUT_ENV, timeout.to_string());
+        }
+    }
+
+    // Disable the SSH agent for this run
+    if cli.no_ssh_agent {
+        unsafe {
+            std::env::set_var(config::NO_SSH_AGENT_ENV, "1");
+        }
+    }
+
+    // Read the config in its current format
+    if cli.no_migrate {
+        unsafe {
+            std::env::set_var(config::NO_MIGRATE_ENV, "1");
+        }
     }
 
     let mut config = config::load_config()?;
 
+    // The colored_output setting only applies when no flag or environment variable decided
+    if !config.settings.colored_output
+        && !cli.force_color
+        && std::env::var_os("CLICOLOR_FORCE").is_none()
+    {
+        configure_colors(true, false);
+    }
+    if !matches!(cli.command, Commands::Migrate { .. }) {
+        config::warn_if_migration_pending(&config);
+    }
+
+    // --progress beats GIT_SWITCH_PROGRESS, which beats the show_progress setting
+    let progress = match cli.progress {
+        Some(mode) => Some(mode),
+        None if std::env::var_os(utils::PROGRESS_ENV).is_some() => None,
+        None if config.settings.show_progress => Some(utils::ProgressMode::Auto),
+        None => Some(utils::ProgressMode::Never),
+    };
+    if let Some(mode) = progress {
+        unsafe {
+            std::env::set_var(utils::PROGRESS_ENV, mode.as_str());
+        }
+    }
+
+    // Perform startup validation; --no-verify overrides the strict_startup setting
+    if !cli.no_verify {
+        let strict = config.settings.strict_startup;
+        if let Err(e) = validation::validate_startup(strict, config.ssh_agent_enabled(), json) {
+            if strict {
+                return Err(e.into());
+            }
+            tracing::warn!("Startup validation failed: {}", e);
+        }
+    }
+
     match cli.command {
         Commands::Add {
             name,
             username,
             email,
             ssh_key_path,
+            no_key,
+            force,
+            strict,
+            copy_key,
+            open,
+            stdin,
             interactive,
             provider,
+            login,
+            default_branch,
+            commit_template,
+            hooks_path,
+            git_settings,
         } => {
-            if interactive {
-                commands::add_account_interactive(&mut config, &name)?;
+            let options = commands::AddAccountOptions {
+                login,
+                no_key,
+                force,
+                strict,
+                copy_key,
+                open_key_page: open,
+                default_branch,
+                key_name: None,
+                commit_template,
+                hooks_path,
+                git_settings,
+            };
+            if stdin {
+                commands::add_accounts_from_stdin(&mut config, options)?;
+            } else if interactive {
+                commands::add_account_interactive(&mut config, &name.unwrap_or_default())?;
             } else {
                 commands::add_account(
                     &mut config,
-                    &name,
-                    &username,
-                    &email,
+                    &name.unwrap_or_default(),
+                    &username.unwrap_or_default(),
+                    &email.unwrap_or_default(),
                     ssh_key_path,
                     provider,
+                    options,
                 )?;
             }
         }
-        Commands::List { detailed } => commands::list_accounts(&config, detailed)?,
-        Commands::Use { name } => commands::use_account_globally(&config, &name)?,
-        Commands::Remove { name, no_prompt } => {
-            commands::remove_account(&mut config, &name, no_prompt)?;
+        Commands::List {
+            detailed,
+            tree,
+            group_by,
+            check_providers,
+            porcelain,
+            null,
+        } => {
+            // --json is global, so clap cannot declare the conflicts on the subcommand
+            if json && check_providers {
+                return Err(GitSwitchError::Other(
+                    "--check-providers cannot be combined with --json".to_string(),
+                )
+                .into());
+            }
+            if json && (porcelain || null) {
+                return Err(GitSwitchError::Other(
+                    "--porcelain and --null cannot be combined with --json".to_string(),
+                )
+                .into());
+            }
+            commands::list_accounts(
+                &config,
+                commands::ListOptions {
+                    detailed,
+                    group_by: group_by.or(tree.then_some(commands::ListGrouping::Provider)),
+                    json,
+                    check_providers,
+                    porcelain,
+                    null,
+                },
+            )?
         }
-        Commands::Account { name } => {
-            commands::handle_account_subcommand(&config, &name)?;
+        Commands::Use {
+            name,
+            group,
+            lifetime,
+            no_agent,
+            temp,
+            unset_temp,
+            verify,
+            verify_strict,
+        } => {
+            let name = match group {
+                Some(group) => commands::group_account_name(&config, &group)?,
+                None => name.unwrap_or_default(),
+            };
+            if unset_temp {
+                commands::print_temp_unset();
+            } else if temp {
+                commands::print_temp_switch(&config, &name)?;
+            } else {
+                commands::use_account_globally(
+                    &config,
+                    &name,
+                    commands::AgentOptions { no_agent, lifetime },
+                    commands::SwitchVerify::from_flags(verify, verify_strict),
+                )?
+            }
         }
-        Commands::Remote { https, ssh } => {
-            commands::handle_remote_subcommand(https, ssh)?;
+        Commands::Switch {
+            name,
+            global,
+            local,
+        } => {
+            commands::switch_account(&config, &name, global, local)?;
         }
-        Commands::Whoami => {
-            commands::handle_whoami_subcommand(&config)?;
+        Commands::Remove {
+            name,
+            no_prompt,
+            dry_run,
+            delete_key,
+            keep_key,
+        } => {
+            let key_file = if delete_key {
+                commands::KeyFileChoice::Delete
+            } else if keep_key {
+                commands::KeyFileChoice::Keep
+            } else {
+                commands::KeyFileChoice::Ask
+            };
+            commands::remove_account(&mut config, &name, no_prompt || yes, dry_run, key_file)?;
+        }
+        Commands::Account(account_opts) => match account_opts.command {
+            Some(AccountCommands::Show { account, copy_key }) => {
+                commands::show_account(&config, &account, copy_key)?;
+            }
+            Some(AccountCommands::AddUrlPattern { account, pattern }) => {
+                commands::add_url_pattern(&mut config, &account, &pattern)?;
+            }
+            Some(AccountCommands::SetPassphraseRef { account, reference }) => {
+                commands::set_passphrase_ref(&mut config, &account, &reference)?;
+            }
+            Some(AccountCommands::SetDefaultBranch { account, branch }) => {
+                commands::set_default_branch(&mut config, &account, &branch)?;
+            }
+            Some(AccountCommands::SetCommitTemplate { account, path }) => {
+                commands::set_commit_template(&mut config, &account, &path)?;
+            }
+            Some(AccountCommands::SetHooksPath { account, path }) => {
+                commands::set_hooks_path(&mut config, &account, &path)?;
+            }
+            Some(AccountCommands::SetGitSetting {
+                account,
+                key,
+                value,
+            }) => {
+                commands::set_git_setting(&mut config, &account, &key, &value)?;
+            }
+            Some(AccountCommands::SetDomain { account, domain }) => {
+                commands::set_email_domain(&mut config, &account, &domain)?;
+            }
+            Some(AccountCommands::SetProvider { account, provider }) => {
+                commands::set_provider(&mut config, &account, &provider)?;
+            }
+            Some(AccountCommands::SetHost { account, host }) => {
+                commands::set_host(&mut config, &account, &host)?;
+            }
+            Some(AccountCommands::SetEmail { account, email }) => {
+                commands::set_email(&mut config, &account, &email)?;
+            }
+            Some(AccountCommands::SetGitName { account, git_name }) => {
+                commands::set_git_name(&mut config, &account, &git_name)?;
+            }
+            Some(AccountCommands::SetLogin { account, login }) => {
+                commands::set_login(&mut config, &account, &login)?;
+            }
+            Some(AccountCommands::Move { account, to }) => {
+                commands::move_account(&mut config, &account, &to)?;
+            }
+            Some(AccountCommands::CheckUrl { url }) => {
+                commands::check_url(&config, &url, json)?;
+            }
+            Some(AccountCommands::VerifyPair { account }) => {
+                commands::verify_account_key_pair(&config, &account)?;
+            }
+            Some(AccountCommands::ExportKey {
+                account,
+                output,
+                force,
+            }) => {
+                commands::export_account_key(&config, &account, &output, force)?;
+            }
+            Some(AccountCommands::ImportKey {
+                account,
+                from,
+                force,
+            }) => {
+                commands::import_account_key(&mut config, &account, &from, force)?;
+            }
+            Some(AccountCommands::Touch { account, verified }) => {
+                commands::touch_account(&config, &account, verified)?;
+            }
+            Some(AccountCommands::AddEmail { account, email }) => {
+                commands::add_account_email(&mut config, &account, &email)?;
+            }
+            Some(AccountCommands::History { account, limit }) => {
+                analytics::show_history(account.as_deref(), limit, json)?;
+            }
+            None => {
+                if let Some(name) = account_opts.name {
+                    commands::handle_account_subcommand(
+                        &config,
+                        &name,
+                        account_opts.key.as_deref(),
+                        commands::SwitchVerify::from_flags(
+                            account_opts.verify,
+                            account_opts.verify_strict,
+                        ),
+                    )?;
+                }
+            }
+        },
+        Commands::Remote(remote_opts) => match remote_opts.command {
+            Some(RemoteCommands::Add { name, url, account }) => {
+                commands::add_remote(&config, &name, &url, account.as_deref())?;
+            }
+            None => {
+                let scope = if remote_opts.fetch {
+                    commands::RemoteScope::Fetch
+                } else if remote_opts.push {
+                    commands::RemoteScope::Push
+                } else {
+                    commands::RemoteScope::Both
+                };
+                commands::handle_remote_subcommand(remote_opts.https, remote_opts.ssh, scope)?;
+            }
+        },
+        Commands::AuditCommits {
+            path,
+            since,
+            max_depth,
+        } => commands::audit_commits(&config, &path, since.as_deref(), max_depth)?,
+        Commands::Whoami {
+            all_repos,
+            max_depth,
+            explain,
+            short,
+            template,
+        } => {
+            if json && !all_repos {
+                return Err(GitSwitchError::Other(
+                    "whoami supports --json only with --all-repos".to_string(),
+                )
+                .into());
+            } else if all_repos {
+                commands::handle_whoami_all_repos(&config, max_depth, json)?;
+            } else if short {
+                commands::handle_whoami_short(&config)?;
+            } else if let Some(template) = template {
+                commands::handle_whoami_template(&config, &template)?;
+            } else {
+                commands::handle_whoami_subcommand(&config, explain)?;
+            }
         }
         Commands::Auth(auth_opts) => match auth_opts.command {
-            AuthCommands::Test => {
-                commands::handle_auth_test_subcommand(&config)?;
+            AuthCommands::Test { group } => {
+                commands::handle_auth_test_subcommand(&config, group.as_deref())?;
+            }
+        },
+        Commands::Doctor => doctor::run_doctor(&config, json)?,
+        Commands::Status => status::run_status(&config, json)?,
+        Commands::Key(key_opts) => match key_opts.command {
+            KeyCommands::Regenerate {
+                account,
+                key_type,
+                force,
+            } => {
+                commands::regenerate_account_key(&config, &account, key_type, force)?;
+            }
+            KeyCommands::Promote { account, key } => {
+                commands::promote_account_key(&mut config, &account, &key)?;
+            }
+            KeyCommands::Set {
+                account,
+                generate,
+                path,
+                key_type,
+            } => {
+                let source = match path {
+                    Some(path) if !generate => commands::KeySource::Path(path),
+                    _ => commands::KeySource::Generate(key_type),
+                };
+                commands::set_account_key(&mut config, &account, source)?;
             }
         },
         Commands::Backup(backup_opts) => match backup_opts.command {
-            BackupCommands::Create { output } => {
-                backup::backup_config(output.as_deref())?;
+            BackupCommands::Create {
+                output,
+                keep,
+                encrypt,
+                include_keys,
+            } => {
+                backup::backup_config(
+                    output.as_deref(),
+                    backup::BackupOptions {
+                        keep,
+                        encrypt,
+                        include_keys,
+                    },
+                )?;
             }
-            BackupCommands::Restore { backup_file } => {
-                backup::restore_config(&backup_file)?;
+            BackupCommands::List => {
+                backup::list_backups()?;
+            }
+            BackupCommands::Restore {
+                backup_file,
+                latest,
+                preview,
+            } => {
+                let backup_file = match backup_file {
+                    Some(path) if !latest => path,
+                    _ => backup::latest_backup()?,
+                };
+                backup::restore_config(&backup_file, preview)?;
+            }
+            BackupCommands::Verify { backup_file } => {
+                backup::verify_backup_file(&backup_file)?;
             }
             BackupCommands::Export { output, format } => {
                 backup::export_accounts(&output, format)?;
             }
-            BackupCommands::Import { input, merge } => {
-                backup::import_accounts(&input, merge)?;
+            BackupCommands::Import {
+                input,
+                merge,
+                preview,
+                prefix,
+                suffix,
+                rename_map,
+            } => {
+                backup::import_accounts(
+                    &input,
+                    &backup::ImportOptions {
+                        merge,
+                        preview,
+                        prefix,
+                        suffix,
+                        rename_map,
+                    },
+                )?;
             }
         },
         Commands::Profile(profile_opts) => match profile_opts.command {
@@ -420,9 +1424,13 @@ fn run_cli() -> Result<(), anyhow::Error> {
                 let profile_manager = profiles::ProfileManager::new(config)?;
                 profile_manager.list_profiles()?;
             }
-            ProfileCommands::Use { name, account } => {
+            ProfileCommands::Use {
+                name,
+                account,
+                global,
+            } => {
                 let mut profile_manager = profiles::ProfileManager::new(config)?;
-                profile_manager.switch_profile(&name, account)?;
+                profile_manager.switch_profile(&name, account, global)?;
             }
             ProfileCommands::Update {
                 name,
@@ -440,10 +1448,26 @@ fn run_cli() -> Result<(), anyhow::Error> {
                     default,
                 )?;
             }
+            ProfileCommands::Clone {
+                source,
+                dest,
+                default,
+            } => {
+                let mut profile_manager = profiles::ProfileManager::new(config)?;
+                profile_manager.clone_profile(&source, dest, default)?;
+            }
+            ProfileCommands::Rename { old, new } => {
+                let mut profile_manager = profiles::ProfileManager::new(config)?;
+                profile_manager.rename_profile(&old, new)?;
+            }
             ProfileCommands::Remove { name } => {
                 let mut profile_manager = profiles::ProfileManager::new(config)?;
                 profile_manager.delete_profile(&name)?;
             }
+            ProfileCommands::Default { name, clear: _ } => {
+                let mut profile_manager = profiles::ProfileManager::new(config)?;
+                profile_manager.set_default_profile(name.as_deref())?;
+            }
             ProfileCommands::Stats => {
                 let profile_manager = profiles::ProfileManager::new(config)?;
                 profile_manager.get_profile_stats()?;
@@ -458,46 +1482,155 @@ fn run_cli() -> Result<(), anyhow::Error> {
                 name,
                 username,
                 email,
+                ssh_key_path,
+                no_key,
+                interactive,
             } => {
-                let tmpl = templates::get_template(&template)?;
-                let account =
-                    templates::create_account_from_template(&name, &username, &email, &tmpl);
-                config.accounts.insert(name.clone(), account);
-                config::save_config(&config)?;
-                println!(
-                    "{} Account '{}' created from {} template",
-                    "✓".green().bold(),
-                    name.cyan(),
-                    template.cyan()
-                );
+                if interactive {
+                    commands::add_account_from_template_interactive(
+                        &mut config,
+                        &template,
+                        ssh_key_path,
+                        no_key,
+                    )?
+                } else {
+                    commands::add_account_from_template(
+                        &mut config,
+                        &template,
+                        &name.unwrap_or_default(),
+                        &username.unwrap_or_default(),
+                        &email.unwrap_or_default(),
+                        ssh_key_path,
+                        no_key,
+                    )?
+                }
+            }
+        },
+        Commands::History(history_opts) => match history_opts.command {
+            HistoryCommands::Prune { apply } => {
+                analytics::prune_history(config.settings.history_retention_days, apply)?;
             }
         },
         Commands::Analytics(analytics_opts) => match analytics_opts.command {
             AnalyticsCommands::Show => {
                 analytics::show_analytics(&config)?;
             }
-            AnalyticsCommands::Clear => {
-                analytics::clear_analytics()?;
+            AnalyticsCommands::Clear { dry_run, backup } => {
+                analytics::clear_analytics(analytics::ClearOptions {
+                    dry_run,
+                    backup,
+                    yes,
+                })?;
             }
         },
-        Commands::Detect => {
-            detection::suggest_account(&config)?;
-            detection::check_account_mismatch(&config)?;
+        Commands::Migrate { check: _, apply } => {
+            commands::handle_migrate_subcommand(&mut config, apply)?;
         }
+        Commands::SetupUrlRewrite { account } => {
+            commands::setup_url_rewrite(&config, &account)?;
+        }
+        Commands::RemoveUrlRewrite { account } => {
+            commands::remove_url_rewrite(&config, &account)?;
+        }
+        Commands::Detect { apply, dry_run } => {
+            if apply {
+                commands::apply_detected_account(&config, dry_run)?;
+            } else {
+                detection::suggest_account(&config)?;
+                detection::check_account_mismatch(&config)?;
+            }
+        }
+        Commands::Config(config_opts) => match config_opts.command {
+            ConfigCommands::Set { key, value } => {
+                commands::handle_config_set_subcommand(&mut config, key, &value)?;
+            }
+            ConfigCommands::Path => {
+                commands::handle_config_path_subcommand(&config, json)?;
+            }
+            ConfigCommands::Edit => {
+                commands::handle_config_edit_subcommand(&config)?;
+            }
+        },
         Commands::Repo(repo_opts) => {
             let mut repo_manager = repository::RepoManager::new(config);
             match repo_opts.command {
-                RepoCommands::Discover { path, max_depth } => {
-                    repo_manager.discover_repositories(&path, Some(max_depth))?;
+                RepoCommands::Discover {
+                    path,
+                    max_depth,
+                    hidden,
+                    stream,
+                    account,
+                    parallel,
+                } => {
+                    let output = if json && stream {
+                        return Err(GitSwitchError::Other(
+                            "--stream cannot be combined with --json".to_string(),
+                        )
+                        .into());
+                    } else if json {
+                        repository::DiscoverOutput::Json
+                    } else if stream {
+                        repository::DiscoverOutput::Stream
+                    } else {
+                        repository::DiscoverOutput::Human
+                    };
+                    repo_manager.discover_repositories(
+                        &path,
+                        Some(max_depth),
+                        hidden,
+                        output,
+                        account.as_deref(),
+                        parallel,
+                    )?;
                 }
                 RepoCommands::List => {
                     repo_manager.list_discovered()?;
                 }
-                RepoCommands::Apply { dry_run, force } => {
-                    repo_manager.bulk_apply(dry_run, force)?;
+                RepoCommands::Apply {
+                    dry_run,
+                    force,
+                    interactive_per_conflict,
+                    parallel,
+                    path,
+                } => {
+                    if let Some(path) = path {
+                        repo_manager.discover_repositories(
+                            &path,
+                            None,
+                            false,
+                            repository::DiscoverOutput::Human,
+                            None,
+                            repository::DEFAULT_DISCOVER_PARALLELISM,
+                        )?;
+                    }
+                    repo_manager.bulk_apply(repository::ApplyOptions {
+                        dry_run,
+                        force,
+                        interactive: interactive_per_conflict,
+                        parallel,
+                    })?;
                 }
-                RepoCommands::Report { output } => {
-                    repo_manager.generate_report(output.as_deref())?;
+                RepoCommands::Report {
+                    output,
+                    path,
+                    compare_previous,
+                    snapshot,
+                } => {
+                    if let Some(path) = path {
+                        repo_manager.discover_repositories(
+                            &path,
+                            None,
+                            false,
+                            repository::DiscoverOutput::Human,
+                            None,
+                            repository::DEFAULT_DISCOVER_PARALLELISM,
+                        )?;
+                    }
+                    repo_manager.generate_report(repository::ReportOptions {
+                        output: output.as_deref(),
+                        compare_previous,
+                        snapshot,
+                    })?;
                 }
                 RepoCommands::Interactive => {
                     repo_manager.interactive_configure()?;
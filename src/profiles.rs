@@ -28,21 +28,26 @@ impl ProfileManager {
     }
 
     fn load_profiles(config: &Config) -> Result<HashMap<String, Profile>> {
-        let profiles_path = config.get_profiles_path();
+        let profiles_path = config.get_profiles_path()?;
         if !profiles_path.exists() {
             return Ok(HashMap::new());
         }
 
-        let content = std::fs::read_to_string(&profiles_path).map_err(GitSwitchError::Io)?;
+        let content = crate::utils::read_document(&profiles_path)?;
 
-        let profiles: HashMap<String, Profile> = toml::from_str(&content)
-            .map_err(|e| GitSwitchError::SerializationError(e.to_string()))?;
+        let profiles: HashMap<String, Profile> = toml::from_str(&content).map_err(|e| {
+            GitSwitchError::SerializationError(crate::utils::toml_parse_error(
+                &profiles_path,
+                &content,
+                &e,
+            ))
+        })?;
 
         Ok(profiles)
     }
 
     fn save_profiles(&self) -> Result<()> {
-        let profiles_path = self.config.get_profiles_path();
+        let profiles_path = self.config.get_profiles_path()?;
 
         // Create parent directory if it doesn't exist
         if let Some(parent) = profiles_path.parent() {
@@ -78,15 +83,31 @@ impl ProfileManager {
             }
         }
 
-        // Validate default account if specified
-        if let Some(ref default) = default_account {
-            if !accounts.contains(default) {
+        // Validate default account if specified; a lone account is the default
+        let default_account = match (default_account, accounts.as_slice()) {
+            (Some(default), []) => {
+                return Err(GitSwitchError::Other(format!(
+                    "Profile '{}' has no accounts, so '{}' cannot be its default; include it with --accounts {}",
+                    name, default, default
+                )));
+            }
+            (Some(default), _) if !accounts.contains(&default) => {
                 return Err(GitSwitchError::InvalidDefaultAccount {
                     profile: name.clone(),
-                    account: default.clone(),
+                    account: default,
                 });
             }
-        }
+            (None, [only]) => {
+                println!(
+                    "{} Default account set to '{}', the profile's only account",
+                    "ℹ".blue(),
+                    only.cyan()
+                );
+                Some(only.clone())
+            }
+            (default, _) => default,
+        };
+        let empty = accounts.is_empty();
 
         let profile = Profile {
             name: name.clone(),
@@ -101,6 +122,87 @@ impl ProfileManager {
         self.save_profiles()?;
 
         println!("{} Profile '{}' created successfully", "✓".green(), name);
+        if empty {
+            println!(
+                "{} The profile has no accounts yet; add some with {}",
+                "⚠".yellow(),
+                format!("git-switch profile update {} --add-accounts <names>", name).cyan()
+            );
+        }
+        Ok(())
+    }
+
+    /// Copy a profile's accounts and description under a new name, with fresh timestamps
+    pub fn clone_profile(
+        &mut self,
+        source: &str,
+        dest: String,
+        default_account: Option<String>,
+    ) -> Result<()> {
+        let source_profile =
+            self.profiles
+                .get(source)
+                .ok_or_else(|| GitSwitchError::ProfileNotFound {
+                    name: source.to_string(),
+                })?;
+        if self.profiles.contains_key(&dest) {
+            return Err(GitSwitchError::ProfileAlreadyExists { name: dest });
+        }
+
+        if let Some(ref default) = default_account
+            && !source_profile.accounts.contains(default)
+        {
+            return Err(GitSwitchError::InvalidDefaultAccount {
+                profile: dest,
+                account: default.clone(),
+            });
+        }
+
+        let profile = Profile {
+            name: dest.clone(),
+            description: source_profile.description.clone(),
+            accounts: source_profile.accounts.clone(),
+            default_account: default_account.or_else(|| source_profile.default_account.clone()),
+            created_at: chrono::Utc::now(),
+            last_used: None,
+        };
+
+        self.profiles.insert(dest.clone(), profile);
+        self.save_profiles()?;
+
+        println!("{} Profile '{}' cloned to '{}'", "✓".green(), source, dest);
+        Ok(())
+    }
+
+    /// Rename a profile, keeping its timestamps and moving the default and active references
+    pub fn rename_profile(&mut self, old: &str, new: String) -> Result<()> {
+        if self.profiles.contains_key(&new) {
+            return Err(GitSwitchError::ProfileAlreadyExists { name: new });
+        }
+        let mut profile =
+            self.profiles
+                .remove(old)
+                .ok_or_else(|| GitSwitchError::ProfileNotFound {
+                    name: old.to_string(),
+                })?;
+
+        profile.name = new.clone();
+        self.profiles.insert(new.clone(), profile);
+        self.save_profiles()?;
+
+        if self.config.settings.default_profile.as_deref() == Some(old) {
+            self.config.settings.default_profile = Some(new.clone());
+            crate::config::save_config(&self.config)?;
+        }
+        let mut state = crate::state::load_state()?;
+        if let Some(active) = state.active_profile.as_mut()
+            && active.name == old
+        {
+            active.name = new.clone();
+            crate::state::save_state(&state)?;
+        }
+
+        println!("{} Profile '{}' renamed to '{}'", "✓".green(), old, new);
         Ok(())
     }
 
@@ -115,6 +217,17 @@ impl ProfileManager {
         self.profiles.remove(name);
         self.save_profiles()?;
 
+        if self.config.settings.default_profile.as_deref() == Some(name) {
+            self.config.settings.default_profile = None;
+            crate::config::save_config(&self.config)?;
+        }
+        if crate::state::load_state()?
+            .active_profile
+            .is_some_and(|active| active.name == name)
+        {
+            crate::state::clear_active_profile()?;
+        }
+
         println!("{} Profile '{}' deleted successfully", "✓".green(), name);
         Ok(())
     }
@@ -134,7 +247,11 @@ impl ProfileManager {
         println!();
 
         for (name, profile) in &self.profiles {
-            println!("{} {}", "▶".green(), name.bold());
+            if self.config.settings.default_profile.as_ref() == Some(name) {
+                println!("{} {} {}", "★".yellow(), name.bold(), "(default)".dimmed());
+            } else {
+                println!("{} {}", "▶".green(), name.bold());
+            }
 
             if let Some(ref description) = profile.description {
                 println!("  Description: {}", description.italic());
@@ -168,17 +285,63 @@ impl ProfileManager {
         Ok(())
     }
 
-    /// Switch to a profile
-    pub fn switch_profile(&mut self, name: &str, account_override: Option<String>) -> Result<()> {
+    /// Mark a profile as the default, or clear the default with None
+    pub fn set_default_profile(&mut self, name: Option<&str>) -> Result<()> {
+        if let Some(name) = name {
+            if !self.profiles.contains_key(name) {
+                return Err(GitSwitchError::ProfileNotFound {
+                    name: name.to_string(),
+                });
+            }
+            self.config.settings.default_profile = Some(name.to_string());
+            crate::config::save_config(&self.config)?;
+            println!("{} Profile '{}' set as default", "✓".green(), name);
+        } else {
+            self.config.settings.default_profile = None;
+            crate::config::save_config(&self.config)?;
+            println!("{} Default profile cleared", "✓".green());
+        }
+        Ok(())
+    }
+
+    /// Profiles that list `account` as a member or default, sorted by name
+    pub fn profiles_referencing(&self, account: &str) -> Vec<&Profile> {
+        let mut profiles: Vec<&Profile> = self
+            .profiles
+            .values()
+            .filter(|profile| {
+                profile.accounts.iter().any(|a| a == account)
+                    || profile.default_account.as_deref() == Some(account)
+            })
+            .collect();
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles
+    }
+
+    /// Get the default profile and its default account, if both are set
+    pub fn default_profile_account(&self) -> Option<(&Profile, &str)> {
+        let name = self.config.settings.default_profile.as_ref()?;
+        let profile = self.profiles.get(name)?;
+        let account = profile.default_account.as_deref()?;
+        Some((profile, account))
+    }
+
+    /// Switch to a profile, globally when `global` is set
+    pub fn switch_profile(
+        &mut self,
+        name: &str,
+        account_override: Option<String>,
+        global: bool,
+    ) -> Result<()> {
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| GitSwitchError::ProfileNotFound {
+                name: name.to_string(),
+            })?;
+
         // Determine which account to use
         let account_name = if let Some(override_account) = account_override {
-            let profile =
-                self.profiles
-                    .get(name)
-                    .ok_or_else(|| GitSwitchError::ProfileNotFound {
-                        name: name.to_string(),
-                    })?;
-
             if !profile.accounts.contains(&override_account) {
                 return Err(GitSwitchError::AccountNotInProfile {
                     profile: name.to_string(),
@@ -186,20 +349,11 @@ impl ProfileManager {
                 });
             }
             override_account
+        } else if let Some(ref default) = profile.default_account {
+            default.clone()
         } else {
-            let profile =
-                self.profiles
-                    .get(name)
-                    .ok_or_else(|| GitSwitchError::ProfileNotFound {
-                        name: name.to_string(),
-                    })?;
-
-            if let Some(ref default) = profile.default_account {
-                default.clone()
-            } else {
-                // If no default, prompt user to choose
-                return self.prompt_account_selection_by_name(name);
-            }
+            // If no default, prompt user to choose
+            self.prompt_account_selection_by_name(name)?
         };
 
         // Update last used timestamp
@@ -209,7 +363,25 @@ impl ProfileManager {
         }
 
         // Switch to the selected account
-        crate::commands::handle_account_subcommand(&self.config, &account_name)?;
+        if global {
+            crate::commands::use_account_globally(
+                &self.config,
+                &account_name,
+                crate::commands::AgentOptions::default(),
+                crate::commands::SwitchVerify::Off,
+            )?;
+            // The switch has already happened, so this is not worth failing over
+            if let Err(e) = crate::state::set_active_profile(name, &account_name) {
+                tracing::warn!("Failed to record the active profile: {}", e);
+            }
+        } else {
+            crate::commands::handle_account_subcommand(
+                &self.config,
+                &account_name,
+                None,
+                crate::commands::SwitchVerify::Off,
+            )?;
+        }
 
         println!(
             "{} Switched to profile '{}' using account '{}'",
@@ -221,7 +393,7 @@ impl ProfileManager {
         Ok(())
     }
 
-    fn prompt_account_selection_by_name(&self, profile_name: &str) -> Result<()> {
+    fn prompt_account_selection_by_name(&self, profile_name: &str) -> Result<String> {
         use dialoguer::Select;
 
         let profile =
@@ -241,11 +413,7 @@ impl ProfileManager {
             .items(&profile.accounts)
             .interact()?;
 
-        let selected_account = &profile.accounts[selection];
-        crate::commands::handle_account_subcommand(&self.config, selected_account)?;
-
-        println!("{} Switched to account '{}'", "✓".green(), selected_account);
-        Ok(())
+        Ok(profile.accounts[selection].clone())
     }
 
     /// Update profile
@@ -1,10 +1,17 @@
 use crate::config::{Account, Config};
 use crate::error::{GitSwitchError, Result};
 use crate::git;
+use crate::utils;
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, mpsc};
+
+/// Confidence of a suggestion based only on the committer email's domain
+const EMAIL_DOMAIN_CONFIDENCE: f32 = 0.5;
 
 /// Represents a discovered Git repository
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +24,210 @@ pub struct DiscoveredRepo {
     pub account_confidence: f32, // 0.0 to 1.0
     pub last_commit_author: Option<String>,
     pub branch: Option<String>,
+    /// A linked worktree (`git worktree add`), whose `.git` is a file
+    #[serde(default)]
+    pub worktree: bool,
+}
+
+/// How `repo discover` reports its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoverOutput {
+    /// Progress bar and summary for people
+    Human,
+    /// A single JSON array once every repository is analyzed
+    Json,
+    /// One JSON object per line as each repository is analyzed (NDJSON)
+    Stream,
+}
+
+/// Repositories `repo discover` analyzes at once unless `--parallel` says otherwise
+pub const DEFAULT_DISCOVER_PARALLELISM: usize = 4;
+
+/// Live progress of `repo discover`: a status line with the repositories found
+/// and analyzed so far and the directory being scanned, and one line per worker
+/// naming the repository it is analyzing. When progress is not drawn
+/// (`--progress never`, or stdout is not a terminal) it is a single spinner.
+struct DiscoverProgress {
+    status: ProgressBar,
+    workers: Vec<ProgressBar>,
+    found: AtomicUsize,
+    analyzed: AtomicUsize,
+}
+
+impl DiscoverProgress {
+    fn new(human: bool, workers: usize) -> Self {
+        let multi = if human { utils::multi_progress() } else { None };
+        let status = match &multi {
+            Some(multi) => multi.add(ProgressBar::new_spinner()),
+            None if human => utils::progress_spinner(),
+            None => ProgressBar::hidden(),
+        };
+        status.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} [{elapsed_precise}] {prefix} {wide_msg:.dim}")
+                .unwrap(),
+        );
+        status.enable_steady_tick(std::time::Duration::from_millis(100));
+
+        let workers = match &multi {
+            Some(multi) => (0..workers)
+                .map(|_| {
+                    let line = multi.add(ProgressBar::new_spinner());
+                    line.set_style(
+                        ProgressStyle::default_spinner()
+                            .template("  {spinner:.cyan} {wide_msg}")
+                            .unwrap(),
+                    );
+                    line.set_message("waiting");
+                    line
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let progress = Self {
+            status,
+            workers,
+            found: AtomicUsize::new(0),
+            analyzed: AtomicUsize::new(0),
+        };
+        progress.update_counts();
+        progress
+    }
+
+    fn update_counts(&self) {
+        self.status.set_prefix(format!(
+            "{} found, {} analyzed",
+            self.found(),
+            self.analyzed.load(Ordering::SeqCst)
+        ));
+    }
+
+    fn found(&self) -> usize {
+        self.found.load(Ordering::SeqCst)
+    }
+
+    fn scanning(&self, dir: &Path) {
+        self.status
+            .set_message(format!("scanning {}", dir.display()));
+    }
+
+    fn found_repository(&self) {
+        self.found.fetch_add(1, Ordering::SeqCst);
+        self.update_counts();
+    }
+
+    fn analyzing(&self, worker: usize, repo_path: &Path) {
+        if let Some(line) = self.workers.get(worker) {
+            line.set_message(format!("analyzing {}", repo_path.display()));
+        }
+    }
+
+    fn analyzed(&self, worker: usize) {
+        self.analyzed.fetch_add(1, Ordering::SeqCst);
+        self.update_counts();
+        if let Some(line) = self.workers.get(worker) {
+            line.set_message("waiting");
+        }
+    }
+
+    fn finish(&self) {
+        for line in &self.workers {
+            line.finish_and_clear();
+        }
+        self.status.finish_and_clear();
+    }
+}
+
+/// Repositories `repo apply` configures at once unless `--parallel` says otherwise
+pub const DEFAULT_APPLY_PARALLELISM: usize = 4;
+
+/// How `repo apply` treats suggestions
+#[derive(Debug, Clone, Copy)]
+pub struct ApplyOptions {
+    /// Only preview what would be applied
+    pub dry_run: bool,
+    /// Apply low-confidence suggestions too
+    pub force: bool,
+    /// Ask about each low-confidence suggestion
+    pub interactive: bool,
+    /// Repositories configured at once (a dry run is always sequential)
+    pub parallel: usize,
+}
+
+/// What `repo report` does besides printing or saving the report
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportOptions<'a> {
+    /// Save the report here instead of printing it
+    pub output: Option<&'a Path>,
+    /// Add a section diffing this scan against the saved snapshot
+    pub compare_previous: bool,
+    /// Save this scan as the snapshot later reports compare against
+    pub snapshot: bool,
+}
+
+/// Discovery results saved by `repo report` for later comparison
+#[derive(Debug, Serialize, Deserialize)]
+struct ReportSnapshot {
+    generated: String, // ISO date string
+    repositories: Vec<DiscoveredRepo>,
+}
+
+/// Get the path of the snapshot `repo report --compare-previous` diffs against
+pub fn get_report_snapshot_path() -> Result<PathBuf> {
+    let home_dir = utils::home_dir()?;
+    Ok(home_dir.join(".git-switch-report-snapshot.json"))
+}
+
+fn load_report_snapshot() -> Result<Option<ReportSnapshot>> {
+    let path = get_report_snapshot_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = utils::read_document(&path)?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| GitSwitchError::CorruptedConfig {
+            message: utils::json_parse_error(&path, &content, &e),
+        })
+}
+
+/// Identity configured in a repository, as shown in reports
+fn describe_identity(repo: &DiscoveredRepo) -> String {
+    match (&repo.current_user_name, &repo.current_user_email) {
+        (Some(name), Some(email)) => format!("{} <{}>", name, email),
+        (Some(name), None) => name.clone(),
+        (None, Some(email)) => format!("<{}>", email),
+        (None, None) => "Not configured".to_string(),
+    }
+}
+
+/// Whether `account` is suggested for the repository but its local identity is
+/// not that account's (including when no local email is set)
+fn needs_account(repo: &DiscoveredRepo, account: &Account) -> bool {
+    repo.suggested_account.as_deref() == Some(account.name.as_str())
+        && !repo
+            .current_user_email
+            .as_deref()
+            .is_some_and(|email| account.matches_email(email))
+}
+
+/// Find Git repositories under a path, without descending into repositories
+///
+/// Dot directories are skipped unless `hidden` is set; `.git` never is searched.
+pub fn find_git_repositories(path: &Path, max_depth: usize, hidden: bool) -> Result<Vec<PathBuf>> {
+    let mut repositories = Vec::new();
+    RepoManager::find_git_repositories_recursive(
+        path,
+        max_depth,
+        0,
+        hidden,
+        &mut |dir, is_repo| {
+            if is_repo {
+                repositories.push(dir.to_path_buf());
+            }
+        },
+    )?;
+    Ok(repositories)
 }
 
 /// Repository discovery and bulk operations manager
@@ -33,104 +244,204 @@ impl RepoManager {
         }
     }
 
-    /// Discover Git repositories recursively from a given path
+    /// Discover Git repositories recursively from a given path, analyzing up to
+    /// `parallel` of them at once while the walk continues.
+    ///
+    /// With `account`, only the repositories suggested for that account whose
+    /// identity does not match it yet are kept: the ones still to fix.
     pub fn discover_repositories(
         &mut self,
         search_path: &Path,
         max_depth: Option<usize>,
+        hidden: bool,
+        output: DiscoverOutput,
+        account: Option<&str>,
+        parallel: usize,
     ) -> Result<()> {
-        println!(
-            "{} Discovering Git repositories in {}...",
-            "🔍".cyan(),
-            search_path.display()
-        );
-
-        let repos = self.find_git_repositories(search_path, max_depth.unwrap_or(5))?;
-
-        if repos.is_empty() {
+        let wanted = account
+            .map(|name| {
+                self.config.accounts.get(name).cloned().ok_or_else(|| {
+                    GitSwitchError::AccountNotFound {
+                        name: name.to_string(),
+                    }
+                })
+            })
+            .transpose()?;
+        let human = output == DiscoverOutput::Human;
+        if human {
             println!(
-                "{} No Git repositories found in {}",
-                "ℹ".blue(),
+                "{} Discovering Git repositories in {}...",
+                "🔍".cyan(),
                 search_path.display()
             );
-            return Ok(());
         }
 
-        println!(
-            "{} Found {} repositories. Analyzing...",
-            "✓".green(),
-            repos.len()
-        );
-
-        // Create progress bar
-        let pb = ProgressBar::new(repos.len() as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({eta})")
-                .unwrap()
-                .progress_chars("#>-"),
-        );
-
         self.discovered_repos.clear();
-
-        for repo_path in repos {
-            let discovered = self.analyze_repository(&repo_path)?;
-            self.discovered_repos.push(discovered);
-            pb.inc(1);
+        let max_depth = max_depth.unwrap_or(5);
+        let workers = parallel.max(1);
+        // Machine-readable output goes to stdout untouched, without progress
+        let progress = DiscoverProgress::new(human, workers);
+
+        // The walk feeds the workers as it goes, so analysis starts before the
+        // total is known; results come back tagged with their walk position
+        let this = &*self;
+        let (repo_tx, repo_rx) = mpsc::channel::<(usize, PathBuf)>();
+        let repo_rx = Mutex::new(repo_rx);
+        let (result_tx, result_rx) = mpsc::channel::<(usize, Result<DiscoveredRepo>)>();
+        let mut kept = Vec::new();
+        let mut first_error = None;
+        std::thread::scope(|scope| -> Result<()> {
+            let progress = &progress;
+            let repo_rx = &repo_rx;
+            let walker = scope.spawn(move || {
+                let mut found = 0;
+                Self::find_git_repositories_recursive(
+                    search_path,
+                    max_depth,
+                    0,
+                    hidden,
+                    &mut |dir, is_repo| {
+                        if is_repo {
+                            // Sending only fails once every worker has stopped
+                            let _ = repo_tx.send((found, dir.to_path_buf()));
+                            found += 1;
+                            progress.found_repository();
+                        } else {
+                            progress.scanning(dir);
+                        }
+                    },
+                )
+            });
+            for worker in 0..workers {
+                let result_tx = result_tx.clone();
+                scope.spawn(move || {
+                    loop {
+                        let next = repo_rx.lock().ok().and_then(|rx| rx.recv().ok());
+                        let Some((index, repo_path)) = next else {
+                            break;
+                        };
+                        progress.analyzing(worker, &repo_path);
+                        let result = this.analyze_repository(&repo_path);
+                        progress.analyzed(worker);
+                        if result_tx.send((index, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(result_tx);
+
+            for (index, result) in result_rx {
+                let discovered = match result {
+                    Ok(discovered) => discovered,
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                        continue;
+                    }
+                };
+                if let Some(account) = &wanted
+                    && !needs_account(&discovered, account)
+                {
+                    continue;
+                }
+                if output == DiscoverOutput::Stream {
+                    println!(
+                        "{}",
+                        serde_json::to_string(&discovered).map_err(GitSwitchError::Json)?
+                    );
+                }
+                kept.push((index, discovered));
+            }
+            walker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })?;
+        progress.finish();
+        if let Some(e) = first_error {
+            return Err(e);
         }
 
-        pb.finish_with_message("Analysis complete!");
-
-        println!(
-            "{} Analyzed {} repositories",
-            "✓".green(),
-            self.discovered_repos.len()
-        );
-        self.print_discovery_summary()?;
+        if progress.found() == 0 {
+            match output {
+                DiscoverOutput::Human => println!(
+                    "{} No Git repositories found in {}",
+                    "ℹ".blue(),
+                    search_path.display()
+                ),
+                DiscoverOutput::Json => println!("[]"),
+                DiscoverOutput::Stream => {}
+            }
+            return Ok(());
+        }
+        kept.sort_by_key(|(index, _)| *index);
+        self.discovered_repos = kept.into_iter().map(|(_, repo)| repo).collect();
+
+        match output {
+            DiscoverOutput::Human => {
+                println!("{} Found {} repositories", "✓".green(), progress.found());
+                match &wanted {
+                    Some(account) => self.print_repos_needing_account(account),
+                    None => {
+                        println!(
+                            "{} Analyzed {} repositories",
+                            "✓".green(),
+                            self.discovered_repos.len()
+                        );
+                        self.print_discovery_summary()?;
+                    }
+                }
+            }
+            DiscoverOutput::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&self.discovered_repos)
+                    .map_err(GitSwitchError::Json)?
+            ),
+            DiscoverOutput::Stream => {}
+        }
 
         Ok(())
     }
 
-    fn find_git_repositories(&self, path: &Path, max_depth: usize) -> Result<Vec<PathBuf>> {
-        let mut repositories = Vec::new();
-        Self::find_git_repositories_recursive(path, max_depth, 0, &mut repositories)?;
-        Ok(repositories)
-    }
-
+    /// Walk `path`, calling `visit` with each directory entered and whether it is a
+    /// repository
     fn find_git_repositories_recursive(
         path: &Path,
         max_depth: usize,
         current_depth: usize,
-        repositories: &mut Vec<PathBuf>,
+        hidden: bool,
+        visit: &mut dyn FnMut(&Path, bool),
     ) -> Result<()> {
         if current_depth > max_depth {
             return Ok(());
         }
 
-        // Check if current directory is a Git repository
+        // Check if current directory is a Git repository; in linked worktrees
+        // `.git` is a file that must point at an existing Git directory
         if path.join(".git").exists() {
-            repositories.push(path.to_path_buf());
+            if git::resolve_git_dir(path).is_some() {
+                visit(path, true);
+            }
             // Don't recurse into subdirectories of Git repositories
             return Ok(());
         }
 
+        visit(path, false);
+
         // Recurse into subdirectories
         if let Ok(entries) = std::fs::read_dir(path) {
             for entry in entries.flatten() {
                 let entry_path = entry.path();
-                if entry_path.is_dir()
-                    && !entry_path
-                        .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .starts_with('.')
-                {
+                let file_name = entry.file_name();
+                let name = file_name.to_string_lossy();
+                // Never walk into Git internals; other dot directories only on request
+                let skipped = name == ".git" || (name.starts_with('.') && !hidden);
+                if entry_path.is_dir() && !skipped {
                     Self::find_git_repositories_recursive(
                         &entry_path,
                         max_depth,
                         current_depth + 1,
-                        repositories,
+                        hidden,
+                        visit,
                     )?;
                 }
             }
@@ -139,29 +450,20 @@ impl RepoManager {
         Ok(())
     }
 
+    /// Read a repository's identity and remote and suggest an account for it.
+    ///
+    /// Every command runs in `repo_path`, never the process's working directory,
+    /// so repositories can be analyzed from several threads at once.
     fn analyze_repository(&self, repo_path: &Path) -> Result<DiscoveredRepo> {
-        let original_dir = std::env::current_dir().map_err(GitSwitchError::Io)?;
-
-        // Change to repository directory
-        std::env::set_current_dir(repo_path).map_err(GitSwitchError::Io)?;
-
-        let result = self.analyze_current_repository(repo_path);
-
-        // Restore original directory
-        std::env::set_current_dir(original_dir).map_err(GitSwitchError::Io)?;
-
-        result
-    }
-
-    fn analyze_current_repository(&self, repo_path: &Path) -> Result<DiscoveredRepo> {
-        let remote_url = git::get_remote_url("origin").ok();
-        let current_user_name = git::get_local_config_key("user.name").ok();
-        let current_user_email = git::get_local_config_key("user.email").ok();
-        let branch = git::get_current_branch().ok();
+        let remote_url = git::get_remote_url_in(repo_path, "origin").ok();
+        let current_user_name = git::get_local_config_key_in(repo_path, "user.name").ok();
+        let current_user_email = git::get_local_config_key_in(repo_path, "user.email").ok();
+        let branch = git::get_current_branch_in(repo_path).ok();
 
         // Get last commit author
         let last_commit_author = std::process::Command::new("git")
             .args(["log", "-1", "--pretty=format:%an <%ae>"])
+            .current_dir(repo_path)
             .output()
             .ok()
             .and_then(|output| {
@@ -174,11 +476,17 @@ impl RepoManager {
 
         // Detect suggested account
         let (suggested_account, confidence) = if let Some(url) = &remote_url {
-            match crate::detection::detect_account_for_remote_url(&self.config, url) {
-                Ok(Some(account)) => (Some(account), 0.9),
-                _ => {
-                    // Try to match by email or name
-                    self.find_matching_account_by_user(&current_user_email, &current_user_name)
+            if let Some(account) =
+                crate::detection::detect_account_by_url_pattern(&self.config, url)
+            {
+                (Some(account), 1.0)
+            } else {
+                match crate::detection::detect_account_for_remote_url(&self.config, url) {
+                    Ok(Some(account)) => (Some(account), 0.9),
+                    _ => {
+                        // Try to match by email or name
+                        self.find_matching_account_by_user(&current_user_email, &current_user_name)
+                    }
                 }
             }
         } else {
@@ -187,13 +495,16 @@ impl RepoManager {
 
         Ok(DiscoveredRepo {
             path: repo_path.to_path_buf(),
-            remote_url,
+            // Never keep credentials embedded in the URL: this struct is printed and exported
+            remote_url: remote_url.map(|url| git::redact_url(&url)),
             current_user_name,
             current_user_email,
             suggested_account,
             account_confidence: confidence,
             last_commit_author,
             branch,
+            worktree: git::resolve_git_dir(repo_path)
+                .is_some_and(|git_dir| git::is_linked_worktree(&git_dir)),
         })
     }
 
@@ -211,16 +522,16 @@ impl RepoManager {
             let mut total_checks = 0;
 
             // Check email match
-            if let (Some(repo_email), account_email) = (email, &account.email) {
+            if let Some(repo_email) = email {
                 total_checks += 1;
-                if repo_email == account_email {
+                if account.matches_email(repo_email) {
                     matches += 1;
                     confidence += 0.6; // Email is highly indicative
                 }
             }
 
             // Check name match
-            if let (Some(repo_name), account_name_val) = (name, &account.name) {
+            if let (Some(repo_name), account_name_val) = (name, &account.git_name) {
                 total_checks += 1;
                 if repo_name == account_name_val {
                     matches += 1;
@@ -238,9 +549,49 @@ impl RepoManager {
             }
         }
 
+        // An organization email domain is a medium-confidence signal on its own
+        if best_confidence < EMAIL_DOMAIN_CONFIDENCE
+            && let Some(account) = email.as_deref().and_then(|email| {
+                crate::detection::detect_account_by_email_domain(&self.config, email)
+            })
+        {
+            return (Some(account), EMAIL_DOMAIN_CONFIDENCE);
+        }
+
         (best_match, best_confidence)
     }
 
+    /// Print the repositories kept by `discover --account`
+    fn print_repos_needing_account(&self, account: &Account) {
+        if self.discovered_repos.is_empty() {
+            println!(
+                "{} Every repository suggested for '{}' already uses it",
+                "✓".green(),
+                account.name
+            );
+            return;
+        }
+
+        println!(
+            "{} {} repositories need account '{}':",
+            "⚠".yellow(),
+            self.discovered_repos.len(),
+            account.name.cyan()
+        );
+        for repo in &self.discovered_repos {
+            println!(
+                "  {} (currently {})",
+                repo.path.display().to_string().bold(),
+                describe_identity(repo).yellow()
+            );
+        }
+        println!(
+            "Run {} in each, or {} to configure them all",
+            format!("git-switch account {}", account.name).cyan(),
+            "git-switch repo apply".cyan()
+        );
+    }
+
     fn print_discovery_summary(&self) -> Result<()> {
         let mut with_suggestions = 0;
         let mut high_confidence = 0;
@@ -255,14 +606,12 @@ impl RepoManager {
             }
 
             // Check for potential mismatches
-            if let (Some(suggested), Some(current_email)) =
-                (&repo.suggested_account, &repo.current_user_email)
+            if repo.current_user_email.is_some()
+                && let Some(suggested) = &repo.suggested_account
+                && let Some(account) = self.config.accounts.get(suggested)
+                && needs_account(repo, account)
             {
-                if let Some(account) = self.config.accounts.get(suggested) {
-                    if current_email != &account.email {
-                        mismatched += 1;
-                    }
-                }
+                mismatched += 1;
             }
         }
 
@@ -303,16 +652,21 @@ impl RepoManager {
 
         for (i, repo) in self.discovered_repos.iter().enumerate() {
             println!(
-                "{} {}",
+                "{} {}{}",
                 format!("{}.", i + 1).cyan(),
-                repo.path.display().to_string().bold()
+                repo.path.display().to_string().bold(),
+                if repo.worktree {
+                    " (worktree)".dimmed().to_string()
+                } else {
+                    String::new()
+                }
             );
 
             if let Some(url) = &repo.remote_url {
                 println!("   Remote: {}", url.dimmed());
             }
 
-            if let Some(branch) = &repo.branch {
+            if let Some(branch) = repo.branch.as_deref().filter(|b| !b.is_empty()) {
                 println!("   Branch: {}", branch.cyan());
             }
 
@@ -358,10 +712,22 @@ impl RepoManager {
     }
 
     /// Apply account configurations to multiple repositories
-    pub fn bulk_apply(&mut self, dry_run: bool, force: bool) -> Result<()> {
+    ///
+    /// Low-confidence suggestions are skipped unless `force` applies them all or
+    /// `interactive` asks about each one; a dry run never prompts. Decisions are
+    /// made in order, then up to `parallel` repositories are configured at once
+    /// and failures are reported together at the end.
+    pub fn bulk_apply(&mut self, options: ApplyOptions) -> Result<()> {
         if self.discovered_repos.is_empty() {
             return Err(GitSwitchError::NoRepositoriesDiscovered);
         }
+        // Fail before anything is applied rather than at the first prompt
+        if options.interactive && !options.dry_run && !std::io::stdin().is_terminal() {
+            return Err(GitSwitchError::Other(
+                "--interactive-per-conflict needs a terminal; preview with --dry-run or use --force"
+                    .to_string(),
+            ));
+        }
 
         let applicable_repos: Vec<_> = self
             .discovered_repos
@@ -379,12 +745,13 @@ impl RepoManager {
 
         println!("{} repositories with suggestions:", applicable_repos.len());
 
-        if dry_run {
+        if options.dry_run {
             println!("{}", "DRY RUN - No changes will be made".yellow().bold());
         }
 
         println!();
 
+        let mut jobs: Vec<(PathBuf, String)> = Vec::new();
         for repo in &applicable_repos {
             let suggested_account = repo.suggested_account.as_ref().unwrap();
             let account = self.config.accounts.get(suggested_account).unwrap();
@@ -392,37 +759,159 @@ impl RepoManager {
             println!("{} {}", "▶".green(), repo.path.display());
             println!("  Account: {}", suggested_account.cyan());
 
-            println!("  Name: {}", account.name);
+            println!("  Name: {}", account.git_name);
             println!("  Email: {}", account.email);
 
-            if !dry_run {
-                if !force && repo.account_confidence < 0.5 {
+            let low_confidence = !options.force && repo.account_confidence < 0.5;
+            if options.dry_run && low_confidence && options.interactive {
+                println!(
+                    "  {}: Low confidence, would ask before applying",
+                    "⚠".yellow()
+                );
+            } else if !options.dry_run {
+                let account_name = if !low_confidence {
+                    suggested_account.clone()
+                } else if options.interactive {
+                    match self.resolve_low_confidence(repo, account)? {
+                        Some(name) => name,
+                        None => {
+                            println!("  {}: Skipped", "↷".bright_black());
+                            println!();
+                            continue;
+                        }
+                    }
+                } else {
                     println!(
-                        "  {}: Low confidence, skipping (use --force to apply)",
+                        "  {}: Low confidence, skipping (use --force or --interactive-per-conflict)",
                         "⚠".yellow()
                     );
                     continue;
-                }
-
-                // Apply the account configuration
-                match self.apply_account_to_repo(&repo.path, suggested_account) {
-                    Ok(_) => println!("  {}: Applied successfully", "✓".green()),
-                    Err(e) => println!("  {}: Failed - {}", "✗".red(), e),
-                }
+                };
+                jobs.push((repo.path.clone(), account_name));
             }
 
             println!();
         }
 
-        if dry_run {
+        if options.dry_run {
             println!("Run without --dry-run to apply changes");
-        } else {
-            println!("{} Bulk operation completed", "✓".green());
+            return Ok(());
+        }
+
+        let results = self.apply_in_parallel(&jobs, options.parallel);
+        let failures: Vec<_> = jobs
+            .iter()
+            .zip(&results)
+            .filter_map(|((path, _), result)| result.as_ref().err().map(|e| (path, e)))
+            .collect();
+
+        println!(
+            "{} Applied {} of {} repositories",
+            "✓".green(),
+            jobs.len() - failures.len(),
+            jobs.len()
+        );
+        if !failures.is_empty() {
+            println!("{} Failed ({}):", "✗".red(), failures.len());
+            for (path, error) in failures {
+                println!("  {} - {}", path.display(), error);
+            }
         }
 
         Ok(())
     }
 
+    /// Apply each `(repository, account)` job, `parallel` at a time, with a progress bar.
+    ///
+    /// Results are returned in job order.
+    fn apply_in_parallel(&self, jobs: &[(PathBuf, String)], parallel: usize) -> Vec<Result<()>> {
+        let pb = utils::progress_bar(jobs.len() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({eta})")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+
+        // Workers pull jobs from a shared cursor and tag results with the job index
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let mut results: Vec<(usize, Result<()>)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..parallel.max(1).min(jobs.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                            let Some((path, account_name)) = jobs.get(index) else {
+                                break;
+                            };
+                            done.push((index, self.apply_account_to_repo(path, account_name)));
+                            pb.inc(1);
+                        }
+                        done
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
+        pb.finish_and_clear();
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Ask what to do with a low-confidence suggestion; None means skip the repository
+    fn resolve_low_confidence(
+        &self,
+        repo: &DiscoveredRepo,
+        suggested: &Account,
+    ) -> Result<Option<String>> {
+        use dialoguer::Select;
+        use dialoguer::theme::ColorfulTheme;
+
+        let current = match (&repo.current_user_name, &repo.current_user_email) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (_, Some(email)) => email.clone(),
+            (Some(name), None) => name.clone(),
+            (None, None) => "(not set)".to_string(),
+        };
+        println!("  Current:   {}", current);
+        println!(
+            "  Suggested: {} <{}> ({}% confidence)",
+            suggested.name,
+            suggested.email,
+            (repo.account_confidence * 100.0) as u8
+        );
+
+        let choice = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Low-confidence match")
+            .items(&[
+                format!("Apply '{}'", suggested.name),
+                "Skip".to_string(),
+                "Choose another account".to_string(),
+            ])
+            .default(1)
+            .interact()?;
+
+        match choice {
+            0 => Ok(Some(suggested.name.clone())),
+            2 => {
+                let mut names: Vec<&String> = self.config.accounts.keys().collect();
+                names.sort();
+                let selection = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Account to apply")
+                    .items(&names)
+                    .default(0)
+                    .interact()?;
+                Ok(Some(names[selection].clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
     fn apply_account_to_repo(&self, repo_path: &Path, account_name: &str) -> Result<()> {
         let account = self.config.accounts.get(account_name).ok_or_else(|| {
             GitSwitchError::AccountNotFound {
@@ -430,42 +919,39 @@ impl RepoManager {
             }
         })?;
 
-        let original_dir = std::env::current_dir().map_err(GitSwitchError::Io)?;
-
-        // Change to repository directory
-        std::env::set_current_dir(repo_path).map_err(GitSwitchError::Io)?;
-
-        let result = self.apply_account_config(account);
-
-        // Restore original directory
-        std::env::set_current_dir(original_dir).map_err(GitSwitchError::Io)?;
-
-        result
+        self.apply_account_config(repo_path, account)
     }
 
-    fn apply_account_config(&self, account: &Account) -> Result<()> {
+    fn apply_account_config(&self, repo_path: &Path, account: &Account) -> Result<()> {
         // Set user name
-        git::set_local_config_key("user.name", &account.name)?;
+        git::set_local_config_key_in(repo_path, "user.name", &account.git_name)?;
 
         // Set user email
-        git::set_local_config_key("user.email", &account.email)?;
+        git::set_local_config_key_in(repo_path, "user.email", &account.email)?;
 
         // Set SSH key if available
         if !account.ssh_key_path.is_empty() {
-            git::set_local_config_key(
+            git::set_local_config_key_in(
+                repo_path,
                 "core.sshCommand",
-                &format!("ssh -i {}", account.ssh_key_path),
+                &git::ssh_command_for_key(
+                    &account.ssh_key_path,
+                    self.config.strict_ssh_for(account),
+                ),
             )?;
         }
 
-        Ok(())
+        crate::commands::apply_repository_settings(&self.config, repo_path, account)
     }
 
     /// Generate a report of repository analysis
-    pub fn generate_report(&self, output_path: Option<&Path>) -> Result<()> {
-        let report = self.create_report()?;
+    pub fn generate_report(&self, options: ReportOptions) -> Result<()> {
+        let mut report = self.create_report()?;
+        if options.compare_previous {
+            report.push_str(&self.create_comparison(load_report_snapshot()?.as_ref()));
+        }
 
-        match output_path {
+        match options.output {
             Some(path) => {
                 std::fs::write(path, &report).map_err(GitSwitchError::Io)?;
                 println!("{} Report saved to {}", "✓".green(), path.display());
@@ -475,9 +961,75 @@ impl RepoManager {
             }
         }
 
+        if options.snapshot {
+            let snapshot = ReportSnapshot {
+                generated: chrono::Utc::now().to_rfc3339(),
+                repositories: self.discovered_repos.clone(),
+            };
+            let path = get_report_snapshot_path()?;
+            std::fs::write(
+                &path,
+                serde_json::to_string_pretty(&snapshot).map_err(GitSwitchError::Json)?,
+            )?;
+            println!("{} Snapshot saved to {}", "✓".green(), path.display());
+        }
+
         Ok(())
     }
 
+    /// Report section listing repositories added, removed or with a changed identity
+    fn create_comparison(&self, previous: Option<&ReportSnapshot>) -> String {
+        let mut section = String::from("## Changes Since Previous Report\n");
+        let Some(previous) = previous else {
+            section.push_str("No previous snapshot; save one with `repo report --snapshot`.\n");
+            return section;
+        };
+        section.push_str(&format!("Previous: {}\n\n", previous.generated));
+
+        let find = |repos: &[DiscoveredRepo], path: &Path| {
+            repos.iter().find(|repo| repo.path == path).cloned()
+        };
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for repo in &self.discovered_repos {
+            match find(&previous.repositories, &repo.path) {
+                None => added.push(format!("- {}\n", repo.path.display())),
+                Some(old) if describe_identity(&old) != describe_identity(repo) => {
+                    changed.push(format!(
+                        "- {}: {} -> {}\n",
+                        repo.path.display(),
+                        describe_identity(&old),
+                        describe_identity(repo)
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        let removed: Vec<String> = previous
+            .repositories
+            .iter()
+            .filter(|old| find(&self.discovered_repos, &old.path).is_none())
+            .map(|old| format!("- {}\n", old.path.display()))
+            .collect();
+
+        if added.is_empty() && removed.is_empty() && changed.is_empty() {
+            section.push_str("No changes.\n");
+            return section;
+        }
+        for (title, lines) in [
+            ("New Repositories", added),
+            ("Removed Repositories", removed),
+            ("Identity Changes", changed),
+        ] {
+            if !lines.is_empty() {
+                section.push_str(&format!("### {}\n", title));
+                section.extend(lines);
+                section.push('\n');
+            }
+        }
+        section
+    }
+
     fn create_report(&self) -> Result<String> {
         let mut report = String::new();
 
@@ -516,24 +1068,14 @@ impl RepoManager {
                 report.push_str(&format!("- **Remote**: {}\n", url));
             }
 
-            if let Some(branch) = &repo.branch {
+            if let Some(branch) = repo.branch.as_deref().filter(|b| !b.is_empty()) {
                 report.push_str(&format!("- **Branch**: {}\n", branch));
             }
 
-            match (&repo.current_user_name, &repo.current_user_email) {
-                (Some(name), Some(email)) => {
-                    report.push_str(&format!("- **Current Config**: {} <{}>\n", name, email));
-                }
-                (Some(name), None) => {
-                    report.push_str(&format!("- **Current Config**: {}\n", name));
-                }
-                (None, Some(email)) => {
-                    report.push_str(&format!("- **Current Config**: <{}>\n", email));
-                }
-                (None, None) => {
-                    report.push_str("- **Current Config**: Not configured\n");
-                }
-            }
+            report.push_str(&format!(
+                "- **Current Config**: {}\n",
+                describe_identity(repo)
+            ));
 
             if let Some(suggested) = &repo.suggested_account {
                 report.push_str(&format!(
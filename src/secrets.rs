@@ -0,0 +1,79 @@
+//! Passphrases resolved through an external password manager command.
+//!
+//! Security model: `secret_command` is a template such as `op read {ref}` or
+//! `pass show {ref}`, run through the shell with the account's reference quoted
+//! in place of `{ref}`. Its stdout is the key passphrase. The passphrase is held
+//! in zeroizing buffers and only handed to `ssh-add` through the environment of
+//! that single child process, where git-switch itself answers the passphrase
+//! prompt as `SSH_ASKPASS`. It is never written to the config, the state file,
+//! or logs; like any child environment it is readable by the same OS user for
+//! the few milliseconds `ssh-add` runs.
+
+use crate::error::{GitSwitchError, Result};
+use crate::utils::{run_shell_command, shell_quote};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Placeholder in `secret_command` replaced by the account's passphrase reference
+const REF_PLACEHOLDER: &str = "{ref}";
+
+/// Environment variable carrying the passphrase to the askpass helper
+pub const ASKPASS_SECRET_ENV: &str = "GIT_SWITCH_ASKPASS_SECRET";
+
+/// Run the secret command for a reference and return its output as the passphrase
+pub fn resolve_secret(command_template: &str, reference: &str) -> Result<Zeroizing<String>> {
+    if !command_template.contains(REF_PLACEHOLDER) {
+        return Err(GitSwitchError::SecretCommand {
+            message: format!(
+                "secret-command must contain {} (e.g. \"op read {}\")",
+                REF_PLACEHOLDER, REF_PLACEHOLDER
+            ),
+        });
+    }
+    let command = command_template.replace(REF_PLACEHOLDER, &shell_quote(reference));
+
+    let output = run_shell_command(&command)?;
+    let mut stdout = output.stdout;
+
+    if !output.status.success() {
+        stdout.zeroize();
+        return Err(GitSwitchError::SecretCommand {
+            message: format!(
+                "'{}' exited with {}: {}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    let secret = Zeroizing::new(
+        String::from_utf8_lossy(&stdout)
+            .trim_end_matches(['\r', '\n'])
+            .to_string(),
+    );
+    stdout.zeroize();
+
+    if secret.is_empty() {
+        return Err(GitSwitchError::SecretCommand {
+            message: format!("'{}' returned an empty secret", command),
+        });
+    }
+    Ok(secret)
+}
+
+/// Answer ssh-add's passphrase prompt when git-switch runs as its `SSH_ASKPASS`.
+///
+/// Returns false when this is a normal invocation. A retry prompt after a bad
+/// passphrase gets an empty answer so ssh-add gives up instead of looping.
+pub fn run_askpass() -> bool {
+    let Some(secret) = std::env::var(ASKPASS_SECRET_ENV).ok().map(Zeroizing::new) else {
+        return false;
+    };
+    let prompt = std::env::args().nth(1).unwrap_or_default();
+    if prompt.starts_with("Bad passphrase") {
+        println!();
+    } else {
+        println!("{}", secret.as_str());
+    }
+    true
+}
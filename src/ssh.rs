@@ -1,61 +1,220 @@
+use crate::config::Account;
 use crate::error::{GitSwitchError, Result};
 use crate::utils::{
-    ensure_parent_dir_exists, expand_path, read_file_content, run_command, run_command_with_output,
+    ensure_parent_dir_exists, expand_path, home_dir, read_file_content, run_command,
+    run_command_with_env, run_command_with_full_output, run_command_with_output,
     write_file_content,
 };
 use colored::*;
 use std::path::{Path, PathBuf};
 
 fn get_ssh_dir_path() -> Result<PathBuf> {
-    home::home_dir()
-        .map(|home| home.join(".ssh"))
-        .ok_or(GitSwitchError::HomeDirectoryNotFound)
+    home_dir().map(|home| home.join(".ssh"))
 }
 
-fn get_ssh_config_file_path() -> Result<PathBuf> {
+pub fn get_ssh_config_file_path() -> Result<PathBuf> {
     get_ssh_dir_path().map(|ssh_dir| ssh_dir.join("config"))
 }
 
+/// Name of the file git-switch owns in include mode, next to the main SSH config
+const MANAGED_SSH_CONFIG_NAME: &str = "git-switch.config";
+
+/// Line added to the top of the main SSH config in include mode
+const SSH_INCLUDE_LINE: &str = "Include ~/.ssh/git-switch.config";
+
+/// The SSH config file git-switch writes its host entries to: its own file in
+/// include mode (see `GlobalSettings::ssh_include_mode`), otherwise the main config
+pub fn managed_ssh_config_path(include_mode: bool) -> Result<PathBuf> {
+    if include_mode {
+        get_ssh_dir_path().map(|ssh_dir| ssh_dir.join(MANAGED_SSH_CONFIG_NAME))
+    } else {
+        get_ssh_config_file_path()
+    }
+}
+
+/// Add the `Include` for git-switch's file at the top of the main SSH config,
+/// unless it is already included. Host blocks in the main config are left alone.
+pub fn ensure_ssh_include() -> Result<()> {
+    let config_path = get_ssh_config_file_path()?;
+    ensure_parent_dir_exists(&config_path)?;
+    let current_config = if config_path.exists() {
+        read_file_content(&config_path)?
+    } else {
+        String::new()
+    };
+
+    let included = current_config.lines().any(|line| {
+        let mut words = line.split_whitespace();
+        words
+            .next()
+            .is_some_and(|keyword| keyword.eq_ignore_ascii_case("include"))
+            && words.any(|target| target.ends_with(MANAGED_SSH_CONFIG_NAME))
+    });
+    if included {
+        return Ok(());
+    }
+
+    // Include only applies everywhere before the first Host block, so it goes first
+    write_file_content(
+        &config_path,
+        &format!("{}\n\n{}", SSH_INCLUDE_LINE, current_config),
+    )?;
+    Ok(())
+}
+
+/// Main SSH config followed by git-switch's own file, to look for existing hosts
+pub fn read_ssh_configs() -> String {
+    [get_ssh_config_file_path(), managed_ssh_config_path(true)]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.exists())
+        .filter_map(|path| read_file_content(&path).ok())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Key algorithms offered when generating a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyType {
+    #[default]
+    Rsa,
+    Ed25519,
+    Ecdsa,
+}
+
+impl KeyType {
+    /// `ssh-keygen` arguments selecting this algorithm
+    fn keygen_args(&self) -> &'static [&'static str] {
+        match self {
+            KeyType::Rsa => &["-t", "rsa", "-b", "4096"],
+            KeyType::Ed25519 => &["-t", "ed25519"],
+            KeyType::Ecdsa => &["-t", "ecdsa", "-b", "521"],
+        }
+    }
+}
+
+impl std::str::FromStr for KeyType {
+    type Err = GitSwitchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "rsa" => Ok(KeyType::Rsa),
+            "ed25519" => Ok(KeyType::Ed25519),
+            "ecdsa" => Ok(KeyType::Ecdsa),
+            _ => Err(GitSwitchError::Other(format!(
+                "Unknown key type: {}. Supported: rsa, ed25519, ecdsa",
+                s
+            ))),
+        }
+    }
+}
+
 pub fn generate_ssh_key(identity_file_path: &Path) -> Result<()> {
+    generate_ssh_key_of_type(identity_file_path, KeyType::Rsa)
+}
+
+/// Generate a passphrase-less key pair, leaving an existing key untouched
+pub fn generate_ssh_key_of_type(identity_file_path: &Path, key_type: KeyType) -> Result<()> {
     if identity_file_path.exists() {
         return Ok(());
     }
 
     ensure_parent_dir_exists(identity_file_path)?;
 
+    let mut args = key_type.keygen_args().to_vec();
+    args.extend([
+        "-f",
+        identity_file_path
+            .to_str()
+            .ok_or_else(|| GitSwitchError::PathExpansion {
+                path: format!("{:?}", identity_file_path),
+            })?,
+        "-N",
+        "",   // No passphrase
+        "-q", // Quiet mode
+    ]);
+
     // Generate SSH key quietly
     run_command_with_output(
         "ssh-keygen",
-        &[
-            "-t",
-            "rsa",
-            "-b",
-            "4096",
-            "-f",
-            identity_file_path
-                .to_str()
-                .ok_or_else(|| GitSwitchError::PathExpansion {
-                    path: format!("{:?}", identity_file_path),
-                })?,
-            "-N",
-            "",   // No passphrase
-            "-q", // Quiet mode
-        ],
+        &args,
         None, // No specific current_dir needed
     )
-    .map_err(|e| GitSwitchError::SshKeyGeneration {
-        message: format!(
-            "Failed to generate SSH key at {}: {}",
-            identity_file_path.display(),
-            e
-        ),
+    .map_err(|e| match e {
+        GitSwitchError::OpenSshNotFound { .. } => e,
+        e => GitSwitchError::SshKeyGeneration {
+            message: format!(
+                "Failed to generate SSH key at {}: {}",
+                identity_file_path.display(),
+                e
+            ),
+        },
     })?;
 
     Ok(())
 }
 
+/// The public half of a key pair: the private key path with `.pub` appended, so
+/// that `id.work` pairs with `id.work.pub` rather than `id.pub`
+fn public_key_path(identity_file_path: &Path) -> PathBuf {
+    let mut public_key_path = identity_file_path.as_os_str().to_owned();
+    public_key_path.push(".pub");
+    PathBuf::from(public_key_path)
+}
+
+/// Get the SHA256 fingerprint of a key pair via `ssh-keygen -lf`.
+///
+/// Returns None when the public key is missing or ssh-keygen is unavailable,
+/// since the fingerprint is informational only.
+pub fn get_key_fingerprint(identity_file_path: &Path) -> Option<String> {
+    let public_key_path = public_key_path(identity_file_path);
+    if !public_key_path.exists() {
+        return None;
+    }
+
+    let output = match run_command_with_full_output(
+        "ssh-keygen",
+        &["-l", "-E", "sha256", "-f", public_key_path.to_str()?],
+        None,
+    ) {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            tracing::debug!(
+                "ssh-keygen could not fingerprint {}: {}",
+                public_key_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Err(e) => {
+            tracing::debug!("ssh-keygen not available for fingerprinting: {}", e);
+            return None;
+        }
+    };
+
+    // Output looks like: "4096 SHA256:abc... comment (RSA)"
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find(|part| part.starts_with("SHA256:"))
+        .map(|fingerprint| fingerprint.to_string())
+}
+
+/// Read the public key matching a private key path
+pub fn read_public_key(identity_file_path: &Path) -> Result<String> {
+    let public_key_path = public_key_path(identity_file_path);
+    if !public_key_path.exists() {
+        return Err(GitSwitchError::SshKeyGeneration {
+            message: format!(
+                "Public key file not found at: {}",
+                public_key_path.display()
+            ),
+        });
+    }
+    Ok(read_file_content(&public_key_path)?.trim().to_string())
+}
+
 pub fn display_public_key_formatted(identity_file_path: &Path) -> Result<()> {
-    let public_key_path = identity_file_path.with_extension("pub");
+    let public_key_path = public_key_path(identity_file_path);
     if !public_key_path.exists() {
         return Err(GitSwitchError::SshKeyGeneration {
             message: format!(
@@ -112,21 +271,46 @@ pub fn display_public_key_formatted(identity_file_path: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn update_ssh_config(account_name: &str, identity_file_path_str: &str) -> Result<()> {
-    let identity_file_path = expand_path(identity_file_path_str)?; // Expand tilde
-    let config_path = get_ssh_config_file_path()?;
+/// SSH config host alias for an account slug (see `Account::slug`)
+pub fn host_alias(slug: &str) -> String {
+    format!("github.com-{}", slug)
+}
+
+/// Whether `content` has a `Host` line for exactly `alias`
+pub fn has_host_entry(content: &str, alias: &str) -> bool {
+    let host_line = format!("Host {}", alias);
+    content.lines().any(|line| line.trim() == host_line)
+}
+
+pub fn update_ssh_config(account: &Account, include_mode: bool) -> Result<()> {
+    let identity_file_path = expand_path(&account.ssh_key_path)?; // Expand tilde
+    if include_mode {
+        ensure_ssh_include()?;
+    }
+    let config_path = managed_ssh_config_path(include_mode)?;
     ensure_parent_dir_exists(&config_path)?;
 
     // Use a more specific host alias to avoid potential conflicts and ensure clarity
-    let host_alias = format!(
-        "github.com-{}",
-        account_name.replace(" ", "_").to_lowercase()
-    );
+    let host_alias = host_alias(&account.slug());
     let identity_file_display = identity_file_path.to_str().unwrap_or("INVALID_PATH");
 
+    // ssh offers the keys in order, so the primary comes first and additional
+    // keys keep working during a rotation
+    let mut identity_files = format!("  IdentityFile {}\n", identity_file_display);
+    for key in &account.additional_ssh_keys {
+        let key_path = expand_path(key)?;
+        identity_files.push_str(&format!(
+            "  IdentityFile {}\n",
+            key_path.to_str().unwrap_or("INVALID_PATH")
+        ));
+    }
+
     let config_entry = format!(
-        "\n# {} GitHub Account (git-switch managed)\nHost {}\n  HostName github.com\n  User git\n  IdentityFile {}\n  IdentitiesOnly yes\n",
-        account_name, host_alias, identity_file_display
+        "\n# {} GitHub Account (git-switch managed)\nHost {}\n  HostName {}\n  User git\n{}  IdentitiesOnly yes\n",
+        account.name,
+        host_alias,
+        account.ssh_hostname(),
+        identity_files
     );
 
     let mut current_config = if config_path.exists() {
@@ -136,7 +320,7 @@ pub fn update_ssh_config(account_name: &str, identity_file_path_str: &str) -> Re
     };
 
     // Prevent duplicate entries
-    if current_config.contains(&format!("Host {}", host_alias)) {
+    if has_host_entry(&current_config, &host_alias) {
         return Ok(());
     }
 
@@ -146,7 +330,15 @@ pub fn update_ssh_config(account_name: &str, identity_file_path_str: &str) -> Re
     Ok(())
 }
 
-pub fn add_ssh_key(key_path_str: &str) -> Result<bool> {
+/// Add a key to the SSH agent, optionally expiring it after `lifetime` seconds.
+///
+/// A `passphrase` is answered non-interactively by running git-switch itself as
+/// `SSH_ASKPASS` (see the `secrets` module for the security model).
+pub fn add_ssh_key(
+    key_path_str: &str,
+    lifetime: Option<u64>,
+    passphrase: Option<&str>,
+) -> Result<bool> {
     let expanded_key_path = expand_path(key_path_str)?;
 
     if !expanded_key_path.exists() {
@@ -169,8 +361,20 @@ pub fn add_ssh_key(key_path_str: &str) -> Result<bool> {
         "🔑 Adding SSH key to agent: {}",
         expanded_key_path.display()
     );
-    match run_command("ssh-add", &[key_path_arg], None) {
+    let lifetime_arg = lifetime.map(|seconds| seconds.to_string());
+    let mut args = Vec::new();
+    if let Some(seconds) = lifetime_arg.as_deref() {
+        args.extend(["-t", seconds]);
+    }
+    args.push(key_path_arg);
+
+    if let Some(passphrase) = passphrase {
+        return add_ssh_key_with_passphrase(&args, passphrase, &expanded_key_path);
+    }
+
+    match run_command("ssh-add", &args, None) {
         Ok(_) => Ok(true), // Assume success means it's added or already there and usable.
+        Err(e @ GitSwitchError::OpenSshNotFound { .. }) => Err(e),
         Err(e) => {
             let error_msg = e.to_string();
             // Check if it's because the agent is not running
@@ -199,30 +403,118 @@ pub fn add_ssh_key(key_path_str: &str) -> Result<bool> {
     }
 }
 
-pub fn remove_ssh_config_entry(account_name: &str) -> Result<()> {
-    let config_path = get_ssh_config_file_path()?;
-    if !config_path.exists() {
+fn add_ssh_key_with_passphrase(
+    args: &[&str],
+    passphrase: &str,
+    expanded_key_path: &Path,
+) -> Result<bool> {
+    let askpass = std::env::current_exe()?;
+    let askpass = askpass
+        .to_str()
+        .ok_or_else(|| GitSwitchError::PathExpansion {
+            path: format!("{:?}", askpass),
+        })?;
+    // Older OpenSSH only consults SSH_ASKPASS when DISPLAY is set
+    let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+
+    let output = run_command_with_env(
+        "ssh-add",
+        args,
+        &[
+            ("SSH_ASKPASS", askpass),
+            ("SSH_ASKPASS_REQUIRE", "force"),
+            ("DISPLAY", &display),
+            (crate::secrets::ASKPASS_SECRET_ENV, passphrase),
+        ],
+    )?;
+    if output.status.success() {
+        return Ok(true);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let reason = if stderr.is_empty() {
+        "the passphrase from the secret command was rejected".to_string()
+    } else {
+        stderr
+    };
+    Err(GitSwitchError::SshCommand {
+        command: "ssh-add".to_string(),
+        message: format!(
+            "Failed to add key {}: {}",
+            expanded_key_path.display(),
+            reason
+        ),
+    })
+}
+
+/// Remove the account's managed block from both the main SSH config and
+/// git-switch's own file, whichever `ssh_include_mode` wrote it to
+pub fn remove_ssh_config_entry(account: &Account) -> Result<()> {
+    if strip_ssh_config_entries(account)? {
         println!(
-            "ℹ️ SSH config file not found at {}. Nothing to remove.",
-            config_path.display()
+            "✅ SSH config entry for account \'{}\' removed.",
+            account.name
         );
-        return Ok(());
+    } else {
+        println!(
+            "ℹ️ No SSH config entry found for account \'{}\' to remove.",
+            account.name
+        );
+    }
+    Ok(())
+}
+
+/// Point an account's SSH config entry at its current identity file, dropping
+/// any block left in the file the other include mode writes to
+pub fn replace_ssh_config_entry(account: &Account, include_mode: bool) -> Result<()> {
+    strip_ssh_config_entries(account)?;
+    update_ssh_config(account, include_mode)
+}
+
+/// Strip the account's managed block from every file git-switch may have
+/// written it to; returns whether any block was found
+fn strip_ssh_config_entries(account: &Account) -> Result<bool> {
+    let mut removed = false;
+    for include_mode in [false, true] {
+        let config_path = managed_ssh_config_path(include_mode)?;
+        if !config_path.exists() {
+            continue;
+        }
+        let original_content = read_file_content(&config_path)?;
+        let (new_content, removed_lines) = split_ssh_config_entry(&original_content, account);
+        if !removed_lines.is_empty() {
+            write_file_content(&config_path, &new_content)?;
+            removed = true;
+        }
+    }
+    Ok(removed)
+}
+
+/// The account's managed block in the SSH config, if there is one
+pub fn ssh_config_entry(account: &Account, include_mode: bool) -> Result<Option<String>> {
+    let config_path = managed_ssh_config_path(include_mode)?;
+    if !config_path.exists() {
+        return Ok(None);
     }
+    let removed = split_ssh_config_entry(&read_file_content(&config_path)?, account).1;
+    Ok((!removed.is_empty()).then(|| removed.join("\n")))
+}
 
-    let original_content = read_file_content(&config_path)?;
+/// Split SSH config content into the lines kept and the lines of the account's block
+fn split_ssh_config_entry(original_content: &str, account: &Account) -> (String, Vec<String>) {
     let mut new_content_lines = Vec::new();
+    let mut removed_lines = Vec::new();
     let mut in_matching_block = false;
-    // Ensure the host_marker matches the one used in update_ssh_config
-    let host_marker = format!(
-        "Host github.com-{}",
-        account_name.replace(" ", "_").to_lowercase()
-    );
-    let comment_marker = format!("# {} GitHub Account (git-switch managed)", account_name);
+    // Ensure the host_marker matches the one used in update_ssh_config; compared exactly
+    // so that `github.com-work` does not also match `github.com-work_2`
+    let host_marker = format!("Host {}", host_alias(&account.slug()));
+    let comment_marker = format!("# {} GitHub Account (git-switch managed)", account.name);
 
     for line in original_content.lines() {
-        if line.trim() == comment_marker || line.trim().starts_with(&host_marker) {
+        if line.trim() == comment_marker || line.trim() == host_marker {
             in_matching_block = true;
             // Skip this line and subsequent lines of the block
+            removed_lines.push(line.to_string());
         } else if in_matching_block
             && (line.trim().starts_with("Host ") || line.trim().starts_with("# "))
         {
@@ -231,6 +523,8 @@ pub fn remove_ssh_config_entry(account_name: &str) -> Result<()> {
             new_content_lines.push(line.to_string());
         } else if !in_matching_block {
             new_content_lines.push(line.to_string());
+        } else if !line.trim().is_empty() {
+            removed_lines.push(line.to_string());
         }
         // If in_matching_block is true and it's not a new Host line, the line is part of the block to remove, so we do nothing.
     }
@@ -238,20 +532,5 @@ pub fn remove_ssh_config_entry(account_name: &str) -> Result<()> {
     // Edge case: if the block to remove was at the very end of the file
     // in_matching_block might still be true here. The logic should handle it.
 
-    let new_content = new_content_lines.join("\n");
-
-    if new_content.trim() == original_content.trim() {
-        println!(
-            "ℹ️ No SSH config entry found for account \'{}\' to remove.",
-            account_name
-        );
-    } else {
-        write_file_content(&config_path, &new_content)?;
-        println!(
-            "✅ SSH config entry for account \'{}\' removed.",
-            account_name
-        );
-    }
-
-    Ok(())
+    (new_content_lines.join("\n"), removed_lines)
 }
@@ -0,0 +1,68 @@
+use crate::config::get_config_file_path;
+use crate::error::{GitSwitchError, Result};
+use crate::utils::{read_file_content, write_file_content};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// State that persists between commands, such as the globally active profile
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct State {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<ActiveProfile>,
+}
+
+/// A profile applied with `profile use --global`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActiveProfile {
+    pub name: String,
+    pub account: String,
+    pub activated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Get state file path (next to the config file)
+pub fn get_state_file_path() -> Result<PathBuf> {
+    let config_path = get_config_file_path()?;
+    let config_dir = config_path
+        .parent()
+        .ok_or_else(|| GitSwitchError::Other("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join(".git-switch-state.toml"))
+}
+
+/// Load persisted state
+pub fn load_state() -> Result<State> {
+    let path = get_state_file_path()?;
+    if !path.exists() {
+        return Ok(State::default());
+    }
+
+    let content = read_file_content(&path)?;
+    let state = toml::from_str(&content).map_err(GitSwitchError::Toml)?;
+    Ok(state)
+}
+
+/// Save persisted state
+pub fn save_state(state: &State) -> Result<()> {
+    let path = get_state_file_path()?;
+    let content = toml::to_string_pretty(state).map_err(GitSwitchError::TomlSer)?;
+    write_file_content(&path, &content)
+}
+
+/// Record the profile (and its account) now active globally
+pub fn set_active_profile(name: &str, account: &str) -> Result<()> {
+    let mut state = load_state()?;
+    state.active_profile = Some(ActiveProfile {
+        name: name.to_string(),
+        account: account.to_string(),
+        activated_at: chrono::Utc::now(),
+    });
+    save_state(&state)
+}
+
+/// Forget the globally active profile, if any
+pub fn clear_active_profile() -> Result<()> {
+    let mut state = load_state()?;
+    if state.active_profile.take().is_some() {
+        save_state(&state)?;
+    }
+    Ok(())
+}
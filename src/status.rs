@@ -0,0 +1,216 @@
+//! The identity dashboard behind `git-switch status`.
+//!
+//! `collect_status` gathers the global and repository identities, the account
+//! the remote suggests and the recommended actions; the human and JSON
+//! renderers only format what it returns. Unlike `doctor`, which checks the
+//! environment, this is about which identity is in use right now.
+
+use crate::commands;
+use crate::config::{self, Config};
+use crate::detection;
+use crate::doctor::{self, CheckStatus};
+use crate::error::{GitSwitchError, Result};
+use crate::git;
+use colored::*;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A `user.name`/`user.email` pair and the account its email belongs to
+#[derive(Debug, Clone, Serialize)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: String,
+    pub account: Option<String>,
+}
+
+/// The current repository's identity and the account its remote suggests
+#[derive(Debug, Clone, Serialize)]
+pub struct RepositoryStatus {
+    pub root: PathBuf,
+    /// `None` when the repository inherits the global identity
+    pub local: Option<Identity>,
+    /// Redacted `origin` URL
+    pub remote: Option<String>,
+    pub detected_account: Option<String>,
+    /// Whether the identity in use belongs to the detected account; `None` when
+    /// nothing was detected
+    pub matches_detected: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    pub global: Option<Identity>,
+    pub repository: Option<RepositoryStatus>,
+    pub accounts: usize,
+    pub actions: Vec<String>,
+}
+
+fn identity(config: &Config, name: Result<String>, email: Result<String>) -> Option<Identity> {
+    let email = email.ok().filter(|email| !email.is_empty())?;
+    Some(Identity {
+        name: name.ok().filter(|name| !name.is_empty()),
+        account: commands::account_for_email(config, &email).map(|account| account.name.clone()),
+        email,
+    })
+}
+
+fn repository_status(config: &Config, global: Option<&Identity>) -> Result<RepositoryStatus> {
+    let local = identity(
+        config,
+        git::get_local_config_key("user.name"),
+        git::get_local_config_key("user.email"),
+    );
+    let detected_account = detection::detect_account_from_remote(config)?;
+    let in_use = local.as_ref().or(global).and_then(|i| i.account.as_ref());
+    let matches_detected = detected_account
+        .as_ref()
+        .map(|detected| in_use == Some(detected));
+    Ok(RepositoryStatus {
+        root: git::get_repo_root()?,
+        local,
+        remote: git::get_remote_url("origin")
+            .ok()
+            .map(|url| git::redact_url(&url)),
+        detected_account,
+        matches_detected,
+    })
+}
+
+/// What the user should do next, most pressing first
+fn recommended_actions(config: &Config, report: &StatusReport) -> Vec<String> {
+    let mut actions = Vec::new();
+    if config.accounts.is_empty() {
+        actions.push("add an account: git-switch add <name> <username> <email>".to_string());
+    }
+
+    if let Some(repo) = &report.repository
+        && let (Some(detected), Some(false)) = (&repo.detected_account, repo.matches_detected)
+    {
+        actions.push(format!(
+            "apply the {} account to this repository: git-switch account {}",
+            detected, detected
+        ));
+    }
+
+    match &report.global {
+        None if !config.accounts.is_empty() => {
+            actions.push("set a global identity: git-switch use <account>".to_string());
+        }
+        Some(global) if global.account.is_none() => actions.push(format!(
+            "global email {} matches no account: git-switch use <account>",
+            global.email
+        )),
+        _ => {}
+    }
+
+    if config::migration_pending(config) {
+        actions.push("upgrade the config: git-switch migrate --apply".to_string());
+    }
+    for check in doctor::account_checks_for(config) {
+        if check.status != CheckStatus::Ok {
+            actions.push(format!("fix account {}: {}", check.target, check.message));
+        }
+    }
+    actions
+}
+
+/// Gather the identity context of the current directory
+pub fn collect_status(config: &Config) -> Result<StatusReport> {
+    let global = identity(
+        config,
+        git::get_global_config_key("user.name"),
+        git::get_global_config_key("user.email"),
+    );
+    let repository = if git::is_in_git_repository()? {
+        Some(repository_status(config, global.as_ref())?)
+    } else {
+        None
+    };
+
+    let mut report = StatusReport {
+        global,
+        repository,
+        accounts: config.accounts.len(),
+        actions: Vec::new(),
+    };
+    report.actions = recommended_actions(config, &report);
+    Ok(report)
+}
+
+fn format_identity(identity: &Identity) -> String {
+    let label = match &identity.name {
+        Some(name) => format!("{} <{}>", name, identity.email),
+        None => format!("<{}>", identity.email),
+    };
+    match &identity.account {
+        Some(account) => format!("{} {}", label, format!("(account: {})", account).green()),
+        None => format!("{} {}", label, "(no matching account)".yellow()),
+    }
+}
+
+/// Print the status dashboard, or the report as JSON
+pub fn run_status(config: &Config, json: bool) -> Result<()> {
+    let report = collect_status(config)?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(GitSwitchError::Json)?
+        );
+        return Ok(());
+    }
+
+    println!("{}", "📊 GitSwitch Status".bold().cyan());
+    println!("{}", "─".repeat(30));
+
+    match &report.global {
+        Some(global) => println!("🌍 Global: {}", format_identity(global)),
+        None => println!("🌍 Global: {}", "not set".yellow()),
+    }
+
+    match &report.repository {
+        Some(repo) => {
+            println!(
+                "📂 Repository: {}",
+                repo.root.display().to_string().bright_white()
+            );
+            match &repo.local {
+                Some(local) => println!("   Local: {}", format_identity(local)),
+                None => println!(
+                    "   Local: {}",
+                    "not set (uses the global identity)".dimmed()
+                ),
+            }
+            if let Some(remote) = &repo.remote {
+                println!("   Remote: {}", remote);
+            }
+            match (&repo.detected_account, repo.matches_detected) {
+                (Some(detected), Some(true)) => {
+                    println!(
+                        "   Detected account: {} {}",
+                        detected.cyan(),
+                        "✓ in use".green()
+                    )
+                }
+                (Some(detected), _) => println!(
+                    "   Detected account: {} {}",
+                    detected.cyan(),
+                    "⚠ not in use".yellow()
+                ),
+                (None, _) => println!("   Detected account: {}", "none".dimmed()),
+            }
+        }
+        None => println!("📂 Repository: {}", "not in a Git repository".dimmed()),
+    }
+
+    println!("👥 Accounts: {}", report.accounts);
+
+    if report.actions.is_empty() {
+        println!("\n{} Nothing to do", "✓".green().bold());
+    } else {
+        println!("\n{}", "💡 Recommended actions:".bold());
+        for action in &report.actions {
+            println!("  • {}", action);
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,3 @@
-use crate::config::Account;
 use crate::error::{GitSwitchError, Result};
 use std::collections::HashMap;
 
@@ -8,6 +7,10 @@ pub struct AccountTemplate {
     pub provider: String,
     pub ssh_test_host: String,
     pub ssh_key_upload_url: String,
+    /// Page that opens the provider's "new SSH key" form directly
+    pub ssh_key_new_url: String,
+    /// Whether `ssh_key_new_url` fills in the key title from a `title` query parameter
+    pub prefills_key_title: bool,
     pub default_ssh_key_name: String,
 }
 
@@ -21,6 +24,8 @@ pub fn get_templates() -> HashMap<String, AccountTemplate> {
             provider: "github".to_string(),
             ssh_test_host: "git@github.com".to_string(),
             ssh_key_upload_url: "https://github.com/settings/keys".to_string(),
+            ssh_key_new_url: "https://github.com/settings/ssh/new".to_string(),
+            prefills_key_title: true,
             default_ssh_key_name: "id_rsa_github".to_string(),
         },
     );
@@ -31,6 +36,8 @@ pub fn get_templates() -> HashMap<String, AccountTemplate> {
             provider: "gitlab".to_string(),
             ssh_test_host: "git@gitlab.com".to_string(),
             ssh_key_upload_url: "https://gitlab.com/-/profile/keys".to_string(),
+            ssh_key_new_url: "https://gitlab.com/-/user_settings/ssh_keys".to_string(),
+            prefills_key_title: true,
             default_ssh_key_name: "id_rsa_gitlab".to_string(),
         },
     );
@@ -41,6 +48,8 @@ pub fn get_templates() -> HashMap<String, AccountTemplate> {
             provider: "bitbucket".to_string(),
             ssh_test_host: "git@bitbucket.org".to_string(),
             ssh_key_upload_url: "https://bitbucket.org/account/settings/ssh-keys/".to_string(),
+            ssh_key_new_url: "https://bitbucket.org/account/settings/ssh-keys/".to_string(),
+            prefills_key_title: false,
             default_ssh_key_name: "id_rsa_bitbucket".to_string(),
         },
     );
@@ -51,6 +60,8 @@ pub fn get_templates() -> HashMap<String, AccountTemplate> {
             provider: "azure".to_string(),
             ssh_test_host: "git@ssh.dev.azure.com".to_string(),
             ssh_key_upload_url: "https://dev.azure.com/_usersSettings/keys".to_string(),
+            ssh_key_new_url: "https://dev.azure.com/_usersSettings/keys".to_string(),
+            prefills_key_title: false,
             default_ssh_key_name: "id_rsa_azure".to_string(),
         },
     );
@@ -58,24 +69,6 @@ pub fn get_templates() -> HashMap<String, AccountTemplate> {
     templates
 }
 
-/// Create account from template
-pub fn create_account_from_template(
-    name: &str,
-    username: &str,
-    email: &str,
-    template: &AccountTemplate,
-) -> Account {
-    Account {
-        name: name.to_string(),
-        username: username.to_string(),
-        email: email.to_string(),
-        ssh_key_path: format!("~/.ssh/{}", template.default_ssh_key_name),
-        additional_ssh_keys: Vec::new(),
-        provider: Some(template.provider.clone()),
-        groups: Vec::new(),
-    }
-}
-
 /// Get template by name
 pub fn get_template(name: &str) -> Result<AccountTemplate> {
     let templates = get_templates();
@@ -85,6 +78,50 @@ pub fn get_template(name: &str) -> Result<AccountTemplate> {
         .ok_or_else(|| GitSwitchError::Other(format!("Unknown template: {}", name)))
 }
 
+/// SSH host name of a provider, e.g. `gitlab.com`
+pub fn provider_host(provider: &str) -> Option<String> {
+    get_templates().remove(provider).map(|template| {
+        template
+            .ssh_test_host
+            .trim_start_matches("git@")
+            .to_string()
+    })
+}
+
+/// Suggested title for an account's key on the provider, e.g. `git-switch-work-laptop`
+pub fn key_title(account_name: &str) -> String {
+    let title = match crate::utils::machine_name() {
+        Some(host) => format!("git-switch-{}-{}", account_name, host),
+        None => format!("git-switch-{}", account_name),
+    };
+    // Keep the title safe to pass as a query parameter without encoding
+    title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Link to the provider's "new SSH key" page for an account, with the key
+/// title filled in where the provider supports it
+pub fn key_upload_link(provider: &str, account_name: &str) -> Option<String> {
+    let template = get_templates().remove(provider)?;
+    Some(if template.prefills_key_title {
+        format!(
+            "{}?title={}",
+            template.ssh_key_new_url,
+            key_title(account_name)
+        )
+    } else {
+        template.ssh_key_new_url
+    })
+}
+
 /// List available templates
 pub fn list_templates() {
     let templates = get_templates();
@@ -1,13 +1,82 @@
 use crate::error::{GitSwitchError, Result};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget};
 use std::fs;
-use std::io;
+use std::io::{self, IsTerminal, Read};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// Environment variable overriding the subprocess timeout, in seconds (0 disables it)
+pub const COMMAND_TIMEOUT_ENV: &str = "GIT_SWITCH_COMMAND_TIMEOUT";
+
+/// Default time a git/ssh subprocess may run before it is killed
+const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 60;
+
+/// Environment variable holding the progress mode resolved from `--progress` and `show_progress`
+pub const PROGRESS_ENV: &str = "GIT_SWITCH_PROGRESS";
+
+/// When spinners and progress bars are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    Always,
+    Never,
+    /// Only when stdout is a terminal
+    Auto,
+}
+
+impl ProgressMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgressMode::Always => "always",
+            ProgressMode::Never => "never",
+            ProgressMode::Auto => "auto",
+        }
+    }
+}
+
+impl std::str::FromStr for ProgressMode {
+    type Err = GitSwitchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "always" => Ok(ProgressMode::Always),
+            "never" => Ok(ProgressMode::Never),
+            "auto" => Ok(ProgressMode::Auto),
+            _ => Err(GitSwitchError::Other(format!(
+                "Unknown progress mode: {}. Supported: always, never, auto",
+                s
+            ))),
+        }
+    }
+}
+
+/// Resolves the user's home directory from HOME (USERPROFILE on Windows).
+///
+/// Unlike `home::home_dir`, this does not fall back to the passwd entry, so a
+/// deliberately unset HOME surfaces as `HomeDirectoryNotFound` instead of
+/// silently writing to another directory.
+pub fn home_dir() -> Result<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    match std::env::var_os(var) {
+        Some(value) if !value.is_empty() => Ok(PathBuf::from(value)),
+        _ => Err(GitSwitchError::HomeDirectoryNotFound),
+    }
+}
+
+/// This machine's host name (`HOSTNAME`/`COMPUTERNAME`, else /etc/hostname), if known
+pub fn machine_name() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+}
 
 /// Expands a path that may start with '~' to an absolute path.
 pub fn expand_path(path_str: &str) -> Result<PathBuf> {
     if let Some(rest) = path_str.strip_prefix('~') {
-        if let Some(home_dir) = home::home_dir() {
+        if let Ok(home_dir) = home_dir() {
             let mut path = home_dir;
             if path_str.len() > 1 {
                 // Handles "~/" or "~something"
@@ -32,15 +101,15 @@ pub fn expand_path(path_str: &str) -> Result<PathBuf> {
 /// Ensures that the directory for the given path exists, creating it if necessary.
 /// This function checks the parent directory of the provided path.
 pub fn ensure_parent_dir_exists(path: &Path) -> Result<()> {
-    if let Some(parent_dir) = path.parent() {
-        if !parent_dir.exists() {
-            fs::create_dir_all(parent_dir).map_err(|e| {
-                GitSwitchError::Io(io::Error::new(
-                    e.kind(),
-                    format!("Failed to create directory {}: {}", parent_dir.display(), e),
-                ))
-            })?;
-        }
+    if let Some(parent_dir) = path.parent()
+        && !parent_dir.exists()
+    {
+        fs::create_dir_all(parent_dir).map_err(|e| {
+            GitSwitchError::Io(io::Error::new(
+                e.kind(),
+                format!("Failed to create directory {}: {}", parent_dir.display(), e),
+            ))
+        })?;
     }
     Ok(())
 }
@@ -55,6 +124,48 @@ pub fn read_file_content(path: &Path) -> Result<String> {
     })
 }
 
+/// Reads a hand-editable document (config, backup, import file), dropping a
+/// UTF-8 BOM and CRLF line endings left by Windows editors or `core.autocrlf`.
+pub fn read_document(path: &Path) -> Result<String> {
+    let content = read_file_content(path)?;
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    Ok(content.replace("\r\n", "\n"))
+}
+
+/// Describe a TOML parse failure by file, line and the text of that line
+pub fn toml_parse_error(path: &Path, content: &str, error: &toml::de::Error) -> String {
+    let line = error
+        .span()
+        .and_then(|span| content.get(..span.start))
+        .map(|before| before.matches('\n').count() + 1);
+    describe_parse_error(path, content, line, error.message())
+}
+
+/// Describe a JSON parse failure by file, line and the text of that line
+pub fn json_parse_error(path: &Path, content: &str, error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    // The line is quoted separately, so drop serde_json's own position suffix
+    let message = message
+        .rsplit_once(" at line ")
+        .map_or(message.as_str(), |(message, _)| message);
+    describe_parse_error(path, content, Some(error.line()), message)
+}
+
+fn describe_parse_error(path: &Path, content: &str, line: Option<usize>, message: &str) -> String {
+    let message = message.trim().replace('\n', "; ");
+    let quoted = line.and_then(|n| Some((n, content.lines().nth(n.checked_sub(1)?)?)));
+    match quoted {
+        Some((n, text)) => format!(
+            "{} line {}: {}\n    {}",
+            path.display(),
+            n,
+            message,
+            text.trim_end()
+        ),
+        None => format!("{}: {}", path.display(), message),
+    }
+}
+
 /// Writes string content to a file.
 pub fn write_file_content(path: &Path, content: &str) -> Result<()> {
     ensure_parent_dir_exists(path)?;
@@ -66,7 +177,215 @@ pub fn write_file_content(path: &Path, content: &str) -> Result<()> {
     })
 }
 
-/// Runs a command and waits for it to complete, returning its status.
+/// Writes string content through a temporary file renamed into place, so a
+/// crash never leaves a half-written file behind.
+pub fn write_file_atomic(path: &Path, content: &str) -> Result<()> {
+    let temp_path = PathBuf::from(format!("{}.{}.tmp", path.display(), std::process::id()));
+    write_file_content(&temp_path, content)?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        GitSwitchError::Io(io::Error::new(
+            e.kind(),
+            format!("Failed to replace file {}: {}", path.display(), e),
+        ))
+    })
+}
+
+/// Takes an exclusive lock on `<path>.lock`, waiting for other processes to
+/// release it; the lock is held until the returned file is dropped.
+pub fn lock_file(path: &Path) -> Result<fs::File> {
+    let lock_path = PathBuf::from(format!("{}.lock", path.display()));
+    ensure_parent_dir_exists(&lock_path)?;
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// Copies text to the system clipboard.
+///
+/// Returns whether the contents outlive this process. On Linux (X11) the
+/// process itself serves them, so they are gone once it exits unless a clipboard
+/// manager takes them over; callers should print the text as well.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(text: &str) -> std::result::Result<bool, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text.to_string()))
+        .map(|()| !cfg!(target_os = "linux"))
+        .map_err(|e| e.to_string())
+}
+
+/// Clipboard support is compiled out; callers print the text instead.
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_text: &str) -> std::result::Result<bool, String> {
+    Err("built without the clipboard feature".to_string())
+}
+
+/// Opens a URL in the browser: `$BROWSER` when set, otherwise the platform's
+/// opener. Fails on Linux without a graphical session.
+#[cfg(feature = "browser")]
+pub fn open_url(url: &str) -> std::result::Result<(), String> {
+    let output = match std::env::var("BROWSER")
+        .ok()
+        .filter(|b| !b.trim().is_empty())
+    {
+        Some(browser) => run_shell_command(&format!("{} {}", browser, shell_quote(url))),
+        None if cfg!(target_os = "macos") => run_command_with_full_output("open", &[url], None),
+        None if cfg!(windows) => {
+            run_command_with_full_output("cmd", &["/C", "start", "", url], None)
+        }
+        None => {
+            if std::env::var_os("DISPLAY").is_none()
+                && std::env::var_os("WAYLAND_DISPLAY").is_none()
+            {
+                return Err("no graphical session".to_string());
+            }
+            run_command_with_full_output("xdg-open", &[url], None)
+        }
+    };
+    match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!("browser command exited with {}", output.status)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Browser support is compiled out; callers print the URL instead.
+#[cfg(not(feature = "browser"))]
+pub fn open_url(_url: &str) -> std::result::Result<(), String> {
+    Err("built without the browser feature".to_string())
+}
+
+/// Timeout applied to subprocesses, or None when disabled.
+fn command_timeout() -> Option<Duration> {
+    let secs = std::env::var(COMMAND_TIMEOUT_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// OpenSSH programs git-switch shells out to
+const OPENSSH_PROGRAMS: &[&str] = &["ssh", "ssh-add", "ssh-keygen"];
+
+/// Error for a command that could not be started, singling out missing OpenSSH tools
+fn spawn_error(command_str: &str, context: &str, e: io::Error) -> GitSwitchError {
+    if e.kind() == io::ErrorKind::NotFound && OPENSSH_PROGRAMS.contains(&command_str) {
+        return GitSwitchError::OpenSshNotFound {
+            program: command_str.to_string(),
+        };
+    }
+    GitSwitchError::CommandExecution {
+        command: command_str.to_string(),
+        message: format!("{}: {}", context, e),
+    }
+}
+
+/// Whether `program` can be found on PATH
+pub fn program_on_path(program: &str) -> bool {
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    let names: Vec<String> = if cfg!(windows) {
+        vec![format!("{}.exe", program), program.to_string()]
+    } else {
+        vec![program.to_string()]
+    };
+    std::env::split_paths(&paths).any(|dir| names.iter().any(|name| dir.join(name).is_file()))
+}
+
+/// Fail early with `OpenSshNotFound` when an OpenSSH tool is missing from PATH
+pub fn require_openssh_program(program: &str) -> Result<()> {
+    if program_on_path(program) {
+        Ok(())
+    } else {
+        Err(GitSwitchError::OpenSshNotFound {
+            program: program.to_string(),
+        })
+    }
+}
+
+/// Waits for a child process, killing it once the command timeout expires.
+///
+/// A child that shares a terminal stdin may be waiting on the user (`ssh-add`
+/// asking for a passphrase), so `interactive` children are never timed out.
+fn wait_with_timeout(
+    child: &mut Child,
+    command_str: &str,
+    args: &[&str],
+    interactive: bool,
+) -> Result<ExitStatus> {
+    let wait_error = |e: io::Error| GitSwitchError::CommandExecution {
+        command: command_str.to_string(),
+        message: format!("Failed to wait for command: {}", e),
+    };
+
+    let Some(timeout) = command_timeout().filter(|_| !interactive) else {
+        return child.wait().map_err(wait_error);
+    };
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait().map_err(wait_error)? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(GitSwitchError::CommandExecution {
+                command: command_str.to_string(),
+                message: format!(
+                    "Command with args '{}' timed out after {}s",
+                    args.join(" "),
+                    timeout.as_secs()
+                ),
+            });
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Collects a command's output like `Command::output`, subject to the command timeout.
+fn output_with_timeout(cmd: &mut Command, command_str: &str, args: &[&str]) -> Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| spawn_error(command_str, "Failed to spawn command for output", e))?;
+
+    // Drain both pipes concurrently so a chatty child cannot block on a full pipe
+    let mut stdout_pipe = child.stdout.take();
+    let mut stderr_pipe = child.stderr.take();
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(pipe) = stdout_pipe.as_mut() {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    });
+
+    // On timeout the readers are left detached: a grandchild may still hold the pipes
+    let status = wait_with_timeout(&mut child, command_str, args, false)?;
+    Ok(Output {
+        status,
+        stdout: stdout_reader.join().unwrap_or_default(),
+        stderr: stderr_reader.join().unwrap_or_default(),
+    })
+}
+
+/// Runs a command and waits for it to complete, returning its status. The
+/// timeout only applies when stdin is not a terminal.
 pub fn run_command(command_str: &str, args: &[&str], current_dir: Option<&Path>) -> Result<()> {
     let mut cmd = Command::new(command_str);
     cmd.args(args);
@@ -74,10 +393,11 @@ pub fn run_command(command_str: &str, args: &[&str], current_dir: Option<&Path>)
         cmd.current_dir(dir);
     }
 
-    let status = cmd.status().map_err(|e| GitSwitchError::CommandExecution {
-        command: command_str.to_string(),
-        message: format!("Failed to spawn command: {}", e),
-    })?;
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| spawn_error(command_str, "Failed to spawn command", e))?;
+    // stdin is inherited, so the command may prompt the user
+    let status = wait_with_timeout(&mut child, command_str, args, io::stdin().is_terminal())?;
 
     if !status.success() {
         return Err(GitSwitchError::CommandExecution {
@@ -92,6 +412,17 @@ pub fn run_command(command_str: &str, args: &[&str], current_dir: Option<&Path>)
     Ok(())
 }
 
+/// Runs a command with extra environment variables and no stdin, returning its output.
+pub fn run_command_with_env(
+    command_str: &str,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<Output> {
+    let mut cmd = Command::new(command_str);
+    cmd.args(args).envs(envs.iter().copied());
+    output_with_timeout(&mut cmd, command_str, args)
+}
+
 /// Runs a command and returns its output (stdout, stderr, status).
 #[allow(dead_code)]
 pub fn run_command_with_output(
@@ -105,10 +436,7 @@ pub fn run_command_with_output(
         cmd.current_dir(dir);
     }
 
-    let output = cmd.output().map_err(|e| GitSwitchError::CommandExecution {
-        command: command_str.to_string(),
-        message: format!("Failed to spawn command for output: {}", e),
-    })?;
+    let output = output_with_timeout(&mut cmd, command_str, args)?;
 
     if !output.status.success() {
         return Err(GitSwitchError::CommandExecution {
@@ -136,8 +464,58 @@ pub fn run_command_with_full_output(
         cmd.current_dir(dir);
     }
 
-    cmd.output().map_err(|e| GitSwitchError::CommandExecution {
-        command: command_str.to_string(),
-        message: format!("Failed to spawn command for full output: {}", e),
-    })
+    output_with_timeout(&mut cmd, command_str, args)
+}
+
+/// Runs a command line through the platform shell (`sh -c`, or `cmd /C` on Windows)
+pub fn run_shell_command(command: &str) -> Result<Output> {
+    if cfg!(windows) {
+        run_command_with_full_output("cmd", &["/C", command], None)
+    } else {
+        run_command_with_full_output("sh", &["-c", command], None)
+    }
+}
+
+/// Quote a value so it reaches a shell command as a single argument
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Progress mode for this run (auto unless `--progress` or the config decided)
+fn progress_mode() -> ProgressMode {
+    std::env::var(PROGRESS_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(ProgressMode::Auto)
+}
+
+fn progress_draw_target() -> ProgressDrawTarget {
+    match progress_mode() {
+        ProgressMode::Never => ProgressDrawTarget::hidden(),
+        // A TermLike target draws even when stderr is not a terminal
+        ProgressMode::Always => ProgressDrawTarget::term_like(Box::new(console::Term::stderr())),
+        ProgressMode::Auto if io::stdout().is_terminal() => ProgressDrawTarget::stderr(),
+        ProgressMode::Auto => ProgressDrawTarget::hidden(),
+    }
+}
+
+/// Progress bar of `len` steps; every bar goes through here so `--progress` applies to it
+pub fn progress_bar(len: u64) -> ProgressBar {
+    ProgressBar::with_draw_target(Some(len), progress_draw_target())
+}
+
+/// Multi-line progress display drawn under the same rules as `progress_bar`; None
+/// when nothing would be drawn, so callers can fall back to a single spinner
+pub fn multi_progress() -> Option<MultiProgress> {
+    let target = progress_draw_target();
+    (!target.is_hidden()).then(|| MultiProgress::with_draw_target(target))
+}
+
+/// Spinner for work of unknown length, drawn under the same rules as `progress_bar`
+pub fn progress_spinner() -> ProgressBar {
+    ProgressBar::with_draw_target(None, progress_draw_target())
 }
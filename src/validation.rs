@@ -1,4 +1,5 @@
 use crate::error::{GitSwitchError, Result};
+use crate::utils;
 use std::path::Path;
 use std::process::Command;
 
@@ -13,6 +14,84 @@ pub fn validate_email(email: &str) -> Result<()> {
     }
 }
 
+/// Validate an email domain such as "company.com"
+pub fn validate_email_domain(domain: &str) -> Result<()> {
+    if domain.contains('.') && email_address::EmailAddress::is_valid(&format!("user@{}", domain)) {
+        Ok(())
+    } else {
+        Err(GitSwitchError::Other(format!(
+            "Invalid email domain: '{}'",
+            domain
+        )))
+    }
+}
+
+/// Validate an SSH host name such as `github.example.com`
+pub fn validate_ssh_host(host: &str) -> Result<()> {
+    let valid = !host.is_empty()
+        && !host.starts_with(['-', '.'])
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(GitSwitchError::Other(format!(
+            "Invalid host: '{}'",
+            host.escape_default()
+        )))
+    }
+}
+
+/// Validate a path setting such as an account's commit template or hooks path
+pub fn validate_path_setting(label: &str, path: &str) -> Result<()> {
+    if path.trim().is_empty() || path.chars().any(char::is_control) {
+        return Err(GitSwitchError::Other(format!(
+            "Invalid {}: '{}'",
+            label,
+            path.escape_default()
+        )));
+    }
+    Ok(())
+}
+
+/// Git settings an account may apply to repositories. Anything else is rejected so
+/// that a config file cannot inject arbitrary configuration such as `core.sshCommand`
+pub const ALLOWED_GIT_SETTINGS: &[&str] = &[
+    "push.default",
+    "push.autoSetupRemote",
+    "push.followTags",
+    "pull.rebase",
+    "pull.ff",
+    "fetch.prune",
+    "fetch.pruneTags",
+    "rebase.autoStash",
+    "merge.ff",
+];
+
+/// Validate a per-account Git setting, returning the key as spelled in the allowlist
+/// (Git keys are case-insensitive)
+pub fn validate_git_setting(key: &str, value: &str) -> Result<&'static str> {
+    let key = ALLOWED_GIT_SETTINGS
+        .iter()
+        .find(|allowed| allowed.eq_ignore_ascii_case(key.trim()))
+        .ok_or_else(|| {
+            GitSwitchError::Other(format!(
+                "Unsupported Git setting: {}. Supported: {}",
+                key,
+                ALLOWED_GIT_SETTINGS.join(", ")
+            ))
+        })?;
+    if value.trim().is_empty() || value.chars().any(char::is_control) {
+        return Err(GitSwitchError::Other(format!(
+            "Invalid value for {}: '{}'",
+            key,
+            value.escape_default()
+        )));
+    }
+    Ok(key)
+}
+
 /// Validate SSH key format and permissions
 pub fn validate_ssh_key(key_path: &Path) -> Result<()> {
     if !key_path.exists() {
@@ -99,8 +178,7 @@ pub fn validate_ssh_key_comprehensive(key_path: &Path) -> Result<()> {
 }
 
 /// Validate SSH private key content format
-#[allow(dead_code)]
-fn validate_ssh_private_key_content(content: &str) -> Result<()> {
+pub fn validate_ssh_private_key_content(content: &str) -> Result<()> {
     let content = content.trim();
 
     // Check for OpenSSH format (preferred)
@@ -142,7 +220,6 @@ fn validate_ssh_private_key_content(content: &str) -> Result<()> {
 }
 
 /// Validate OpenSSH format private key
-#[allow(dead_code)]
 fn validate_openssh_private_key(content: &str) -> Result<()> {
     let lines: Vec<&str> = content.lines().collect();
 
@@ -165,20 +242,18 @@ fn validate_openssh_private_key(content: &str) -> Result<()> {
         });
     }
 
-    // Validate base64 content
-    for (i, line) in lines.iter().enumerate().skip(1).take(lines.len() - 2) {
-        if !is_valid_base64(line) {
-            return Err(GitSwitchError::InvalidSshKey {
-                message: format!("Invalid base64 content at line {}", i + 1),
-            });
-        }
+    // Validate base64 content; lines are wrapped at 70 characters, so decode them joined
+    let body: String = lines[1..lines.len() - 1].concat();
+    if !is_valid_base64(&body) {
+        return Err(GitSwitchError::InvalidSshKey {
+            message: "Invalid base64 content in OpenSSH private key".to_string(),
+        });
     }
 
     Ok(())
 }
 
 /// Validate traditional format private key
-#[allow(dead_code)]
 fn validate_traditional_private_key(content: &str, begin: &str, end: &str) -> Result<()> {
     let lines: Vec<&str> = content.lines().collect();
 
@@ -201,31 +276,31 @@ fn validate_traditional_private_key(content: &str, begin: &str, end: &str) -> Re
         });
     }
 
-    // Validate base64 content
-    for (i, line) in lines.iter().enumerate().skip(1).take(lines.len() - 2) {
-        if !line.starts_with("Proc-Type:")
-            && !line.starts_with("DEK-Info:")
-            && !is_valid_base64(line)
-        {
-            return Err(GitSwitchError::InvalidSshKey {
-                message: format!("Invalid content at line {}", i + 1),
-            });
-        }
+    // Validate base64 content, skipping the encryption headers and the blank line after them
+    let body: String = lines[1..lines.len() - 1]
+        .iter()
+        .filter(|line| {
+            !line.starts_with("Proc-Type:") && !line.starts_with("DEK-Info:") && !line.is_empty()
+        })
+        .copied()
+        .collect();
+    if !is_valid_base64(&body) {
+        return Err(GitSwitchError::InvalidSshKey {
+            message: "Invalid base64 content in private key".to_string(),
+        });
     }
 
     Ok(())
 }
 
 /// Validate SSH public key file
-#[allow(dead_code)]
-fn validate_ssh_public_key_file(pub_key_path: &Path) -> Result<()> {
+pub fn validate_ssh_public_key_file(pub_key_path: &Path) -> Result<()> {
     let content = std::fs::read_to_string(pub_key_path).map_err(GitSwitchError::Io)?;
 
     validate_ssh_public_key_content(&content)
 }
 
 /// Validate SSH public key content
-#[allow(dead_code)]
 fn validate_ssh_public_key_content(content: &str) -> Result<()> {
     let content = content.trim();
     let parts: Vec<&str> = content.split_whitespace().collect();
@@ -270,14 +345,15 @@ fn validate_ssh_public_key_content(content: &str) -> Result<()> {
 }
 
 /// Verify that private and public keys are a matching pair
-#[allow(dead_code)]
-fn verify_ssh_key_pair(private_key_path: &Path, public_key_path: &Path) -> Result<()> {
+pub fn verify_ssh_key_pair(private_key_path: &Path, public_key_path: &Path) -> Result<()> {
     // Use ssh-keygen to generate public key from private key and compare
-    let output = std::process::Command::new("ssh-keygen")
-        .arg("-y")
-        .arg("-f")
-        .arg(private_key_path)
-        .output();
+    let private_key = private_key_path
+        .to_str()
+        .ok_or_else(|| GitSwitchError::PathExpansion {
+            path: format!("{:?}", private_key_path),
+        })?;
+    let output =
+        utils::run_command_with_full_output("ssh-keygen", &["-y", "-f", private_key], None);
 
     match output {
         Ok(result) if result.status.success() => {
@@ -293,8 +369,9 @@ fn verify_ssh_key_pair(private_key_path: &Path, public_key_path: &Path) -> Resul
                 && stored_parts.len() >= 2
                 && (gen_parts[0] != stored_parts[0] || gen_parts[1] != stored_parts[1])
             {
-                return Err(GitSwitchError::InvalidSshKey {
-                    message: "Private and public keys do not match".to_string(),
+                return Err(GitSwitchError::KeyPairMismatch {
+                    private_key: private_key_path.display().to_string(),
+                    public_key: public_key_path.display().to_string(),
                 });
             }
         }
@@ -304,8 +381,11 @@ fn verify_ssh_key_pair(private_key_path: &Path, public_key_path: &Path) -> Resul
                 message: format!("Failed to verify key pair: {}", error),
             });
         }
-        Err(_) => {
-            tracing::warn!("ssh-keygen not available, skipping key pair verification");
+        Err(e @ GitSwitchError::OpenSshNotFound { .. }) => {
+            tracing::warn!("{}; skipping key pair verification", e);
+        }
+        Err(e) => {
+            tracing::warn!("ssh-keygen failed ({}), skipping key pair verification", e);
         }
     }
 
@@ -313,20 +393,18 @@ fn verify_ssh_key_pair(private_key_path: &Path, public_key_path: &Path) -> Resul
 }
 
 /// Validate key strength based on type and size
-#[allow(dead_code)]
 fn validate_key_strength(key_type: &str, key_data: &str) -> Result<()> {
     // Use base64 crate for decoding
     use base64::{Engine as _, engine::general_purpose};
 
     if let Ok(decoded) = general_purpose::STANDARD.decode(key_data) {
         match key_type {
-            "ssh-rsa" => {
+            "ssh-rsa"
                 // RSA keys should be at least 2048 bits
-                if decoded.len() < 256 {
+                if decoded.len() < 256 => {
                     // Rough estimate
                     tracing::warn!("RSA key appears to be less than 2048 bits, consider upgrading");
                 }
-            }
             "ssh-dss" => {
                 tracing::warn!(
                     "DSA keys are deprecated and should be replaced with RSA or Ed25519"
@@ -348,7 +426,6 @@ fn validate_key_strength(key_type: &str, key_data: &str) -> Result<()> {
 }
 
 /// Check if a string is valid base64
-#[allow(dead_code)]
 fn is_valid_base64(s: &str) -> bool {
     if s.is_empty() {
         return false;
@@ -397,6 +474,11 @@ pub fn validate_ssh_agent() -> Result<()> {
                 Ok(())
             }
         }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(GitSwitchError::OpenSshNotFound {
+                program: "ssh-add".to_string(),
+            })
+        }
         Err(_) => Err(GitSwitchError::SshAgentNotRunning),
     }
 }
@@ -445,16 +527,66 @@ pub fn validate_username(username: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validate a provider login such as `jdoe`: a handle without whitespace
+pub fn validate_login(login: &str) -> Result<()> {
+    if login.is_empty() || login.chars().any(char::is_whitespace) || login.len() > 100 {
+        return Err(GitSwitchError::Other(format!(
+            "Invalid login: '{}'. Use the provider handle, e.g. jdoe",
+            login
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a branch name against the common `git check-ref-format` rules
+pub fn validate_branch_name(branch: &str) -> Result<()> {
+    let invalid = branch.is_empty()
+        || branch.starts_with('-')
+        || branch.starts_with('/')
+        || branch.ends_with('/')
+        || branch.ends_with('.')
+        || branch.ends_with(".lock")
+        || branch.contains("..")
+        || branch.contains("@{")
+        || branch.contains("//")
+        || branch
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "~^:?*[\\".contains(c));
+    if invalid {
+        return Err(GitSwitchError::Other(format!(
+            "Invalid branch name: '{}'",
+            branch
+        )));
+    }
+
+    Ok(())
+}
+
 /// Comprehensive startup validation
-pub fn validate_startup() -> Result<()> {
+///
+/// In strict mode a missing SSH agent is an error instead of a warning;
+/// with `check_agent` false the agent is not looked at at all. `quiet`
+/// keeps the non-strict warning off stderr (used for `--json`).
+pub fn validate_startup(strict: bool, check_agent: bool, quiet: bool) -> Result<()> {
     tracing::info!("Performing startup validation...");
 
     validate_git_installation()?;
 
     // SSH agent validation is optional - warn but don't fail
-    if let Err(e) = validate_ssh_agent() {
+    if !check_agent {
+        tracing::info!("SSH agent check skipped");
+    } else if let Err(e) = validate_ssh_agent() {
+        if strict {
+            return Err(e);
+        }
         tracing::warn!("SSH agent validation failed: {}", e);
-        eprintln!("Warning: SSH agent is not running. Some features may not work properly.");
+        if quiet {
+            // The warning is still traced above
+        } else if let GitSwitchError::OpenSshNotFound { .. } = e {
+            eprintln!("Warning: {}", e);
+        } else {
+            eprintln!("Warning: SSH agent is not running. Some features may not work properly.");
+        }
     }
 
     tracing::info!("Startup validation completed successfully");